@@ -0,0 +1,72 @@
+use common::{handlers::ResolveAnomaly, perms::MANAGE_USERS, user::User};
+use leptos::*;
+use leptos_router::ActionForm;
+
+use crate::{i18n::use_locale, loading::Loading, users::user_name_short};
+
+/// Admin list of entries whose revenue was unusual when saved.
+#[component]
+pub fn Anomalies() -> impl IntoView {
+    let locale = use_locale();
+
+    let resolve_anomaly = create_server_action::<ResolveAnomaly>();
+
+    let anomalies = create_resource(
+        move || resolve_anomaly.version().get(),
+        |_| common::handlers::list_anomalies(),
+    );
+
+    let app_user = use_context::<Signal<User>>().unwrap();
+    let can_resolve = Signal::derive(move || app_user().permissions.contains(MANAGE_USERS));
+
+    view! {
+        <Transition fallback=Loading>
+            {move || match anomalies() {
+                Some(Ok(entries)) if entries.is_empty() => view! {
+                    <p class="p-8 text-slate-500">{locale.tr("anomalies-empty")}</p>
+                }.into_view(),
+                Some(Ok(entries)) => view! {
+                    <table class="w-full">
+                        <thead class="border-solid border-b border-slate-500 font-bold text-left">
+                            <tr>
+                                <th class="p-2 pl-8">{locale.tr("field-date")}</th>
+                                <th class="p-2">{locale.tr("field-manager")}</th>
+                                <th class="p-2">{locale.tr("field-address")}</th>
+                                <th class="p-2">{locale.tr("field-revenue")}</th>
+                                <th class="p-2">{locale.tr("anomalies-reason")}</th>
+                                <th class="p-2 pr-8 text-right">
+                                    <i class="fa-solid fa-ellipsis-vertical"></i>
+                                </th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            <For each=move || entries.clone() key=|e| e.id let:entry>
+                                <tr class="border-solid border-b border-slate-500">
+                                    <td class="p-2 pl-8">{entry.date.format("%d.%m.%Y").to_string()}</td>
+                                    <td class="p-2">{user_name_short(&entry.user)}</td>
+                                    <td class="p-2">{entry.address}</td>
+                                    <td class="p-2">{entry.revenue.to_string()}</td>
+                                    <td class="p-2">{locale.text(&entry.anomaly.unwrap_or_default())}</td>
+                                    <td class="p-2 pr-8 text-right">
+                                        <Show when=can_resolve>
+                                            <ActionForm action=resolve_anomaly>
+                                                <input type="hidden" name="id" value=entry.id.to_string()/>
+                                                <button type="submit" title=locale.tr("anomalies-resolve")>
+                                                    <i class="fa-solid fa-check"></i>
+                                                </button>
+                                            </ActionForm>
+                                        </Show>
+                                    </td>
+                                </tr>
+                            </For>
+                        </tbody>
+                    </table>
+                }.into_view(),
+                Some(Err(err)) => view! {
+                    <p class="text-pink-600 pb-2">{locale.error(&err)}</p>
+                }.into_view(),
+                None => view! { <Loading/> }.into_view(),
+            }}
+        </Transition>
+    }
+}
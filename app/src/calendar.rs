@@ -2,7 +2,7 @@ use chrono::NaiveDate;
 use common::Datelike;
 use leptos::*;
 
-use crate::dropdown::Dropdown;
+use crate::{dropdown::Dropdown, i18n::use_locale};
 
 #[component]
 pub fn Calendar(
@@ -10,6 +10,8 @@ pub fn Calendar(
     #[prop(into)] rw_month: RwSignal<Option<u32>>,
     #[prop(into)] options: Signal<Vec<(i32, Vec<u32>)>>,
 ) -> impl IntoView {
+    let locale = use_locale();
+
     let year = rw_year.read_only();
     let (month, set_month) = rw_month.split();
 
@@ -22,22 +24,7 @@ pub fn Calendar(
                     ms.into_iter()
                         .map(|m| {
                             let date = NaiveDate::from_ymd_opt(y, m, 1).unwrap();
-                            let name = match date.month() {
-                                1 => "Январь",
-                                2 => "Февраль",
-                                3 => "Март",
-                                4 => "Апрель",
-                                5 => "Май",
-                                6 => "Июнь",
-                                7 => "Июль",
-                                8 => "Август",
-                                9 => "Сентябрь",
-                                10 => "Октябрь",
-                                11 => "Ноябрь",
-                                12 => "Декабрь",
-                                _ => unreachable!(),
-                            }
-                            .to_string();
+                            let name = locale.tr(&format!("month-{}", date.month()));
                             (m, name)
                         })
                         .collect::<Vec<_>>(),
@@ -81,13 +68,13 @@ pub fn Calendar(
     view! {
         <div class="w-full flex items-stretch bg-slate-50 dark:bg-slate-700">
             <div class="w-1/2 md:w-1/3 lg:w-1/5 shrink-0">
-                <Dropdown name="year" label_text="Год:" options=year_options current_option=rw_year/>
+                <Dropdown name="year" label_text=locale.tr("calendar-year") options=year_options current_option=rw_year/>
             </div>
             <div class="h-full basis-1/2 grow flex flex-col-reverse">
                 <div class="flex flex-wrap items-center basis-8 shrink-0 grow text-xl rounded w-full !bg-transparent !text-inherit dark:!text-inherit border border-slate-500 p-2">
                     {month_options}
                 </div>
-                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-50 dark:bg-slate-700 inline-block">"Месяц:"</span>
+                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-50 dark:bg-slate-700 inline-block">{locale.tr("calendar-month")}</span>
             </div>
         </div>
     }
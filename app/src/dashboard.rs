@@ -1,11 +1,15 @@
 use std::collections::HashSet;
 
-use common::{moneys::Moneys, perms::VIEW_ALL, user::User, IdType};
+use common::{exchange::reporting_totals, i18n::Message, perms::VIEW_ALL, user::User, IdType};
 use leptos::*;
 
 use crate::{
     calendar::Calendar,
+    i18n::use_locale,
+    live::use_live_updates,
     loading::Loading,
+    missing::MissingReports,
+    plans::PlansProgress,
     reports::{ReportsSummary, ReportsTable},
 };
 
@@ -14,6 +18,9 @@ pub fn Dashboard() -> impl IntoView {
     let list_dates = create_server_action::<common::handlers::ListDates>();
     let list_reports = create_server_action::<common::handlers::ListReports>();
 
+    let locale = use_locale();
+    let live_updates = use_live_updates();
+
     let app_user = use_context::<Signal<User>>().unwrap();
     let admin_permissions_guard = Signal::derive(move || app_user().permissions.contains(VIEW_ALL));
 
@@ -26,8 +33,8 @@ pub fn Dashboard() -> impl IntoView {
     };
 
     let dates = create_local_resource(
-        move || (list_dates.version().get(), view_user()),
-        move |(_, view_user)| common::handlers::list_dates(view_user),
+        move || (list_dates.version().get(), live_updates(), view_user()),
+        move |(_, _, view_user)| common::handlers::list_dates(view_user),
     );
 
     let rw_month = create_rw_signal(None);
@@ -51,21 +58,34 @@ pub fn Dashboard() -> impl IntoView {
         move || {
             (
                 list_reports.version().get(),
+                live_updates(),
                 rw_year().unwrap_or_default(),
                 rw_month().unwrap_or_default(),
                 view_user(),
             )
         },
-        move |(_, year, month, user_id)| common::handlers::list_reports(year, month, user_id),
+        move |(_, _, year, month, user_id)| common::handlers::list_reports(year, month, user_id),
     );
 
     let all_reports =
         Signal::derive(move || reports.get().and_then(|r| r.ok()).unwrap_or_default());
 
+    let plans = create_local_resource(
+        move || {
+            (
+                rw_year().unwrap_or_default(),
+                rw_month().unwrap_or_default(),
+            )
+        },
+        move |(year, month)| common::handlers::list_plans(year, month),
+    );
+
+    let all_plans = Signal::derive(move || plans.get().and_then(|r| r.ok()).unwrap_or_default());
+
     let month_revenue = Signal::derive(move || {
         let all = all_reports();
-        let total = Moneys(all.into_iter().fold(0, |acc, e| acc + e.revenue.0));
-        format!("{total}")
+        let totals = reporting_totals(all.into_iter().map(|e| (e.revenue, e.converted)));
+        format!("{totals}")
     });
 
     let entries_count = Signal::derive(move || all_reports().len());
@@ -99,44 +119,25 @@ pub fn Dashboard() -> impl IntoView {
                 <div class="p-10 text-6xl">
                     <span class="inline-flex flex-col">
                         <span class="block">{entries_count}</span>
-                        <small class="block text-lg">{move || plural(entries_count(), "Отчет")}</small>
+                        <small class="block text-lg">{move || locale.message(&Message::new("dashboard-entries").arg("count", entries_count()))}</small>
                     </span>
                     <span>{" /"}</span>
                     <span class="inline-flex flex-col">
                         <span class="block">{users_count}</span>
-                        <small class="block text-lg">{move || plural(users_count(), "Менеджер")}</small>
+                        <small class="block text-lg">{move || locale.message(&Message::new("dashboard-managers").arg("count", users_count()))}</small>
                     </span>
                 </div>
                 <div class="p-10 text-6xl text-wrap break-words">
                     <span>{month_revenue}</span>
                 </div>
             </div>
+            <MissingReports/>
+            <PlansProgress plans=all_plans reports=all_reports/>
             <Show when=admin_permissions_guard fallback={move || {view!{
                 <ReportsTable reports=all_reports />
             }}}>
-                <ReportsSummary reports=all_reports/>
+                <ReportsSummary reports=all_reports plans=all_plans/>
             </Show>
         </Suspense>
     }
 }
-
-fn plural(number: usize, word: &str) -> String {
-    match number.to_string().as_str() {
-        n if n.ends_with('0')
-            || n.ends_with("11")
-            || n.ends_with("12")
-            || n.ends_with("13")
-            || n.ends_with("14")
-            || n.ends_with("15")
-            || n.ends_with("16")
-            || n.ends_with("17")
-            || n.ends_with("18")
-            || n.ends_with("19") =>
-        {
-            format!("{word}ов")
-        }
-        n if n.ends_with('1') => word.to_string(),
-        n if n.ends_with('2') || n.ends_with('3') || n.ends_with('4') => format!("{word}a"),
-        _ => format!("{word}ов"),
-    }
-}
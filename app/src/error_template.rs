@@ -6,16 +6,39 @@ use thiserror::Error;
 #[cfg(feature = "ssr")]
 use leptos_axum::ResponseOptions;
 
+use crate::i18n::use_locale;
+
 #[derive(Clone, Debug, Error)]
 pub enum AppError {
     #[error("Not Found")]
     NotFound,
+    /// A server function the page depends on failed.
+    #[error("{0}")]
+    Server(common::error::AppError),
 }
 
 impl AppError {
     pub fn status_code(&self) -> StatusCode {
         match self {
             AppError::NotFound => StatusCode::NOT_FOUND,
+            AppError::Server(err) => err.status_code(),
+        }
+    }
+
+    /// Catalog message, see [`common::i18n::Message`].
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::NotFound => "err-page-not-found",
+            AppError::Server(err) => err.message(),
+        }
+    }
+}
+
+impl From<&ServerFnError<common::error::AppError>> for AppError {
+    fn from(err: &ServerFnError<common::error::AppError>) -> Self {
+        match err {
+            ServerFnError::WrappedServerError(err) => AppError::Server(err.clone()),
+            _ => AppError::Server(common::error::AppError::Internal),
         }
     }
 }
@@ -27,6 +50,8 @@ pub fn ErrorTemplate(
     #[prop(optional)] outside_errors: Option<Errors>,
     #[prop(optional)] errors: Option<RwSignal<Errors>>,
 ) -> impl IntoView {
+    let locale = use_locale();
+
     let errors = match outside_errors {
         Some(e) => create_rw_signal(e),
         None => match errors {
@@ -37,10 +62,16 @@ pub fn ErrorTemplate(
     // Get Errors from Signal
     let errors = errors.get_untracked();
 
-    // Downcast lets us take a type that implements `std::error::Error`
+    // Downcast lets us take a type that implements `std::error::Error`, server functions
+    // fail with the typed error of `common`
     let errors: Vec<AppError> = errors
         .into_iter()
-        .filter_map(|(_k, v)| v.downcast_ref::<AppError>().cloned())
+        .filter_map(|(_k, v)| {
+            v.downcast_ref::<AppError>().cloned().or_else(|| {
+                v.downcast_ref::<ServerFnError<common::error::AppError>>()
+                    .map(AppError::from)
+            })
+        })
         .collect();
     println!("Errors: {errors:#?}");
 
@@ -48,13 +79,12 @@ pub fn ErrorTemplate(
     // this may be customized by the specific application
     cfg_if! { if #[cfg(feature="ssr")] {
         let response = use_context::<ResponseOptions>();
-        if let Some(response) = response {
-            response.set_status(errors[0].status_code());
+        if let (Some(response), Some(error)) = (response, errors.first()) {
+            response.set_status(error.status_code());
         }
     }}
 
     view! {
-        <h1>{if errors.len() > 1 { "Errors" } else { "Error" }}</h1>
         <For
             // a function that returns the items we're iterating over; a signal is fine
             each=move || { errors.clone().into_iter().enumerate() }
@@ -62,11 +92,12 @@ pub fn ErrorTemplate(
             key=|(index, _error)| *index
             // renders each item to a view
             children=move |error| {
-                let error_string = error.1.to_string();
                 let error_code = error.1.status_code();
                 view! {
-                    <h2>{error_code.to_string()}</h2>
-                    <p>"Error: " {error_string}</p>
+                    <div class="p-8 m-8">
+                        <h2 class="text-2xl mb-4">{error_code.as_u16()}</h2>
+                        <p class="text-pink-600">{locale.error_text(error.1.message())}</p>
+                    </div>
                 }
             }
         />
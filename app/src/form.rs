@@ -0,0 +1,30 @@
+use common::error::AppError;
+use leptos::*;
+
+use crate::i18n::use_locale;
+
+type ActionValue = Option<Result<(), ServerFnError<AppError>>>;
+
+/// Validation error of a form field, shown under the field.
+#[component]
+pub fn FieldError(#[prop(into)] value: Signal<ActionValue>, field: &'static str) -> impl IntoView {
+    let locale = use_locale();
+
+    move || {
+        value.with(|value| match value {
+            Some(Err(ServerFnError::WrappedServerError(err))) if err.field() == Some(field) => {
+                Some(view! {<p class="text-pink-600 -mt-6 pb-4">{locale.text(err.message())}</p>})
+            }
+            _ => None,
+        })
+    }
+}
+
+/// Errors not tied to a field, shown under the form.
+pub fn is_form_error(value: &ActionValue) -> bool {
+    match value {
+        Some(Err(ServerFnError::WrappedServerError(err))) => err.field().is_none(),
+        Some(Err(_)) => true,
+        _ => false,
+    }
+}
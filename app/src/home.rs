@@ -1,14 +1,27 @@
-use common::{perms::VIEW_ALL, user::User};
+use common::{
+    perms::{VIEW_ALL, VIEW_OWNED},
+    user::User,
+};
 use leptos::*;
 use leptos_router::{Outlet, A};
 
-use crate::{error_template::ErrorTemplate, logout::Logout};
+use crate::{
+    error_template::ErrorTemplate,
+    i18n::use_locale,
+    live::provide_live_updates,
+    logout::Logout,
+    offline::{provide_offline_queue, PendingReports},
+    search::GlobalSearch,
+};
 
 #[component]
 pub fn HomePage() -> impl IntoView {
     let logout = create_server_action::<common::handlers::Logout>();
+    let locale = use_locale();
 
     let app_user = use_context::<Signal<User>>().unwrap();
+    provide_offline_queue(app_user);
+    provide_live_updates();
 
     let u_name = move || {
         let u = app_user();
@@ -24,20 +37,37 @@ pub fn HomePage() -> impl IntoView {
     let link_cls = "p-4 border-solid border-b border-slate-500";
     let active_link_cls = "bg-indigo-50 dark:bg-indigo-950 text-indigo-500 pointer-events-none";
 
+    let can_search = move || {
+        let permissions = app_user().permissions;
+        permissions.contains(VIEW_ALL) || permissions.contains(VIEW_OWNED)
+    };
+
     let menu_content = move || {
         if app_user().permissions.contains(VIEW_ALL) {
             view! {
                 <A href="" class=link_cls active_class=active_link_cls>
                     <i class="fa-solid fa-chart-line pr-2"></i>
-                    {"Сводный отчет"}
+                    {locale.tr("menu-summary")}
                 </A>
                 <A href="reports" class=link_cls active_class=active_link_cls>
                 <i class="fa-solid fa-file-invoice pr-2"></i>
-                    {"Индивидуальные отчеты"}
+                    {locale.tr("menu-reports")}
+                </A>
+                <A href="plans" class=link_cls active_class=active_link_cls>
+                    <i class="fa-solid fa-bullseye pr-2"></i>
+                    {locale.tr("menu-plans")}
+                </A>
+                <A href="anomalies" class=link_cls active_class=active_link_cls>
+                    <i class="fa-solid fa-triangle-exclamation pr-2"></i>
+                    {locale.tr("menu-anomalies")}
+                </A>
+                <A href="rates" class=link_cls active_class=active_link_cls>
+                    <i class="fa-solid fa-money-bill-transfer pr-2"></i>
+                    {locale.tr("menu-rates")}
                 </A>
                 <A href="users" class=link_cls active_class=active_link_cls>
                     <i class="fa-solid fa-user-tie pr-2"></i>
-                    {"Менеджеры"}
+                    {locale.tr("menu-users")}
                 </A>
             }
             .into_view()
@@ -45,11 +75,11 @@ pub fn HomePage() -> impl IntoView {
             view! {
                 <A href="" class=link_cls active_class=active_link_cls>
                 <i class="fa-solid fa-chart-line pr-2"></i>
-                    {"Мои отчеты"}
+                    {locale.tr("menu-my-reports")}
                 </A>
                 <A href="reports/new-report" class=link_cls active_class=active_link_cls>
                     <i class="fa-solid fa-file-invoice pr-2"></i>
-                    {"Добавить отчет"}
+                    {locale.tr("menu-new-report")}
                 </A>
             }
             .into_view()
@@ -59,6 +89,9 @@ pub fn HomePage() -> impl IntoView {
     view! {
         <div class="home-grid-layout w-full h-full md:grid-cols-3 lg:grid-cols-5 items-stretch">
             <header class="h-14 md:col-span-3 lg:col-span-5 items-center flex justify-end py-2 px-4 bg-slate-200 dark:bg-slate-800 border-solid border-b-2 border-slate-500">
+                <Show when=can_search>
+                    <GlobalSearch/>
+                </Show>
                 <A href=move || format!("users/{}", app_user().id) class="mx-4" >{u_name}</A>
                 <Logout action=logout/>
             </header>
@@ -68,6 +101,7 @@ pub fn HomePage() -> impl IntoView {
                 </nav>
             </aside>
             <section class="md:col-span-2 lg:col-span-4 row-span-2 overflow-y-auto pb-6">
+                <PendingReports/>
                 <ErrorBoundary fallback=|errors| {
                     view! { <ErrorTemplate errors/> }.into_view()
                 }>
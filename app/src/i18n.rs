@@ -0,0 +1,25 @@
+use common::i18n::Locale;
+use leptos::*;
+
+/// Locale of the page, picked by the server for the request.
+pub fn use_locale() -> Locale {
+    use_context::<Locale>().unwrap_or_default()
+}
+
+/// The server provides the locale as context while rendering, the browser reads it
+/// back from the `lang` attribute the rendered page has.
+pub fn provide_locale() -> Locale {
+    cfg_if::cfg_if! { if #[cfg(feature = "ssr")] {
+        let locale = use_locale();
+    } else {
+        let locale = document()
+            .document_element()
+            .and_then(|html| html.get_attribute("lang"))
+            .and_then(|lang| lang.parse().ok())
+            .unwrap_or_default();
+    }}
+
+    provide_context(locale);
+
+    locale
+}
@@ -1,12 +1,21 @@
+mod anomalies;
 mod calendar;
 mod dashboard;
 mod dropdown;
+mod form;
 mod home;
+mod i18n;
+mod live;
 mod loading;
 mod login;
 mod logout;
+mod missing;
+mod offline;
+mod plans;
+mod rates;
 mod reports;
 mod router;
+mod search;
 mod users;
 
 pub mod error_template;
@@ -20,6 +29,8 @@ use crate::router::AppRouter;
 pub fn App() -> impl IntoView {
     provide_meta_context();
 
+    let locale = i18n::provide_locale();
+
     let login = create_server_action::<common::handlers::Login>();
     let logout = create_server_action::<common::handlers::Logout>();
 
@@ -28,14 +39,23 @@ pub fn App() -> impl IntoView {
         move |_| common::user::get_user(),
     );
 
+    // the page is rendered in the browser's language until the user signs in
+    create_effect(move |_| {
+        if let Some(Ok(Some(user))) = user.get() {
+            if user.locale.is_some_and(|l| l != locale) {
+                _ = window().location().reload();
+            }
+        }
+    });
+
     view! {
-        <Html lang="ru"/>
+        <Html lang=locale.code()/>
 
         <Stylesheet id="reset" href="https://unpkg.com/scss-reset/reset.css"/>
         <Stylesheet id="leptos" href="/pkg/start-axum-workspace.css"/>
         <Script src="https://kit.fontawesome.com/f875badde1.js" crossorigin="anonymous"></Script>
 
-        <Title text="Тестовое задание"/>
+        <Title text=locale.tr("app-title")/>
 
         <Transition fallback=loading::Loading>
             <AppRouter user=user login=login/>
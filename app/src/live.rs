@@ -0,0 +1,72 @@
+use leptos::*;
+
+/// Pause after a change before refetching, so a burst such as an import refetches once.
+#[cfg(feature = "hydrate")]
+const SETTLE: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Bumped whenever entries the user can see change on the server, resources listing
+/// reports track it to refetch.
+#[derive(Clone, Copy)]
+struct LiveUpdates(RwSignal<usize>);
+
+/// Subscribes to the server's entry changes once the page is hydrated.
+pub fn provide_live_updates() {
+    let version = create_rw_signal(0);
+    provide_context(LiveUpdates(version));
+
+    // effects run in the browser only
+    create_effect(move |_| subscribe(version));
+}
+
+pub fn use_live_updates() -> Signal<usize> {
+    use_context::<LiveUpdates>().unwrap().0.into()
+}
+
+/// Listens to `/events` until the page leaves the signed in part of the app.
+#[cfg(feature = "hydrate")]
+fn subscribe(version: RwSignal<usize>) {
+    use leptos::leptos_dom::helpers::TimeoutHandle;
+    use wasm_bindgen::{closure::Closure, JsCast};
+    use web_sys::EventSource;
+
+    let source = match EventSource::new("/events") {
+        Ok(source) => source,
+        Err(err) => {
+            log::warn!("live updates unavailable: {err:?}");
+            return;
+        }
+    };
+
+    let pending = store_value(None::<TimeoutHandle>);
+    let changed = move || {
+        if let Some(handle) = pending.get_value() {
+            handle.clear();
+        }
+        let bump = move || version.update(|v| *v += 1);
+        pending.set_value(set_timeout_with_handle(bump, SETTLE).ok());
+    };
+
+    let on_change = Closure::<dyn Fn()>::new(changed);
+    for event in ["entry", "reload"] {
+        _ = source.add_event_listener_with_callback(event, on_change.as_ref().unchecked_ref());
+    }
+
+    // the browser reconnects by itself, changes made meanwhile weren't sent
+    let mut connected = false;
+    let on_open = Closure::<dyn FnMut()>::new(move || {
+        if connected {
+            changed();
+        }
+        connected = true;
+    });
+    source.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+
+    on_cleanup(move || {
+        source.close();
+        drop(on_change);
+        drop(on_open);
+    });
+}
+
+#[cfg(not(feature = "hydrate"))]
+fn subscribe(_: RwSignal<usize>) {}
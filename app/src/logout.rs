@@ -1,14 +1,18 @@
-use common::handlers::Logout;
+use common::{error::AppError, handlers::Logout};
 use leptos::*;
 use leptos_router::ActionForm;
 
+use crate::i18n::use_locale;
+
 #[component]
-pub fn Logout(action: Action<Logout, Result<(), ServerFnError>>) -> impl IntoView {
+pub fn Logout(action: Action<Logout, Result<(), ServerFnError<AppError>>>) -> impl IntoView {
+    let locale = use_locale();
+
     view! {
         <div id="loginbox">
             <ActionForm action=action>
                 <button type="submit" class="text-lg px-2 py-1 border border-solid border-slate-500 rounded">
-                    "Выйти"
+                    {locale.tr("logout")}
                 </button>
             </ActionForm>
         </div>
@@ -0,0 +1,65 @@
+use common::{i18n::Message, perms::VIEW_ALL, user::User};
+use leptos::*;
+use leptos_router::A;
+
+use crate::{i18n::use_locale, live::use_live_updates, users::user_name_short};
+
+/// Days of the month without reports: every manager's for admins, the user's own otherwise.
+#[component]
+pub fn MissingReports() -> impl IntoView {
+    let locale = use_locale();
+
+    let missing = create_local_resource(use_live_updates(), |_| {
+        common::handlers::list_missing_reports()
+    });
+
+    let app_user = use_context::<Signal<User>>().unwrap();
+    let view_all = Signal::derive(move || app_user().permissions.contains(VIEW_ALL));
+
+    let all_missing =
+        Signal::derive(move || missing.get().and_then(|r| r.ok()).unwrap_or_default());
+
+    let format_dates = |dates: &[chrono::NaiveDate]| {
+        dates
+            .iter()
+            .map(|d| d.format("%d.%m").to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    view! {
+        <Show when=move || all_missing.with(|m| !m.is_empty())>
+            <div class="mx-4 mb-6 p-4 rounded-lg border border-solid border-pink-600">
+                <Show when=view_all fallback=move || view! {
+                    <p>
+                        <i class="fa-solid fa-triangle-exclamation pr-2 text-pink-600"></i>
+                        {move || locale.message(&Message::new("missing-own").arg(
+                            "dates",
+                            all_missing.with(|m| m.iter().map(|m| format_dates(&m.dates)).collect::<Vec<_>>().join(", ")),
+                        ))}
+                    </p>
+                    <A href="reports/new-report" class="text-indigo-500">{locale.tr("missing-add")}</A>
+                }>
+                    <h3 class="text-lg mb-2">
+                        <i class="fa-solid fa-triangle-exclamation pr-2 text-pink-600"></i>
+                        {locale.tr("missing-title")}
+                    </h3>
+                    <ul>
+                        <For each=all_missing key=|m| (m.user.id, m.dates.len()) let:missing>
+                            <li class="py-1">
+                                <A class="text-indigo-500" href=format!("/reports?user_id={}", missing.user.id)>
+                                    {user_name_short(&missing.user)}
+                                </A>
+                                {format!(" — {}", locale.message(
+                                    &Message::new("missing-days")
+                                        .arg("count", missing.dates.len())
+                                        .arg("dates", format_dates(&missing.dates)),
+                                ))}
+                            </li>
+                        </For>
+                    </ul>
+                </Show>
+            </div>
+        </Show>
+    }
+}
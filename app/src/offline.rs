@@ -0,0 +1,383 @@
+use chrono::Utc;
+use common::{
+    error::AppError,
+    i18n::Message,
+    models::{Entry, ReportFields},
+    offline::{QueueState, QueuedReport, SyncOutcome},
+    user::User,
+    IdType,
+};
+use leptos::*;
+
+use crate::i18n::use_locale;
+
+/// Reports filed on the device while the server couldn't be reached, shared by the
+/// report form that queues them and the list that shows them.
+#[derive(Clone, Copy)]
+pub struct OfflineQueue {
+    items: RwSignal<Vec<QueuedReport>>,
+    syncing: RwSignal<bool>,
+    user: Signal<User>,
+}
+
+pub fn use_offline_queue() -> OfflineQueue {
+    use_context::<OfflineQueue>().unwrap()
+}
+
+/// Loads the queue once the page is hydrated and sends it whenever the browser is
+/// back online.
+pub fn provide_offline_queue(user: Signal<User>) -> OfflineQueue {
+    let queue = OfflineQueue {
+        items: create_rw_signal(vec![]),
+        syncing: create_rw_signal(false),
+        user,
+    };
+    provide_context(queue);
+
+    // effects run in the browser only, the server has no queue to load
+    create_effect(move |_| {
+        spawn_local(async move {
+            match store::load().await {
+                Ok(items) => queue.items.set(items),
+                Err(err) => log::warn!("offline queue not loaded: {err:?}"),
+            }
+            queue.sync();
+        });
+    });
+
+    let online = window_event_listener(ev::online, move |_| queue.sync());
+    on_cleanup(move || online.remove());
+
+    queue
+}
+
+/// The server couldn't be reached, the submitted report is queued instead.
+pub fn is_unreachable(value: &Option<Result<(), ServerFnError<AppError>>>) -> bool {
+    matches!(value, Some(Err(ServerFnError::Request(_))))
+}
+
+impl OfflineQueue {
+    /// Reports of the signed in user, others' wait until they sign in on the device.
+    pub fn items(self) -> Signal<Vec<QueuedReport>> {
+        Signal::derive(move || {
+            let user_id = self.user.with(|u| u.id);
+
+            self.items.with(|items| {
+                items
+                    .iter()
+                    .filter(|item| item.user_id == user_id)
+                    .cloned()
+                    .collect()
+            })
+        })
+    }
+
+    pub fn syncing(self) -> Signal<bool> {
+        self.syncing.into()
+    }
+
+    /// Queues a new report, or a change of `entry` as loaded into the form.
+    pub fn push(self, entry: Option<&Entry>, report: ReportFields) {
+        let item = QueuedReport {
+            client_id: store::client_id(),
+            user_id: self.user.with_untracked(|u| u.id),
+            entry_id: entry.map(|e| e.id),
+            base_revision: entry.map(|e| e.revision),
+            report,
+            queued_at: Utc::now(),
+            state: QueueState::Pending,
+        };
+
+        self.items.update(|items| items.push(item.clone()));
+        spawn_local(async move {
+            if let Err(err) = store::put(&item).await {
+                log::warn!("report not queued: {err:?}");
+            }
+        });
+    }
+
+    /// Sends the pending reports one by one, up to the first that doesn't reach the server.
+    pub fn sync(self) {
+        if self.syncing.get_untracked() {
+            return;
+        }
+
+        let pending = self
+            .items()
+            .get_untracked()
+            .into_iter()
+            .filter(QueuedReport::is_pending)
+            .collect::<Vec<_>>();
+        if pending.is_empty() {
+            return;
+        }
+
+        self.syncing.set(true);
+        spawn_local(async move {
+            for mut item in pending {
+                let result = common::handlers::sync_report(
+                    item.client_id,
+                    item.entry_id,
+                    item.base_revision,
+                    item.report.clone(),
+                )
+                .await;
+
+                item.state = match result {
+                    Ok(SyncOutcome::Applied { .. }) => {
+                        self.remove(item.client_id).await;
+                        continue;
+                    }
+                    Ok(SyncOutcome::Conflict(server)) => QueueState::Conflict(server),
+                    // signed out meanwhile, sent after signing in again
+                    Err(ServerFnError::WrappedServerError(AppError::Unauthorized)) => break,
+                    Err(ServerFnError::WrappedServerError(err)) => QueueState::Failed(err),
+                    Err(_) => break,
+                };
+                self.replace(item).await;
+            }
+
+            self.syncing.set(false);
+        });
+    }
+
+    /// Sends the queued version of a conflicting report over the server's one.
+    pub fn keep_mine(self, client_id: IdType) {
+        let Some(mut item) = self.find(client_id) else {
+            return;
+        };
+        item.keep_mine();
+
+        spawn_local(async move {
+            self.replace(item).await;
+            self.sync();
+        });
+    }
+
+    /// Drops a report from the queue, keeping the server's version if there is one.
+    pub fn discard(self, client_id: IdType) {
+        spawn_local(async move { self.remove(client_id).await });
+    }
+
+    fn find(self, client_id: IdType) -> Option<QueuedReport> {
+        self.items.with_untracked(|items| {
+            items
+                .iter()
+                .find(|item| item.client_id == client_id)
+                .cloned()
+        })
+    }
+
+    async fn replace(self, item: QueuedReport) {
+        self.items.update(|items| {
+            if let Some(queued) = items.iter_mut().find(|i| i.client_id == item.client_id) {
+                *queued = item.clone();
+            }
+        });
+
+        if let Err(err) = store::put(&item).await {
+            log::warn!("queued report not updated: {err:?}");
+        }
+    }
+
+    async fn remove(self, client_id: IdType) {
+        self.items
+            .update(|items| items.retain(|item| item.client_id != client_id));
+
+        if let Err(err) = store::delete(client_id).await {
+            log::warn!("queued report not removed: {err:?}");
+        }
+    }
+}
+
+/// Reports waiting on the device, with a choice of version for the conflicting ones.
+#[component]
+pub fn PendingReports() -> impl IntoView {
+    let locale = use_locale();
+    let queue = use_offline_queue();
+    let items = queue.items();
+    let syncing = queue.syncing();
+
+    let button_cls = "px-3 py-1 border border-slate-500 rounded";
+
+    let item_view = move |item: QueuedReport| {
+        let client_id = item.client_id;
+        let revenue = item
+            .revenue()
+            .map(|r| r.to_string())
+            .unwrap_or_else(|| "—".to_string());
+        let summary = locale.message(
+            &Message::new("offline-report")
+                .arg("address", item.report.address.clone())
+                .arg("date", item.report.date.format("%d.%m.%Y").to_string())
+                .arg("revenue", revenue),
+        );
+
+        let state = match item.state {
+            QueueState::Pending => view! {
+                <span class="text-slate-500">
+                    {move || locale.tr(if syncing() { "offline-syncing" } else { "offline-pending" })}
+                </span>
+            }
+            .into_view(),
+            QueueState::Conflict(server) => view! {
+                <span class="text-amber-600">
+                    {locale.message(&Message::new("offline-conflict").arg("revenue", server.revenue.to_string()))}
+                </span>
+                <button type="button" class=button_cls on:click=move |_| queue.keep_mine(client_id)>
+                    {locale.tr("action-keep-mine")}
+                </button>
+                <button type="button" class=button_cls on:click=move |_| queue.discard(client_id)>
+                    {locale.tr("action-keep-server")}
+                </button>
+            }
+            .into_view(),
+            QueueState::Failed(err) => view! {
+                <span class="text-pink-600">
+                    {locale.message(&Message::new("offline-failed").arg("error", locale.text(err.message())))}
+                </span>
+                <button type="button" class=button_cls on:click=move |_| queue.discard(client_id)>
+                    {locale.tr("action-delete")}
+                </button>
+            }
+            .into_view(),
+        };
+
+        view! {
+            <li class="py-2 flex flex-wrap items-center gap-4">
+                <span>{summary}</span>
+                {state}
+            </li>
+        }
+    };
+
+    view! {
+        <Show when=move || items.with(|items| !items.is_empty())>
+            <div class="p-4 mx-8 mt-8 rounded-lg border border-amber-500">
+                <h2 class="text-lg flex items-center">
+                    {locale.tr("offline-title")}
+                    <button
+                        type="button"
+                        disabled=syncing
+                        on:click=move |_| queue.sync()
+                        class=format!("ml-auto {button_cls}")
+                    >
+                        {locale.tr("action-sync")}
+                    </button>
+                </h2>
+                <ul>{move || items().into_iter().map(item_view).collect_view()}</ul>
+            </div>
+        </Show>
+    }
+}
+
+/// Queue persisted in IndexedDB, as JSON keyed by the client id.
+#[cfg(feature = "hydrate")]
+mod store {
+    use common::{offline::QueuedReport, IdType};
+    use js_sys::{Array, Promise};
+    use leptos::window;
+    use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::{IdbDatabase, IdbObjectStore, IdbRequest, IdbTransactionMode};
+
+    const DATABASE: &str = "reports-offline";
+    const QUEUE: &str = "queue";
+
+    /// Random v4 UUID, `crypto.randomUUID()` is missing outside of secure contexts.
+    pub fn client_id() -> IdType {
+        let mut bytes = [0; 16];
+        if let Ok(crypto) = window().crypto() {
+            _ = crypto.get_random_values_with_u8_array(&mut bytes);
+        }
+
+        uuid::Builder::from_random_bytes(bytes).into_uuid()
+    }
+
+    pub async fn load() -> Result<Vec<QueuedReport>, JsValue> {
+        let items = done(&queue(IdbTransactionMode::Readonly).await?.get_all()?).await?;
+
+        Ok(Array::from(&items)
+            .iter()
+            .filter_map(|item| serde_json::from_str(&item.as_string()?).ok())
+            .collect())
+    }
+
+    pub async fn put(item: &QueuedReport) -> Result<(), JsValue> {
+        let json = serde_json::to_string(item).map_err(|err| err.to_string())?;
+        let key = JsValue::from(item.client_id.to_string());
+
+        done(
+            &queue(IdbTransactionMode::Readwrite)
+                .await?
+                .put_with_key(&json.into(), &key)?,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(client_id: IdType) -> Result<(), JsValue> {
+        let key = JsValue::from(client_id.to_string());
+
+        done(&queue(IdbTransactionMode::Readwrite).await?.delete(&key)?).await?;
+
+        Ok(())
+    }
+
+    async fn queue(mode: IdbTransactionMode) -> Result<IdbObjectStore, JsValue> {
+        let factory = window()
+            .indexed_db()?
+            .ok_or_else(|| JsValue::from("IndexedDB unavailable"))?;
+        let request = factory.open_with_u32(DATABASE, 1)?;
+
+        let opened = request.clone();
+        let on_upgrade = Closure::<dyn FnMut()>::new(move || {
+            if let Ok(db) = opened.result() {
+                _ = db
+                    .unchecked_into::<IdbDatabase>()
+                    .create_object_store(QUEUE);
+            }
+        });
+        request.set_onupgradeneeded(Some(on_upgrade.as_ref().unchecked_ref()));
+
+        let db = done(&request).await?.unchecked_into::<IdbDatabase>();
+        request.set_onupgradeneeded(None);
+
+        db.transaction_with_str_and_mode(QUEUE, mode)?
+            .object_store(QUEUE)
+    }
+
+    /// Waits for the request to succeed and returns its result.
+    async fn done(request: &IdbRequest) -> Result<JsValue, JsValue> {
+        let promise = Promise::new(&mut |resolve, reject| {
+            request.set_onsuccess(Some(&resolve));
+            request.set_onerror(Some(&reject));
+        });
+        JsFuture::from(promise).await?;
+
+        request.result()
+    }
+}
+
+/// The server renders no queue, it only exists in the browser.
+#[cfg(not(feature = "hydrate"))]
+mod store {
+    use common::{offline::QueuedReport, IdType};
+
+    pub fn client_id() -> IdType {
+        IdType::nil()
+    }
+
+    pub async fn load() -> Result<Vec<QueuedReport>, ()> {
+        Ok(vec![])
+    }
+
+    pub async fn put(_: &QueuedReport) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub async fn delete(_: IdType) -> Result<(), ()> {
+        Ok(())
+    }
+}
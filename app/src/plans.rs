@@ -0,0 +1,223 @@
+use chrono::{Datelike, NaiveDate, Utc};
+use common::{
+    handlers::{DeletePlan, SavePlan},
+    i18n::Message,
+    models::{EntryWithUser, Plan, PlanScope},
+    moneys::Currency,
+    perms::MANAGE_USERS,
+    tz,
+    user::User,
+};
+use leptos::*;
+use leptos_router::ActionForm;
+
+use crate::{i18n::use_locale, loading::Loading, users::user_name_short};
+
+/// Admin page to set monthly targets of managers and locations.
+#[component]
+pub fn Plans() -> impl IntoView {
+    let locale = use_locale();
+
+    let save_plan = create_server_action::<SavePlan>();
+    let delete_plan = create_server_action::<DeletePlan>();
+
+    let app_user = use_context::<Signal<User>>().unwrap();
+    let can_edit = Signal::derive(move || app_user().permissions.contains(MANAGE_USERS));
+
+    let today = tz::today(tz::resolve(None, app_user.get_untracked().timezone));
+    let rw_year = create_rw_signal(today.year());
+    let rw_month = create_rw_signal(today.month());
+
+    let plans = create_resource(
+        move || {
+            (
+                save_plan.version().get(),
+                delete_plan.version().get(),
+                rw_year(),
+                rw_month(),
+            )
+        },
+        move |(_, _, year, month)| common::handlers::list_plans(year, month),
+    );
+    let managers = create_resource(|| (), |_| common::handlers::list_users(true));
+    let locations = create_resource(|| (), |_| common::handlers::list_locations());
+
+    let manager_name = move |plan: &Plan| match &plan.scope {
+        PlanScope::Manager(user_id) => managers
+            .get()
+            .and_then(|r| r.ok())
+            .and_then(|users| users.into_iter().find(|u| u.id == *user_id))
+            .map(|u| user_name_short(&u))
+            .unwrap_or_else(|| locale.tr("field-manager")),
+        PlanScope::Location { address, .. } => address.clone(),
+    };
+
+    let save_value = save_plan.value();
+
+    view! {
+        <div class="w-full flex flex-wrap items-center gap-4 bg-slate-50 dark:bg-slate-700 pt-4 pb-2 px-6">
+            <label class="text-lg">
+                {locale.tr("plans-month")}
+                " "
+                <input
+                    type="month"
+                    prop:value=move || format!("{:04}-{:02}", rw_year(), rw_month())
+                    on:change=move |ev| {
+                        if let Ok(date) = NaiveDate::parse_from_str(&format!("{}-01", event_target_value(&ev)), "%Y-%m-%d") {
+                            rw_year.set(date.year());
+                            rw_month.set(date.month());
+                        }
+                    }
+                    class="bg-transparent border border-slate-500 rounded px-2"/>
+            </label>
+        </div>
+        <Show when=can_edit>
+            <ActionForm action=save_plan class="w-full flex flex-wrap items-center gap-4 px-6 py-4">
+                <input type="hidden" name="year" prop:value=move || rw_year().to_string()/>
+                <input type="hidden" name="month" prop:value=move || rw_month().to_string()/>
+                <select name="scope" required class="text-lg bg-transparent border border-slate-500 rounded p-1">
+                    <option value="">{locale.tr("plans-scope")}</option>
+                    <optgroup label=locale.tr("plans-managers")>
+                        {move || managers.get().and_then(|r| r.ok()).unwrap_or_default().into_iter().map(|u| view! {
+                            <option value=format!("manager:{}", u.id)>{user_name_short(&u)}</option>
+                        }).collect_view()}
+                    </optgroup>
+                    <optgroup label=locale.tr("plans-locations")>
+                        {move || locations.get().and_then(|r| r.ok()).unwrap_or_default().into_iter().map(|l| view! {
+                            <option value=format!("location:{}", l.id)>{l.address}</option>
+                        }).collect_view()}
+                    </optgroup>
+                </select>
+                <input
+                    type="text"
+                    inputmode="decimal"
+                    name="target"
+                    placeholder=locale.tr("plans-target-placeholder")
+                    required
+                    class="text-lg bg-transparent border border-slate-500 rounded p-1"/>
+                <select name="currency" class="text-lg bg-transparent border border-slate-500 rounded p-1">
+                    <option value="">{locale.tr("plans-currency-default")}</option>
+                    {Currency::ALL.into_iter().map(|currency| view! {
+                        <option value=currency.code()>{currency.code()}</option>
+                    }).collect_view()}
+                </select>
+                <button type="submit" class="bg-indigo-100 dark:bg-indigo-800 text-lg px-2 py-1 border border-solid border-slate-500 rounded">
+                    {locale.tr("action-save")}
+                </button>
+            </ActionForm>
+            {move || match save_value() {
+                Some(Err(err)) => Some(view! {<p class="px-6 pb-2 text-pink-600">{locale.error(&err)}</p>}),
+                _ => None,
+            }}
+        </Show>
+        <Transition fallback=Loading>
+            {move || match plans() {
+                Some(Ok(plans)) => view! {
+                    <table class="w-full">
+                        <thead class="border-solid border-b border-slate-500 font-bold text-left">
+                            <tr>
+                                <th class="p-2 pl-8">{locale.tr("plans-scope")}</th>
+                                <th class="p-2">{locale.tr("field-plan")}</th>
+                                <th class="p-2 pr-8 text-right">
+                                    <i class="fa-solid fa-ellipsis-vertical"></i>
+                                </th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            <For each=move || plans.clone() key=|p| (p.id, p.target.amount, p.target.currency) let:plan>
+                                <tr class="border-solid border-b border-slate-500">
+                                    <td class="p-2 pl-8">{manager_name(&plan)}</td>
+                                    <td class="p-2">{plan.target.to_string()}</td>
+                                    <td class="p-2 pr-8 text-right">
+                                        <Show when=can_edit>
+                                            <ActionForm action=delete_plan>
+                                                <input type="hidden" name="id" value=plan.id.to_string()/>
+                                                <button type="submit" title=locale.tr("action-delete")>
+                                                    <i class="fa-solid fa-trash"></i>
+                                                </button>
+                                            </ActionForm>
+                                        </Show>
+                                    </td>
+                                </tr>
+                            </For>
+                        </tbody>
+                    </table>
+                }.into_view(),
+                Some(Err(err)) => view! {
+                    <p class="text-pink-600 pb-2">{locale.error(&err)}</p>
+                }.into_view(),
+                None => view! { <Loading/> }.into_view(),
+            }}
+        </Transition>
+    }
+}
+
+/// Percent of plan, remaining amount and projection of every plan of the month.
+#[component]
+pub fn PlansProgress(
+    #[prop(into)] plans: Signal<Vec<Plan>>,
+    #[prop(into)] reports: Signal<Vec<EntryWithUser>>,
+) -> impl IntoView {
+    let locale = use_locale();
+
+    let app_user = use_context::<Signal<User>>().unwrap();
+
+    let label = move |plan: &Plan| match &plan.scope {
+        PlanScope::Manager(user_id) if *user_id == app_user().id => locale.tr("plans-own"),
+        PlanScope::Manager(user_id) => reports.with(|reports| {
+            reports
+                .iter()
+                .find(|r| r.user.id == *user_id)
+                .map(|r| user_name_short(&r.user))
+                .unwrap_or_else(|| locale.tr("field-manager"))
+        }),
+        PlanScope::Location { address, .. } => address.clone(),
+    };
+
+    view! {
+        <Show when=move || plans.with(|p| !p.is_empty())>
+            <div class="w-full grid md:grid-cols-2 lg:grid-cols-3 gap-4 px-4 pb-6">
+                <For each=plans key=|p| (p.id, p.target.amount, p.target.currency) let:plan>
+                    {
+                        let progress = {
+                            let plan = plan.clone();
+                            move || reports.with(|reports| plan.progress(reports, Utc::now()))
+                        };
+
+                        view! {
+                            <div class="p-4 rounded-lg bg-slate-200 dark:bg-slate-800">
+                                <p class="text-lg truncate">{label(&plan)}</p>
+                                <p class="text-4xl py-2">{
+                                    let progress = progress.clone();
+                                    move || format!("{:.0}%", progress().percent)
+                                }</p>
+                                <p>{locale.message(&Message::new("plans-target").arg("amount", plan.target.to_string()))}</p>
+                                <p>{
+                                    let progress = progress.clone();
+                                    move || locale.message(&Message::new("plans-actual").arg("amount", progress().actual.to_string()))
+                                }</p>
+                                <p>{
+                                    let progress = progress.clone();
+                                    move || locale.message(&Message::new("plans-remaining").arg("amount", progress().remaining.to_string()))
+                                }</p>
+                                <p>{
+                                    let progress = progress.clone();
+                                    move || match progress().projection {
+                                        Some(projection) => locale.message(&Message::new("plans-projection").arg("amount", projection.to_string())),
+                                        None => locale.tr("plans-projection-none"),
+                                    }
+                                }</p>
+                                {move || {
+                                    let unconverted = progress().unconverted;
+                                    (unconverted > 0).then(|| view! {
+                                        <p class="text-sm text-slate-500">{locale.message(&Message::new("plans-unconverted").arg("amount", unconverted))}</p>
+                                    })
+                                }}
+                            </div>
+                        }
+                    }
+                </For>
+            </div>
+        </Show>
+    }
+}
@@ -0,0 +1,95 @@
+use common::{handlers::ListExchangeRates, i18n::Message, perms::MANAGE_USERS, user::User};
+use leptos::*;
+use leptos_router::use_query_map;
+
+use crate::{i18n::use_locale, loading::Loading};
+
+#[component]
+pub fn ExchangeRates() -> impl IntoView {
+    let locale = use_locale();
+
+    let list_rates = create_server_action::<ListExchangeRates>();
+
+    let rates = create_resource(
+        move || list_rates.version().get(),
+        move |_| common::handlers::list_exchange_rates(),
+    );
+
+    let query = use_query_map();
+    let imported = move || query.with(|q| q.get("imported").cloned());
+    let upload_error = move || query.with(|q| q.get("error").cloned());
+
+    let app_user = use_context::<Signal<User>>().unwrap();
+    let can_upload = Signal::derive(move || app_user().permissions.contains(MANAGE_USERS));
+
+    view! {
+        <Show when=can_upload>
+            <form
+                method="post"
+                action="/rates/upload"
+                enctype="multipart/form-data"
+                class="w-full flex flex-wrap items-center gap-4 bg-slate-50 dark:bg-slate-700 pt-4 pb-2 px-6"
+            >
+                <label class="text-lg">
+                    {locale.tr("rates-file")}
+                    " "
+                    <input type="file" name="file" accept=".csv,text/csv" required/>
+                </label>
+                <button type="submit" class="bg-indigo-100 dark:bg-indigo-800 text-lg px-2 py-1 border border-solid border-slate-500 rounded">
+                    {locale.tr("action-upload")}
+                </button>
+            </form>
+        </Show>
+        {move || imported().and_then(|count| count.parse::<usize>().ok()).map(|count| view! {
+            <p class="px-6 py-2 text-indigo-500">{locale.message(&Message::new("rates-imported").arg("count", count))}</p>
+        })}
+        {move || upload_error().map(|err| view! {
+            <p class="px-6 py-2 text-pink-600">{locale.error_text(&err)}</p>
+        })}
+        <Transition fallback=Loading>
+            {move || match rates() {
+                    Some(Ok((reporting_currency, rates))) => {
+                        view!{
+                            <p class="px-8 pt-4">{locale.message(&Message::new("rates-reporting-currency").arg("currency", reporting_currency.code()))}</p>
+                            <table class="w-full">
+                                <thead class="border-solid border-b border-slate-500 font-bold text-left">
+                                    <tr>
+                                        <th class="p-2 pl-8">{locale.tr("field-date")}</th>
+                                        <th class="p-2">{locale.tr("field-currency")}</th>
+                                        <th class="p-2 pr-8">{locale.tr("rates-rate")}</th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    <For
+                                        each=move || rates.clone()
+                                        key=|r| (r.date, r.currency, r.base)
+                                        let:rate
+                                    >
+                                        <tr class="border-solid border-b border-slate-500">
+                                            <td class="p-2 pl-8">{rate.date.format("%d.%m.%Y").to_string()}</td>
+                                            <td class="p-2">{rate.currency.code()}</td>
+                                            <td class="p-2 pr-8">{format!("{} {}", rate.rate, rate.base)}</td>
+                                        </tr>
+                                    </For>
+                                </tbody>
+                            </table>
+                        }.into_view()
+                    }
+                    Some(Err(err)) => {
+                        let err = locale.error(&err);
+                        view! {
+                            <p class="text-pink-600 pb-2">{err}</p>
+                        }
+                        .into_view()
+                    }
+                    None => {
+                        view! {
+                            <Loading/>
+                        }
+                        .into_view()
+                    }
+                }
+            }
+        </Transition>
+    }
+}
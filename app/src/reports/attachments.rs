@@ -0,0 +1,90 @@
+use common::{i18n::Message, models::Attachment, IdType};
+use leptos::*;
+use leptos_router::use_query_map;
+
+use crate::i18n::use_locale;
+
+/// Files of an entry with upload and removal for its author.
+#[component]
+pub fn EntryAttachments(entry_id: IdType, #[prop(into)] editable: Signal<bool>) -> impl IntoView {
+    let locale = use_locale();
+
+    let attachments = create_resource(|| (), move |_| common::handlers::list_attachments(entry_id));
+
+    let query = use_query_map();
+    let attached = move || query.with(|q| q.get("attached").cloned());
+    let upload_error = move || query.with(|q| q.get("attachment_error").cloned());
+
+    view! {
+        <section class="p-8 m-8 bg-slate-200 dark:bg-slate-800 rounded-lg">
+            <h3 class="text-lg mb-4">{locale.tr("attachments-title")}</h3>
+            {move || attached().and_then(|count| count.parse::<usize>().ok()).map(|count| view! {
+                <p class="pb-2 text-indigo-500">{locale.message(&Message::new("attachments-uploaded").arg("count", count))}</p>
+            })}
+            {move || upload_error().map(|err| view! {
+                <p class="pb-2 text-pink-600">{locale.error_text(&err)}</p>
+            })}
+            <Transition fallback=|| ()>
+                {move || match attachments() {
+                    Some(Ok(attachments)) if attachments.is_empty() => view! {
+                        <p class="pb-4 text-slate-500">{locale.tr("attachments-empty")}</p>
+                    }.into_view(),
+                    Some(Ok(attachments)) => view! {
+                        <ul class="pb-4">
+                            <For each=move || attachments.clone() key=|a| a.id let:attachment>
+                                <AttachmentItem attachment editable/>
+                            </For>
+                        </ul>
+                    }.into_view(),
+                    Some(Err(err)) => view! {
+                        <p class="pb-4 text-pink-600">{locale.error(&err)}</p>
+                    }.into_view(),
+                    None => ().into_view(),
+                }}
+            </Transition>
+            <Show when=editable>
+                <form
+                    method="post"
+                    action=format!("/reports/{entry_id}/attachments")
+                    enctype="multipart/form-data"
+                    class="flex flex-wrap items-center gap-4"
+                >
+                    <input type="file" name="file" multiple required accept=Attachment::CONTENT_TYPES.join(",")/>
+                    <button type="submit" class="px-2 py-1 border border-solid border-slate-500 rounded">
+                        {locale.tr("attachments-attach")}
+                    </button>
+                </form>
+            </Show>
+        </section>
+    }
+}
+
+#[component]
+fn AttachmentItem(attachment: Attachment, editable: Signal<bool>) -> impl IntoView {
+    let locale = use_locale();
+
+    let icon = if attachment.is_image() {
+        "fa-solid fa-file-image pr-2"
+    } else {
+        "fa-solid fa-file-pdf pr-2"
+    };
+
+    view! {
+        <li class="flex items-center gap-4 py-1">
+            <a href=format!("/attachments/{}", attachment.id) target="_blank" rel="noopener" class="underline truncate">
+                <i class=icon></i>
+                {attachment.file_name.clone()}
+            </a>
+            <span class="text-sm text-slate-500">
+                {format!("{}, {}", attachment.size_label(locale), attachment.uploaded_at.format("%d.%m.%Y %H:%M"))}
+            </span>
+            <Show when=editable>
+                <form method="post" action=format!("/attachments/{}/delete", attachment.id)>
+                    <button type="submit" title=locale.tr("action-delete")>
+                        <i class="fa-solid fa-trash"></i>
+                    </button>
+                </form>
+            </Show>
+        </li>
+    }
+}
@@ -0,0 +1,147 @@
+use common::{
+    handlers::{EditComment, PostComment},
+    models::{comment::MAX_COMMENT_LENGTH, Comment},
+    user::User,
+    IdType,
+};
+use leptos::*;
+use leptos_router::ActionForm;
+
+use crate::{i18n::use_locale, loading::Loading, users::user_name_short};
+
+/// Replies deeper than this are shown at the same indentation.
+const MAX_INDENT: usize = 4;
+
+/// Discussion of an entry between its owner and the users who view all reports.
+#[component]
+pub fn CommentsThread(entry_id: IdType) -> impl IntoView {
+    let locale = use_locale();
+
+    let post_comment = create_server_action::<PostComment>();
+    let edit_comment = create_server_action::<EditComment>();
+
+    let comments = create_resource(
+        move || (post_comment.version().get(), edit_comment.version().get()),
+        move |_| common::handlers::list_comments(entry_id),
+    );
+
+    let app_user = use_context::<Signal<User>>().unwrap();
+
+    let replying_to = create_rw_signal(None::<IdType>);
+    let editing = create_rw_signal(None::<IdType>);
+
+    create_effect(move |_| {
+        post_comment.version().track();
+        edit_comment.version().track();
+        replying_to.set(None);
+        editing.set(None);
+    });
+
+    let post_value = post_comment.value();
+    let edit_value = edit_comment.value();
+    let action_error = move || {
+        [post_value(), edit_value()]
+            .into_iter()
+            .find_map(|v| v.and_then(Result::err))
+            .map(|err| view! {<p class="text-pink-600 pb-2">{locale.error(&err)}</p>})
+    };
+
+    let comment_form = move |parent_id: Option<IdType>| {
+        view! {
+            <ActionForm action=post_comment class="flex flex-col gap-2 py-2">
+                <input type="hidden" name="entry_id" value=entry_id.to_string()/>
+                {parent_id.map(|id| view! {<input type="hidden" name="parent_id" value=id.to_string()/>})}
+                <textarea
+                    name="body"
+                    required
+                    maxlength=MAX_COMMENT_LENGTH.to_string()
+                    placeholder=locale.tr(if parent_id.is_some() { "comments-reply-placeholder" } else { "comments-placeholder" })
+                    class="w-full rounded p-2 !bg-transparent !text-inherit dark:!text-inherit border border-slate-500"
+                ></textarea>
+                <button type="submit" class="self-start px-2 py-1 border border-solid border-slate-500 rounded">
+                    {locale.tr(if parent_id.is_some() { "comments-reply" } else { "comments-send" })}
+                </button>
+            </ActionForm>
+        }
+    };
+
+    view! {
+        <div class="py-2">
+            <Transition fallback=Loading>
+                {move || match comments() {
+                    Some(Ok(comments)) if comments.is_empty() => view! {
+                        <p class="text-slate-500 pb-2">{locale.tr("comments-empty")}</p>
+                    }.into_view(),
+                    Some(Ok(comments)) => Comment::thread(&comments)
+                        .into_iter()
+                        .map(|(depth, comment)| {
+                            let id = comment.id;
+                            let is_own = comment.author.id == app_user.get_untracked().id;
+                            let body = comment.body.clone();
+                            let edit_body = store_value(comment.body.clone());
+
+                            view! {
+                                <div
+                                    class="py-2 border-l-2 border-slate-400 pl-3 my-1"
+                                    style=format!("margin-left: {}rem", depth.min(MAX_INDENT) * 2)
+                                >
+                                    <p class="text-sm text-slate-500">
+                                        {user_name_short(&comment.author)}
+                                        {format!(", {}", comment.created_at.format("%d.%m.%Y %H:%M"))}
+                                        {comment.edited_at.map(|_| format!(" {}", locale.tr("comments-edited")))}
+                                    </p>
+                                    <Show
+                                        when=move || editing() == Some(id)
+                                        fallback=move || view! {
+                                            <p class="whitespace-pre-wrap">{body.clone()}</p>
+                                        }
+                                    >
+                                        <ActionForm action=edit_comment class="flex flex-col gap-2 py-2">
+                                            <input type="hidden" name="id" value=id.to_string()/>
+                                            <textarea
+                                                name="body"
+                                                required
+                                                maxlength=MAX_COMMENT_LENGTH.to_string()
+                                                class="w-full rounded p-2 !bg-transparent !text-inherit dark:!text-inherit border border-slate-500"
+                                            >{edit_body.get_value()}</textarea>
+                                            <button type="submit" class="self-start px-2 py-1 border border-solid border-slate-500 rounded">
+                                                {locale.tr("action-save")}
+                                            </button>
+                                        </ActionForm>
+                                    </Show>
+                                    <p class="text-sm flex gap-4">
+                                        <button type="button" class="text-indigo-500" on:click=move |_| {
+                                            replying_to.update(|r| *r = if *r == Some(id) { None } else { Some(id) })
+                                        }>
+                                            {locale.tr("comments-reply")}
+                                        </button>
+                                        {is_own.then(|| view! {
+                                            <button type="button" class="text-indigo-500" on:click=move |_| {
+                                                editing.update(|e| *e = if *e == Some(id) { None } else { Some(id) })
+                                            }>
+                                                {locale.tr("comments-edit")}
+                                            </button>
+                                        })}
+                                    </p>
+                                    <Show when=move || replying_to() == Some(id)>
+                                        {comment_form(Some(id))}
+                                    </Show>
+                                </div>
+                            }
+                        })
+                        .collect_view(),
+                    Some(Err(err)) => view! {
+                        <p class="text-pink-600 pb-2">{locale.error(&err)}</p>
+                    }.into_view(),
+                    None => view! { <Loading/> }.into_view(),
+                }}
+            </Transition>
+            {action_error}
+            // recreated after every post, which clears the text
+            {move || {
+                post_comment.version().track();
+                comment_form(None)
+            }}
+        </div>
+    }
+}
@@ -1,14 +1,24 @@
 use common::{
+    error::AppError,
     handlers::{NewReport, UpdateReport},
-    models::{entry::month_range, Entry},
-    moneys::Moneys,
+    i18n::Message,
+    models::{Entry, Payment, PaymentMethod, ReportFields},
+    moneys::{Currency, Moneys},
+    perms::EDIT_OWNED,
+    tz,
     user::User,
-    Datelike, IdType,
+    IdType,
 };
 use leptos::*;
-use leptos_router::{use_params, ActionForm, Params};
+use leptos_router::{use_params, ActionForm, Params, A};
 
-use crate::loading::Loading;
+use crate::{
+    form::{is_form_error, FieldError},
+    i18n::use_locale,
+    loading::Loading,
+    offline::{is_unreachable, use_offline_queue},
+    reports::EntryAttachments,
+};
 
 #[derive(Params, PartialEq)]
 struct EditReporParams {
@@ -18,162 +28,415 @@ struct EditReporParams {
 #[component]
 pub fn EditReport() -> impl IntoView {
     let params = use_params::<EditReporParams>();
+    let locale = use_locale();
 
     let create_report = create_server_action::<NewReport>();
     let update_report = create_server_action::<UpdateReport>();
 
     let create_value = create_report.value();
-    let has_create_error = move || create_value.with(|val| matches!(val, Some(Err(_))));
+    let has_create_error = move || create_value.with(|v| is_form_error(v) && !is_unreachable(v));
     let update_value = update_report.value();
-    let has_update_error = move || update_value.with(|val| matches!(val, Some(Err(_))));
+    let has_update_error = move || update_value.with(|v| is_form_error(v) && !is_unreachable(v));
+
+    let current_user = use_context::<Signal<User>>().unwrap();
 
-    let report_data = create_resource(
+    let report_result = create_resource(
         move || params.with(|p| p.as_ref().map(|p| p.id).ok().flatten()),
         move |id: Option<IdType>| async move {
             match id {
-                Some(id) => common::handlers::get_report(id).await.unwrap_or_default(),
-                None => Entry::default(),
+                Some(id) => common::handlers::get_report(id).await,
+                None => Ok(Entry::blank(&current_user.get_untracked())),
             }
         },
     );
+    // the form of a report that can't be loaded is replaced by the error
+    let report_data = move || report_result().and_then(Result::ok);
 
-    let current_user = use_context::<Signal<User>>().unwrap();
+    let id_param =
+        Signal::derive(move || params.with(|params| params.as_ref().map(|p| p.id).ok().flatten()));
+
+    // the action's input is cleared along with setting its value, so the submitted fields
+    // are kept to queue them on the device when the server can't be reached
+    let queue = use_offline_queue();
+    let create_input = store_value(None::<NewReport>);
+    let update_input = store_value(None::<UpdateReport>);
+
+    create_effect(move |_| {
+        if let Some(input) = create_report.input().get() {
+            create_input.set_value(Some(input));
+        }
+    });
+    create_effect(move |_| {
+        if let Some(input) = update_report.input().get() {
+            update_input.set_value(Some(input));
+        }
+    });
+    create_effect(move |_| {
+        if !create_value.with(is_unreachable) {
+            return;
+        }
+        if let Some(input) = create_input.get_value() {
+            let NewReport {
+                payments,
+                shift,
+                currency,
+                address,
+                date,
+                ..
+            } = input;
+            queue.push(
+                None,
+                ReportFields {
+                    payments,
+                    shift,
+                    currency,
+                    address,
+                    date,
+                },
+            );
+        }
+    });
+    create_effect(move |_| {
+        if !update_value.with(is_unreachable) {
+            return;
+        }
+        if let (Some(input), Some(report)) = (update_input.get_value(), untrack(report_data)) {
+            let UpdateReport {
+                payments,
+                shift,
+                currency,
+                address,
+                date,
+                ..
+            } = input;
+            queue.push(
+                Some(&report),
+                ReportFields {
+                    payments,
+                    shift,
+                    currency,
+                    address,
+                    date,
+                },
+            );
+        }
+    });
+
+    // breakdown inputs, kept in signals so the derived total follows them
+    let amounts = PaymentMethod::ALL.map(|_| create_rw_signal(String::new()));
+    let selected_currency = create_rw_signal(None::<Currency>);
+
+    create_effect(move |_| {
+        if let Some(report) = report_data() {
+            for (method, amount) in PaymentMethod::ALL.into_iter().zip(amounts) {
+                let value = report
+                    .payments
+                    .iter()
+                    .find(|p| p.method == method)
+                    .map(|p| format_amount(p.amount))
+                    .unwrap_or_default();
+                amount.set(value);
+            }
+            selected_currency.set(None);
+        }
+    });
+
+    let total = move || {
+        let currency = selected_currency().unwrap_or_else(|| {
+            report_result
+                .with(|r| {
+                    r.as_ref()
+                        .and_then(|r| r.as_ref().ok())
+                        .map(|r| r.revenue.currency)
+                })
+                .unwrap_or_default()
+        });
+        let values = amounts.map(|amount| amount());
+        let fields = PaymentMethod::ALL
+            .into_iter()
+            .zip(values.iter().map(String::as_str))
+            .collect::<Vec<_>>();
+
+        match Payment::parse_breakdown(&fields, currency) {
+            Ok((_, total)) => total.to_string(),
+            Err(_) => "—".to_string(),
+        }
+    };
+
+    // set once the server warned about an unusual revenue, so the next submit saves it
+    // anyway, and cleared as soon as the amounts change
+    let confirm = create_rw_signal(false);
+
+    let action_value = Signal::derive(move || {
+        if id_param().is_some() {
+            update_value()
+        } else {
+            create_value()
+        }
+    });
+
+    let server_error = move || match action_value() {
+        Some(Err(ServerFnError::WrappedServerError(err))) => Some(err),
+        _ => None,
+    };
+
+    create_effect(move |_| {
+        if server_error().is_some_and(|err| err.field() == Some("anomaly")) {
+            confirm.set(true);
+        }
+    });
+
+    let anomaly_warning = move || match server_error() {
+        Some(AppError::Validation { field, message }) if field == "anomaly" && confirm() => {
+            Some(view! {
+                <p class="text-amber-600 pb-4">
+                    <i class="fa-solid fa-triangle-exclamation mr-2"></i>
+                    {locale.message(&Message::new("report-anomaly-confirm").arg("warning", locale.text(&message)))}
+                </p>
+            })
+        }
+        _ => None,
+    };
+
+    let duplicate_notice = move || {
+        let err = server_error()?;
+        let id = err.duplicate_of()?;
+
+        Some(view! {
+            <p class="text-pink-600 pb-4">
+                {locale.text(err.message())}
+                ". "
+                <A href=format!("/reports/{id}") class="underline">{locale.tr("report-duplicate-open")}</A>
+            </p>
+        })
+    };
+
+    // same rule as `update_report`: the author's own entry within the report window
+    let can_attach = Signal::derive(move || {
+        let user = current_user();
+
+        user.permissions.contains(EDIT_OWNED)
+            && report_data().is_some_and(|r| {
+                r.by_user_id == user.id && tz::in_report_window(r.date, tz::today(r.timezone))
+            })
+    });
 
     let form_content = move || {
         view! {
             <hr class="my-2"/>
-            <h3 class="text-lg mb-2">"Данные менеджера:"</h3>
+            <h3 class="text-lg mb-2">{locale.tr("report-manager-section")}</h3>
             <label class="w-full pb-8 flex flex-col-reverse">
                 <input
                     type="text"
-                    placeholder="Фамилия"
+                    placeholder=locale.tr("field-family-name")
                     readonly
                     maxlength="250"
                     value=move || current_user().family_name
                     class="w-full text-base rounded px-4 py-2 !bg-transparent !text-inherit dark:!text-inherit border border-slate-300 dark:border-slate-700"/>
-                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">"Фамилия:"</span>
+                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">{format!("{}:", locale.tr("field-family-name"))}</span>
             </label>
             <label class="w-full pb-8 flex flex-col-reverse">
                 <input
                     type="text"
-                    placeholder="Имя"
+                    placeholder=locale.tr("field-name")
                     readonly
                     maxlength="250"
                     value=move || current_user().name
                     class="w-full text-base rounded px-4 py-2 !bg-transparent !text-inherit dark:!text-inherit border border-slate-300 dark:border-slate-700"/>
-                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">"Имя:"</span>
+                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">{format!("{}:", locale.tr("field-name"))}</span>
             </label>
             <label class="w-full pb-8 flex flex-col-reverse">
                 <input
                     type="text"
-                    placeholder="Отчество"
+                    placeholder=locale.tr("field-patronym")
                     readonly
                     maxlength="250"
                     value=move || current_user().patronym
                     class="w-full text-base rounded px-4 py-2 !bg-transparent !text-inherit dark:!text-inherit border border-slate-300 dark:border-slate-700"/>
-                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">"Отчество:"</span>
+                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">{format!("{}:", locale.tr("field-patronym"))}</span>
             </label>
             <hr class="my-2"/>
-            <h3 class="text-lg mb-2">"Данные отчета:"</h3>
+            <h3 class="text-lg mb-2">{locale.tr("report-section")}</h3>
 
             <label class="w-full pb-8 flex flex-col-reverse">
                 <input
                     type="date"
-                    placeholder="Дата"
+                    placeholder=locale.tr("field-date")
                     name="date"
                     prop:value=move || report_data().unwrap_or_default().date.format("%Y-%m-%d").to_string()
                     min={move || {
-                        let date = report_data().unwrap_or_default().date;
-                        let (min_date, _) = month_range(date.year(), date.month());
+                        let (min_date, _) = tz::report_window(tz::today(report_data().unwrap_or_default().timezone));
                         min_date.format("%Y-%m-%d").to_string()
                     }}
                     max={move || {
-                        let date = report_data().unwrap_or_default().date;
-                        let (_, max_date) = month_range(date.year(), date.month());
+                        let (_, max_date) = tz::report_window(tz::today(report_data().unwrap_or_default().timezone));
                         max_date.format("%Y-%m-%d").to_string()
                     }}
                     class="w-full text-xl rounded p-4 !bg-transparent !text-inherit dark:!text-inherit border border-slate-500"/>
-                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">"Дата:"</span>
+                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">{format!("{}:", locale.tr("field-date"))}</span>
             </label>
+            <FieldError value=action_value field="date"/>
 
             <label class="w-full pb-8 flex flex-col-reverse">
                 <textarea
                     prop:value={move || report_data().unwrap_or_default().address}
-                    placeholder="Адрес точки"
+                    placeholder=locale.tr("report-address")
                     name="address"
                     autocomplete="address"
                     class="w-full text-xl rounded p-4 !bg-transparent !text-inherit dark:!text-inherit border border-slate-500">
                     // {move || report_data.get_untracked().unwrap_or_default().address}
                 </textarea>
-                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">"Адрес точки:"</span>
+                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">{format!("{}:", locale.tr("report-address"))}</span>
             </label>
 
+            <h4 class="text-base mb-1">{locale.tr("report-shift")}</h4>
+            <p class="text-sm text-slate-500 mb-4">{locale.tr("report-shift-hint")}</p>
+            <div class="flex gap-4">
+                {[("start", "report-shift-start"), ("end", "report-shift-end")].map(|(part, label)| view! {
+                    <label class="w-full pb-8 flex flex-col-reverse">
+                        <input
+                            type="time"
+                            name=format!("shift[{part}]")
+                            prop:value=move || {
+                                report_data()
+                                    .unwrap_or_default()
+                                    .shift
+                                    .map(|s| if part == "start" { s.start } else { s.end }.format("%H:%M").to_string())
+                                    .unwrap_or_default()
+                            }
+                            class="w-full text-xl rounded p-4 !bg-transparent !text-inherit dark:!text-inherit border border-slate-500"/>
+                        <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">{format!("{}:", locale.tr(label))}</span>
+                    </label>
+                }).collect_view()}
+            </div>
+            <FieldError value=action_value field="shift"/>
             <label class="w-full pb-8 flex flex-col-reverse">
                 <input
-                    type="number"
-                    step="0.01"
-                    placeholder="Выручка"
-                    name="revenue"
-                    prop:value={move || {
-                        let f_val:f64 = Moneys::from(report_data().unwrap_or_default().revenue).into();
-                        f_val
-                    }}
+                    type="text"
+                    maxlength="50"
+                    name="shift[label]"
+                    prop:value=move || report_data().unwrap_or_default().shift_label.unwrap_or_default()
                     class="w-full text-xl rounded p-4 !bg-transparent !text-inherit dark:!text-inherit border border-slate-500"/>
-                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">"Выручка, ₽:"</span>
+                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">{format!("{}:", locale.tr("report-shift-label"))}</span>
             </label>
+            <FieldError value=action_value field="shift_label"/>
+
+            <h4 class="text-base mb-4">{locale.tr("report-breakdown")}</h4>
+            {PaymentMethod::ALL.into_iter().zip(amounts).map(|(method, amount)| view! {
+                <label class="w-full pb-8 flex flex-col-reverse">
+                    <input
+                        type="text"
+                        inputmode="decimal"
+                        placeholder="1 234,56"
+                        name=format!("payments[{}]", method.code())
+                        prop:value=amount
+                        on:input=move |ev| {
+                            amount.set(event_target_value(&ev));
+                            confirm.set(false);
+                        }
+                        class="w-full text-xl rounded p-4 !bg-transparent !text-inherit dark:!text-inherit border border-slate-500"/>
+                    <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">{format!("{}:", method.label(locale))}</span>
+                </label>
+                <FieldError value=action_value field=method.code()/>
+            }).collect_view()}
+
+            <Show when=move || id_param().is_some() && report_data().is_some_and(|r| r.payments.is_empty())>
+                <p class="pb-4 text-slate-500">
+                    {move || locale.message(&Message::new("report-unsplit").arg("revenue", report_data().unwrap_or_default().revenue.to_string()))}
+                </p>
+            </Show>
+            <p class="text-xl pb-8">{move || locale.message(&Message::new("report-total").arg("total", total()))}</p>
+            <FieldError value=action_value field="revenue"/>
+
+            <label class="w-full pb-8 flex flex-col-reverse">
+                <select
+                    name="currency"
+                    on:change=move |ev| {
+                        selected_currency.set(event_target_value(&ev).parse().ok());
+                        confirm.set(false);
+                    }
+                    class="w-full text-xl rounded p-4 !bg-transparent !text-inherit dark:!text-inherit border border-slate-500">
+                    <option value="" selected=move || id_param().is_none()>{locale.tr("report-currency-default")}</option>
+                    {Currency::ALL.into_iter().map(|currency| view! {
+                        <option
+                            value=currency.code()
+                            selected=move || id_param().is_some() && report_data().unwrap_or_default().revenue.currency == currency
+                        >
+                            {format!("{} {}", currency.code(), currency.symbol())}
+                        </option>
+                    }).collect_view()}
+                </select>
+                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">{format!("{}:", locale.tr("field-currency"))}</span>
+            </label>
+            <FieldError value=action_value field="currency"/>
+
+            <input type="hidden" name="confirm" prop:value=move || confirm().to_string()/>
+            {anomaly_warning}
+            {duplicate_notice}
+            <Show when=move || action_value.with(is_unreachable)>
+                <p class="text-amber-600 pb-4">
+                    <i class="fa-solid fa-cloud-arrow-up mr-2"></i>
+                    {locale.tr("offline-saved")}
+                </p>
+            </Show>
         }
     };
 
-    let id_param =
-        Signal::derive(move || params.with(|params| params.as_ref().map(|p| p.id).ok().flatten()));
-
     view! {
         <Transition fallback=Loading>
             {
-                 move || match id_param() {
-                    Some(id) => {
+                 move || match (id_param(), report_result()) {
+                    (Some(_), Some(Err(err))) => Err(err),
+                    (Some(id), _) => Ok({
                         view! {
                             <ActionForm action=update_report
                                 class="p-8 m-8 bg-slate-200 dark:bg-slate-800 rounded-lg"
                                 attributes=vec![("autocomplete", Attribute::String("off".into()))]
                             >
-                                <h1 class="text-2xl mb-12">"Редактирование отчета"</h1>
+                                <h1 class="text-2xl mb-12">{locale.tr("report-edit-title")}</h1>
                                 <input type="hidden" name="id" value=id.to_string()/>
                                 {form_content}
                                 <Show when=has_update_error>
                                     {move || {
-                                        let err = format!("Ошибка: {}", update_value().unwrap().unwrap_err());
+                                        let err = locale.error(&update_value().unwrap().unwrap_err());
                                         view! {<p class="text-pink-600 pb-2">{err}</p>}
                                     }}
                                 </Show>
                                 <button type="submit" class="w-full mb-4 text-xl p-4 border border-solid border-slate-500 rounded">
-                                    "Сохранить"
+                                    {move || locale.tr(if confirm() { "action-save-anyway" } else { "action-save" })}
                                 </button>
                             </ActionForm>
+                            <EntryAttachments entry_id=id editable=can_attach/>
                         }.into_view()
-                    }
-                    None => {
+                    }),
+                    (None, _) => Ok({
                         view! {
                             <ActionForm action=create_report
                                 class="p-8 m-8 bg-slate-200 dark:bg-slate-800 rounded-lg"
                                 attributes=vec![("autocomplete", Attribute::String("off".into()))]
                             >
-                                <h1 class="text-2xl mb-12">"Добавление нового отчета"</h1>
+                                <h1 class="text-2xl mb-12">{locale.tr("report-new-title")}</h1>
                                 {form_content}
                                 <Show when=has_create_error>
                                     {move || {
-                                        let err = format!("Ошибка: {}", create_value().unwrap().unwrap_err());
+                                        let err = locale.error(&create_value().unwrap().unwrap_err());
                                         view! {<p class="text-pink-600 pb-2">{err}</p>}
                                     }}
                                 </Show>
                                 <button type="submit" class="w-full mb-4 text-xl p-4 border border-solid border-slate-500 rounded">
-                                    "Добавить"
+                                    {move || locale.tr(if confirm() { "action-add-anyway" } else { "action-add" })}
                                 </button>
                             </ActionForm>
                         }.into_view()
-                    }
+                    }),
                 }
             }
         </Transition>
     }
 }
+
+/// Amount in major units with a decimal comma, as typed into the form.
+fn format_amount(moneys: Moneys) -> String {
+    let value: f64 = moneys.into();
+    value.to_string().replace('.', ",")
+}
@@ -1,9 +1,12 @@
-use chrono::{Datelike, Utc};
-use common::{handlers::ListReports, models::EntryWithUser, perms::EDIT_OWNED, user::User, IdType};
+use std::collections::HashSet;
+
+use common::{
+    handlers::ListReports, models::EntryWithUser, perms::EDIT_OWNED, tz, user::User, IdType,
+};
 use leptos::*;
 use leptos_router::A;
 
-use crate::loading::Loading;
+use crate::{i18n::use_locale, live::use_live_updates, loading::Loading, reports::CommentsThread};
 
 #[component]
 pub fn ReportsList(
@@ -11,11 +14,22 @@ pub fn ReportsList(
     #[prop(into)] month: Signal<u32>,
     #[prop(into)] user: Signal<Option<IdType>>,
 ) -> impl IntoView {
+    let locale = use_locale();
+
     let list_reports = create_server_action::<ListReports>();
+    let live_updates = use_live_updates();
 
     let reports = create_local_resource(
-        move || (list_reports.version().get(), year(), month(), user()),
-        move |(_, year, month, user_id)| common::handlers::list_reports(year, month, user_id),
+        move || {
+            (
+                list_reports.version().get(),
+                live_updates(),
+                year(),
+                month(),
+                user(),
+            )
+        },
+        move |(_, _, year, month, user_id)| common::handlers::list_reports(year, month, user_id),
     );
 
     view! {
@@ -27,7 +41,7 @@ pub fn ReportsList(
                         }.into_view()
                     }
                     Some(Err(err)) => {
-                        let err = format!("Ошибка: {}", err);
+                        let err = locale.error(&err);
                         view! {
                             <p class="text-pink-600 pb-2">{err}</p>
                         }
@@ -47,19 +61,24 @@ pub fn ReportsList(
 
 #[component]
 pub fn ReportsTable(#[prop(into)] reports: Signal<Vec<EntryWithUser>>) -> impl IntoView {
+    let locale = use_locale();
+
     let app_user = use_context::<Signal<User>>().unwrap();
     let manager_permissions_guard =
         Signal::derive(move || app_user().permissions.contains(EDIT_OWNED));
 
-    let now_month = Signal::derive(|| Utc::now().month());
+    // one thread is open at a time; opened threads count as read until the next fetch
+    let open_thread = create_rw_signal(None::<IdType>);
+    let read_threads = create_rw_signal(HashSet::<IdType>::new());
 
     view! {
         <table class="w-full">
             <thead class="border-solid border-b border-slate-500 font-bold text-left">
                 <tr>
-                    <th class="p-2 pl-8">{"Дата"}</th>
-                    <th class="p-2">{"Адрес"}</th>
-                    <th class="p-2">{"Выручка"}</th>
+                    <th class="p-2 pl-8">{locale.tr("field-date")}</th>
+                    <th class="p-2">{locale.tr("field-address")}</th>
+                    <th class="p-2">{locale.tr("field-revenue")}</th>
+                    <th class="p-2">{locale.tr("reports-discussion")}</th>
                     <Show when=manager_permissions_guard>
                         <th class="p-2 pr-8 text-right">
                             <i class="fa-solid fa-ellipsis-vertical"></i>
@@ -69,20 +88,70 @@ pub fn ReportsTable(#[prop(into)] reports: Signal<Vec<EntryWithUser>>) -> impl I
             </thead>
             <tbody>
                 <For each=reports key=|u| u.id let:report>
+                    {
+                        let id = report.id;
+                        let unread = report.unread_comments;
+                        let comments = report.comments;
+                        let shift = [report.shift_label.clone(), report.shift.map(|s| s.to_string())]
+                            .into_iter()
+                            .flatten()
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        let has_unread = move || unread > 0 && !read_threads.with(|r| r.contains(&id));
+                        let toggle = move |_| {
+                            read_threads.update(|r| {
+                                r.insert(id);
+                            });
+                            open_thread.update(|open| *open = if *open == Some(id) { None } else { Some(id) });
+                        };
+                        view! {
                     <tr class="border-solid border-b border-slate-500">
-                        <td class="p-2 pl-8">{report.date.format("%d.%m.%Y").to_string()}</td>
+                        <td class="p-2 pl-8">
+                            {report.date.format("%d.%m.%Y").to_string()}
+                            {(!shift.is_empty()).then(|| view! {
+                                <small class="block text-slate-500">{shift}</small>
+                            })}
+                        </td>
                         <td class="p-2">{report.address}</td>
-                        <td class="p-2">{format!("{}", report.revenue)}</td>
+                        <td class="p-2">
+                            {format!("{}", report.revenue)}
+                            {report.anomaly.map(|anomaly| view! {
+                                <i title=locale.text(&anomaly) class="fa-solid fa-triangle-exclamation text-amber-600 ml-2"></i>
+                            })}
+                            {report.converted.filter(|c| c.currency != report.revenue.currency).map(|converted| view! {
+                                <small class="block text-slate-500">{format!("≈ {converted}")}</small>
+                            })}
+                        </td>
+                        <td class="p-2">
+                            <button type="button" on:click=toggle title=locale.tr("reports-discussion")>
+                                <i class="fa-regular fa-comments mr-1"></i>
+                                {(comments > 0).then_some(comments)}
+                                <Show when=has_unread>
+                                    <span title=locale.tr("unread-comments") class="ml-1 px-1 rounded-full bg-pink-600 text-white text-xs">
+                                        {unread}
+                                    </span>
+                                </Show>
+                            </button>
+                        </td>
                         <Show when=manager_permissions_guard>
                             <td class="p-2 pr-6 text-right">
-                                <Show when=move || report.date.month() == now_month()>
+                                <Show when=move || tz::in_report_window(report.date, tz::today(report.timezone))>
                                     <A href=format!("{}",report.id) class="px-2 py-1 border border-solid border-slate-500 rounded-sm">
-                                        <i title="Редактировать" class="fa-solid fa-pen-to-square"></i>
+                                        <i title=locale.tr("action-edit") class="fa-solid fa-pen-to-square"></i>
                                     </A>
                                 </Show>
                             </td>
                         </Show>
                     </tr>
+                    <Show when=move || open_thread() == Some(id)>
+                        <tr class="border-solid border-b border-slate-500">
+                            <td colspan="5" class="px-8">
+                                <CommentsThread entry_id=id/>
+                            </td>
+                        </tr>
+                    </Show>
+                        }
+                    }
                 </For>
             </tbody>
         </table>
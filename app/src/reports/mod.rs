@@ -1,8 +1,12 @@
+mod attachments;
+mod comments;
 mod edit;
 mod list;
 mod summary;
 mod viewer;
 
+pub use attachments::*;
+pub use comments::*;
 pub use edit::*;
 pub use list::*;
 pub use summary::*;
@@ -1,57 +1,133 @@
-use std::collections::HashMap;
-
-use common::{models, moneys::Moneys, user::User, IdType};
+use chrono::Utc;
+use common::{
+    models::{self, PaymentMethod, Plan, PlanScope},
+    summary::{by_manager, Totals},
+    IdType,
+};
 use leptos::*;
 use leptos_router::A;
 
-use crate::users::user_name_short;
+use crate::{i18n::use_locale, users::user_name_short};
 
 #[component]
-pub fn ReportsSummary(#[prop(into)] reports: Signal<Vec<models::EntryWithUser>>) -> impl IntoView {
-    let summary = Signal::derive(move || {
-        let grouped = reports().into_iter().fold(
-            HashMap::<IdType, (User, Moneys)>::new(),
-            |mut acc, entry| {
-                let ge = acc.entry(entry.user.id).or_insert((entry.user, Moneys(0)));
-                ge.1 .0 += entry.revenue.0;
-
-                acc
-            },
-        );
-
-        let mut agg = grouped.values().cloned().collect::<Vec<_>>();
-
-        agg.sort_by(|(a_user, _), (b_user, _)| {
-            a_user
-                .family_name
-                .partial_cmp(&b_user.family_name)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-
-        agg
-    });
+pub fn ReportsSummary(
+    #[prop(into)] reports: Signal<Vec<models::EntryWithUser>>,
+    /// Plans of the month, managers' ones add plan-vs-actual columns.
+    #[prop(into, optional)]
+    plans: Signal<Vec<Plan>>,
+) -> impl IntoView {
+    let locale = use_locale();
+
+    let summary = Signal::derive(move || reports.with(|reports| by_manager(reports)));
+
+    let month_totals = Signal::derive(move || reports.with(|reports| Totals::of(reports)));
+
+    let has_unsplit = move || month_totals.with(|t| !t.unsplit.is_empty());
+
+    let shift_columns = Signal::derive(move || month_totals.with(Totals::shift_columns));
+
+    let has_plans = move || {
+        plans.with(|plans| {
+            plans
+                .iter()
+                .any(|p| matches!(p.scope, PlanScope::Manager(_)))
+        })
+    };
+
+    let manager_progress = move |user_id: IdType| {
+        plans.with(|plans| {
+            plans
+                .iter()
+                .find(|p| p.scope == PlanScope::Manager(user_id))
+                .map(|plan| reports.with(|reports| plan.progress(reports, Utc::now())))
+        })
+    };
 
     view! {
         <table class="w-full">
             <thead class="border-solid border-b border-slate-500 font-bold text-left">
                 <tr>
-                    <th class="p-2 pl-8">{"Менеджер"}</th>
-                    <th class="p-2">{"Выручка"}</th>
+                    <th class="p-2 pl-8">{locale.tr("field-manager")}</th>
+                    {PaymentMethod::ALL.map(|method| view! {
+                        <th class="p-2">{method.label(locale)}</th>
+                    }).collect_view()}
+                    <Show when=has_unsplit>
+                        <th class="p-2">{locale.tr("summary-unsplit")}</th>
+                    </Show>
+                    {move || shift_columns().into_iter().map(|name| view! {
+                        <th class="p-2">{name.unwrap_or_else(|| locale.tr("summary-whole-day"))}</th>
+                    }).collect_view()}
+                    <th class="p-2">{locale.tr("field-revenue")}</th>
+                    <Show when=has_plans>
+                        <th class="p-2">{locale.tr("field-plan")}</th>
+                        <th class="p-2">{locale.tr("summary-completion")}</th>
+                        <th class="p-2">{locale.tr("summary-remaining")}</th>
+                        <th class="p-2">{locale.tr("summary-projection")}</th>
+                    </Show>
                     <th class="p-2 pr-8 text-right">
                         <i class="fa-solid fa-ellipsis-vertical"></i>
                     </th>
                 </tr>
             </thead>
             <tbody>
-                <For each=summary key=|(u, _)| u.id let:entry>
-                    <tr class="border-solid border-b border-slate-500">
-                        <td class="p-2 pl-8">
-                            <A class="text-indigo-500" href={format!("/reports?user_id={}", entry.0.id)}>{user_name_short(&entry.0)}</A>
-                        </td>
-                        <td class="p-2">{format!("{}", entry.1)}</td>
-                    </tr>
-                </For>
+                <For each=summary key=|(u, _)| u.id children=move |(user, totals)| {
+                    let user_id = user.id;
+                    let unsplit = totals.unsplit.to_string();
+                    let by_shift = totals.clone();
+                    let unread_comments = totals.unread_comments;
+                    let cell = move |f: fn(&models::PlanProgress) -> String| {
+                        move || manager_progress(user_id).as_ref().map(f).unwrap_or_default()
+                    };
+
+                    view! {
+                        <tr class="border-solid border-b border-slate-500">
+                            <td class="p-2 pl-8">
+                                <A class="text-indigo-500" href={format!("/reports?user_id={user_id}")}>{user_name_short(&user)}</A>
+                                {(unread_comments > 0).then(|| view! {
+                                    <span title=locale.tr("unread-comments") class="ml-2 px-1 rounded-full bg-pink-600 text-white text-xs">
+                                        <i class="fa-solid fa-comment mr-1"></i>
+                                        {unread_comments}
+                                    </span>
+                                })}
+                            </td>
+                            {PaymentMethod::ALL.map(|method| view! {
+                                <td class="p-2">{totals.method(method)}</td>
+                            }).collect_view()}
+                            <Show when=has_unsplit>
+                                <td class="p-2">{unsplit.clone()}</td>
+                            </Show>
+                            {move || shift_columns().iter().map(|name| view! {
+                                <td class="p-2">{by_shift.shift(name)}</td>
+                            }).collect_view()}
+                            <td class="p-2">{totals.revenue.to_string()}</td>
+                            <Show when=has_plans>
+                                <td class="p-2">{cell(|p| p.target.to_string())}</td>
+                                <td class="p-2">{cell(|p| format!("{:.0}%", p.percent))}</td>
+                                <td class="p-2">{cell(|p| p.remaining.to_string())}</td>
+                                <td class="p-2">{cell(|p| p.projection.map(|m| m.to_string()).unwrap_or_default())}</td>
+                            </Show>
+                        </tr>
+                    }
+                }/>
             </tbody>
+            <tfoot class="font-bold">
+                <tr>
+                    <td class="p-2 pl-8">{locale.tr("summary-month-total")}</td>
+                    {PaymentMethod::ALL.map(|method| view! {
+                        <td class="p-2">{move || month_totals.with(|t| t.method(method))}</td>
+                    }).collect_view()}
+                    <Show when=has_unsplit>
+                        <td class="p-2">{move || month_totals.with(|t| t.unsplit.to_string())}</td>
+                    </Show>
+                    {move || shift_columns().iter().map(|name| view! {
+                        <td class="p-2">{month_totals.with(|t| t.shift(name))}</td>
+                    }).collect_view()}
+                    <td class="p-2">{move || month_totals.with(|t| t.revenue.to_string())}</td>
+                    <Show when=has_plans>
+                        <td class="p-2" colspan="4"></td>
+                    </Show>
+                </tr>
+            </tfoot>
         </table>
     }
 }
@@ -1,10 +1,10 @@
-use common::{perms::VIEW_ALL, user::User, IdType};
+use common::{models::entry::month_range, perms::VIEW_ALL, user::User, IdType};
 use leptos::*;
 use leptos_router::{use_query, Params};
 
 use crate::{
-    calendar::Calendar, dropdown::Dropdown, loading::Loading, reports::ReportsList,
-    users::user_name_short,
+    calendar::Calendar, dropdown::Dropdown, i18n::use_locale, live::use_live_updates,
+    loading::Loading, reports::ReportsList, users::user_name_short,
 };
 
 #[derive(Params, PartialEq)]
@@ -16,6 +16,8 @@ struct ReportQuery {
 pub fn ReportsViewer() -> impl IntoView {
     let params = use_query::<ReportQuery>();
 
+    let locale = use_locale();
+
     let list_users = create_server_action::<common::handlers::ListUsers>();
 
     let app_user = use_context::<Signal<User>>().unwrap();
@@ -58,7 +60,7 @@ pub fn ReportsViewer() -> impl IntoView {
 
                             view!{<Dropdown
                                 name="user"
-                                label_text="Отчеты менеджера:"
+                                label_text=locale.tr("reports-manager")
                                 options={options}
                                 current_option=rw_view_user
                             />}
@@ -74,10 +76,11 @@ pub fn ReportsViewer() -> impl IntoView {
 #[component]
 fn ReportUserDates(#[prop(into)] view_user: Signal<Option<IdType>>) -> impl IntoView {
     let list_dates = create_server_action::<common::handlers::ListDates>();
+    let live_updates = use_live_updates();
 
     let user_dates = create_local_resource(
-        move || (list_dates.version().get(), view_user()),
-        move |(_, view_user)| common::handlers::list_dates(view_user),
+        move || (list_dates.version().get(), live_updates(), view_user()),
+        move |(_, _, view_user)| common::handlers::list_dates(view_user),
     );
 
     let rw_month = create_rw_signal(None);
@@ -112,6 +115,7 @@ fn ReportUserDates(#[prop(into)] view_user: Signal<Option<IdType>>) -> impl Into
                     }
                 }}
             </div>
+            <StatementForm year=rw_year month=rw_month view_user=view_user/>
             {move || {
                 let year = Signal::derive(move || rw_year().unwrap_or_default());
                 let month = Signal::derive(move || rw_month().unwrap_or_default());
@@ -122,3 +126,59 @@ fn ReportUserDates(#[prop(into)] view_user: Signal<Option<IdType>>) -> impl Into
         </Transition>
     }
 }
+
+/// Period and manager of a PDF statement, the selected month's by default. Submitted
+/// as a plain form so the browser downloads the file.
+#[component]
+fn StatementForm(
+    #[prop(into)] year: Signal<Option<i32>>,
+    #[prop(into)] month: Signal<Option<u32>>,
+    #[prop(into)] view_user: Signal<Option<IdType>>,
+) -> impl IntoView {
+    let locale = use_locale();
+
+    let app_user = use_context::<Signal<User>>().unwrap();
+    let admin_permissions_guard = Signal::derive(move || app_user().permissions.contains(VIEW_ALL));
+
+    let range = Signal::derive(move || year().zip(month()).map(|(y, m)| month_range(y, m)));
+    let from = move || {
+        range()
+            .map(|(from, _)| from.format("%Y-%m-%d").to_string())
+            .unwrap_or_default()
+    };
+    let to = move || {
+        range()
+            .map(|(_, to)| to.format("%Y-%m-%d").to_string())
+            .unwrap_or_default()
+    };
+    let user_id = move || view_user().map(|id| id.to_string()).unwrap_or_default();
+
+    view! {
+        <form method="get" action="/statements.pdf" class="w-full flex flex-wrap items-center gap-2 bg-slate-50 dark:bg-slate-700 px-4 pb-4">
+            <span class="text-lg">{locale.tr("statement-section")}</span>
+            <label>
+                {locale.tr("statement-from")}
+                " "
+                <input type="date" name="from" required prop:value=from class="px-2 py-1 rounded !bg-transparent border border-slate-500"/>
+            </label>
+            <label>
+                {locale.tr("statement-to")}
+                " "
+                <input type="date" name="to" required prop:value=to class="px-2 py-1 rounded !bg-transparent border border-slate-500"/>
+            </label>
+            <Show
+                when=admin_permissions_guard
+                fallback=move || view! { <input type="hidden" name="user_id" value=user_id/> }
+            >
+                <select name="user_id" class="px-2 py-1 rounded !bg-transparent border border-slate-500">
+                    <option value=user_id>{locale.tr("statement-selected")}</option>
+                    <option value="">{locale.tr("statement-company")}</option>
+                </select>
+            </Show>
+            <button type="submit" class="bg-indigo-100 dark:bg-indigo-800 px-2 py-1 border border-solid border-slate-500 rounded">
+                <i class="fa-solid fa-file-pdf pr-2"></i>
+                {locale.tr("statement-download")}
+            </button>
+        </form>
+    }
+}
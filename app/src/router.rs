@@ -3,18 +3,27 @@ use leptos::*;
 use leptos_router::*;
 
 use crate::{
+    anomalies::Anomalies,
     dashboard::Dashboard,
     error_template::{AppError, ErrorTemplate},
     home::HomePage,
     login::Login,
+    plans::Plans,
+    rates::ExchangeRates,
     reports::{EditReport, ReportsViewer},
     users::{EditUser, Users},
 };
 
 #[component]
 pub fn AppRouter(
-    #[prop(into)] user: Resource<(usize, usize), Result<Option<User>, ServerFnError>>,
-    #[prop(into)] login: Action<common::handlers::Login, Result<(), ServerFnError>>,
+    #[prop(into)] user: Resource<
+        (usize, usize),
+        Result<Option<User>, ServerFnError<common::error::AppError>>,
+    >,
+    #[prop(into)] login: Action<
+        common::handlers::Login,
+        Result<(), ServerFnError<common::error::AppError>>,
+    >,
 ) -> impl IntoView {
     let auth_guard = move || {
         user.with(|s| {
@@ -64,6 +73,9 @@ pub fn AppRouter(
                                 <Route path="reports" view=ReportsViewer/>
                                 <Route path="reports/new-report" view=EditReport/>
                                 <Route path="reports/:id" view=EditReport/>
+                                <Route path="rates" view=ExchangeRates/>
+                                <Route path="plans" view=Plans/>
+                                <Route path="anomalies" view=Anomalies/>
                                 <Route path="users" view=Users/>
                                 <Route path="users/new-user" view=EditUser/>
                                 <Route path="users/:id" view=EditUser/>
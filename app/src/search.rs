@@ -0,0 +1,208 @@
+use std::time::Duration;
+
+use common::{
+    i18n::{Locale, Message},
+    search::{SearchHit, SearchKind, SearchResults, MIN_QUERY_CHARS},
+};
+use leptos::{leptos_dom::helpers::TimeoutHandle, *};
+use leptos_router::use_navigate;
+
+use crate::i18n::use_locale;
+
+/// Pause in typing before the query is sent.
+const DEBOUNCE: Duration = Duration::from_millis(250);
+
+fn group_key(kind: SearchKind) -> &'static str {
+    match kind {
+        SearchKind::Manager => "search-managers",
+        SearchKind::Address => "search-addresses",
+        SearchKind::Date => "search-dates",
+    }
+}
+
+fn hit_detail(locale: Locale, hit: &SearchHit) -> String {
+    let date = hit.date.map(|d| d.format("%d.%m.%Y").to_string());
+
+    match hit.kind {
+        SearchKind::Manager => hit.detail.clone().unwrap_or_default(),
+        SearchKind::Address => locale.message(
+            &Message::new("search-address-reports")
+                .arg("count", hit.reports.unwrap_or_default())
+                .arg("date", date.unwrap_or_default()),
+        ),
+        SearchKind::Date => [date, hit.detail.clone()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+/// Search box of the page header with the hits in a dropdown, walked with the arrow keys
+/// and opened with Enter.
+#[component]
+pub fn GlobalSearch() -> impl IntoView {
+    let locale = use_locale();
+    let navigate = use_navigate();
+
+    let (input, set_input) = create_signal(String::new());
+    let (query, set_query) = create_signal(String::new());
+    let (open, set_open) = create_signal(false);
+    let selected = create_rw_signal(None::<usize>);
+    let pending = store_value(None::<TimeoutHandle>);
+
+    let results = create_local_resource(query, |query| async move {
+        match query.trim().chars().count() < MIN_QUERY_CHARS {
+            true => Ok(SearchResults::default()),
+            false => common::handlers::search(query).await,
+        }
+    });
+    let hits = move || {
+        results
+            .get()
+            .and_then(Result::ok)
+            .map(|r| r.hits().into_iter().cloned().collect::<Vec<_>>())
+            .unwrap_or_default()
+    };
+    create_effect(move |_| {
+        results.track();
+        selected.set(None);
+    });
+
+    let close = move || {
+        set_open(false);
+        selected.set(None);
+    };
+    let reset = move || {
+        close();
+        set_input(String::new());
+        set_query(String::new());
+    };
+
+    let on_input = move |ev| {
+        let value = event_target_value(&ev);
+        set_input(value.clone());
+        set_open(true);
+        if let Some(handle) = pending.get_value() {
+            handle.clear();
+        }
+        pending.set_value(set_timeout_with_handle(move || set_query(value), DEBOUNCE).ok());
+    };
+
+    let on_keydown = move |ev: ev::KeyboardEvent| {
+        let hits = hits();
+        let count = hits.len();
+
+        match ev.key().as_str() {
+            "ArrowDown" if count > 0 => {
+                ev.prevent_default();
+                set_open(true);
+                selected.update(|s| *s = Some(s.map_or(0, |i| (i + 1) % count)));
+            }
+            "ArrowUp" if count > 0 => {
+                ev.prevent_default();
+                set_open(true);
+                selected.update(|s| *s = Some(s.map_or(count - 1, |i| (i + count - 1) % count)));
+            }
+            "Enter" => {
+                let hit = selected.get().and_then(|i| hits.get(i)).or(hits.first());
+                if let Some(hit) = hit {
+                    ev.prevent_default();
+                    navigate(&hit.href(), Default::default());
+                    reset();
+                }
+            }
+            "Escape" => close(),
+            _ => {}
+        }
+    };
+
+    let hit_view = move |index: usize, hit: &SearchHit| {
+        let is_selected = move || selected() == Some(index);
+
+        view! {
+            <li
+                id=format!("search-hit-{index}")
+                role="option"
+                aria-selected=move || is_selected().to_string()
+                class=("bg-indigo-100", is_selected)
+                class=("dark:bg-indigo-900", is_selected)
+                on:mouseenter=move |_| selected.set(Some(index))
+            >
+                <a href=hit.href() on:click=move |_| reset() class="block px-3 py-2">
+                    <span class="block">{hit.title.clone()}</span>
+                    <small class="block text-slate-500">{hit_detail(locale, hit)}</small>
+                </a>
+            </li>
+        }
+    };
+
+    let show_hits = move || open() && input.with(|i| i.trim().chars().count() >= MIN_QUERY_CHARS);
+
+    let dropdown = move || match results.get() {
+        None => view! {
+            <li class="px-3 py-2 text-slate-500">{locale.tr("search-searching")}</li>
+        }
+        .into_view(),
+        Some(Err(err)) => view! {
+            <li class="px-3 py-2 text-pink-600">{locale.error(&err)}</li>
+        }
+        .into_view(),
+        Some(Ok(results)) if results.is_empty() => view! {
+            <li class="px-3 py-2 text-slate-500">{locale.tr("search-nothing")}</li>
+        }
+        .into_view(),
+        Some(Ok(results)) => {
+            let mut index = 0;
+            results
+                .groups()
+                .into_iter()
+                .map(|(kind, hits)| {
+                    let items = hits
+                        .iter()
+                        .map(|hit| {
+                            index += 1;
+                            hit_view(index - 1, hit)
+                        })
+                        .collect_view();
+                    view! {
+                        <li role="presentation" class="px-3 pt-2 text-xs uppercase text-slate-500">
+                            {locale.tr(group_key(kind))}
+                        </li>
+                        {items}
+                    }
+                })
+                .collect_view()
+        }
+    };
+
+    view! {
+        <div class="relative mr-auto w-full max-w-md">
+            <input
+                type="search"
+                role="combobox"
+                aria-autocomplete="list"
+                aria-controls="global-search-hits"
+                aria-expanded=move || open().to_string()
+                aria-activedescendant=move || selected().map(|i| format!("search-hit-{i}"))
+                placeholder=locale.tr("search-placeholder")
+                prop:value=input
+                on:input=on_input
+                on:keydown=on_keydown
+                on:focus=move |_| set_open(true)
+                on:blur=move |_| close()
+                class="w-full px-3 py-1 rounded !bg-transparent border border-slate-500"/>
+            <Show when=show_hits>
+                // keeps the focus in the input while a hit is clicked
+                <ul
+                    id="global-search-hits"
+                    role="listbox"
+                    on:mousedown=|ev| ev.prevent_default()
+                    class="absolute z-20 mt-1 w-full max-h-96 overflow-y-auto rounded border border-slate-500 bg-slate-50 dark:bg-slate-700 shadow-lg"
+                >
+                    {dropdown}
+                </ul>
+            </Show>
+        </div>
+    }
+}
@@ -1,13 +1,20 @@
 use common::{
-    handlers::{NewUser, UpdateUser},
+    digest::DigestFrequency,
+    handlers::{NewUser, SaveDigests, UpdateUser},
+    i18n::{Locale, Message},
     perms::MANAGE_USERS,
+    tz,
     user::User,
     IdType,
 };
 use leptos::*;
 use leptos_router::{use_params, ActionForm, Params};
 
-use crate::loading::Loading;
+use crate::{
+    form::{is_form_error, FieldError},
+    i18n::use_locale,
+    loading::Loading,
+};
 
 #[derive(Params, PartialEq)]
 struct EditUserParams {
@@ -17,24 +24,36 @@ struct EditUserParams {
 #[component]
 pub fn EditUser() -> impl IntoView {
     let params = use_params::<EditUserParams>();
+    let locale = use_locale();
 
     let create_user = create_server_action::<NewUser>();
     let update_user = create_server_action::<UpdateUser>();
 
     let create_value = create_user.value();
-    let has_create_error = move || create_value.with(|val| matches!(val, Some(Err(_))));
+    let has_create_error = move || create_value.with(is_form_error);
     let update_value = update_user.value();
-    let has_update_error = move || update_value.with(|val| matches!(val, Some(Err(_))));
+    let has_update_error = move || update_value.with(is_form_error);
+    let action_value = Signal::derive(move || {
+        if params
+            .with(|p| p.as_ref().map(|p| p.id).ok().flatten())
+            .is_some()
+        {
+            update_value()
+        } else {
+            create_value()
+        }
+    });
 
-    let user_data = create_resource(
+    let user_result = create_resource(
         move || params.with(|p| p.as_ref().map(|p| p.id).ok().flatten()),
         move |id: Option<IdType>| async move {
             match id {
-                Some(id) => common::handlers::get_user(id).await.unwrap_or_default(),
-                None => User::default(),
+                Some(id) => common::handlers::get_user(id).await,
+                None => Ok(User::default()),
             }
         },
     );
+    let user_data = move || user_result().and_then(Result::ok);
 
     let current_user = use_context::<Signal<User>>().unwrap();
 
@@ -46,6 +65,21 @@ pub fn EditUser() -> impl IntoView {
         }) && u.permissions.contains(MANAGE_USERS)
     };
 
+    // the language only switches on a full page load, the router would keep the old texts
+    let locale_select = create_node_ref::<html::Select>();
+    create_effect(move |_| {
+        if !matches!(update_value.get(), Some(Ok(()))) {
+            return;
+        }
+        let user = untrack(user_data).unwrap_or_default();
+        let chosen = locale_select
+            .get_untracked()
+            .map(|select| Locale::parse_choice(Some(&select.value())).ok().flatten());
+        if user.id == current_user.get_untracked().id && chosen.is_some_and(|c| c != user.locale) {
+            _ = window().location().set_href("/");
+        }
+    });
+
     let is_admin = move || {
         user_data()
             .unwrap_or_default()
@@ -56,72 +90,117 @@ pub fn EditUser() -> impl IntoView {
     let form_content = move || {
         view! {
             <hr class="my-2"/>
-            <h3 class="text-lg mb-2">"Данные пользователя:"</h3>
+            <h3 class="text-lg mb-2">{locale.tr("user-section")}</h3>
             <label class="w-full pb-8 flex flex-col-reverse">
                 <input
                     type="text"
-                    placeholder="Фамилия"
+                    placeholder=locale.tr("field-family-name")
                     maxlength="250"
                     name="family_name"
                     value=move || user_data().unwrap_or_default().family_name
                     autocomplete="off"
                     class="w-full text-xl rounded p-4 !bg-transparent !text-inherit dark:!text-inherit border border-slate-500"/>
-                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">"Фамилия:"</span>
+                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">{format!("{}:", locale.tr("field-family-name"))}</span>
             </label>
             <label class="w-full pb-8 flex flex-col-reverse">
                 <input
                     type="text"
-                    placeholder="Имя"
+                    placeholder=locale.tr("field-name")
                     maxlength="250"
                     name="name"
                     value=move || user_data().unwrap_or_default().name
                     autocomplete="off"
                     class="w-full text-xl rounded p-4 !bg-transparent !text-inherit dark:!text-inherit border border-slate-500"/>
-                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">"Имя:"</span>
+                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">{format!("{}:", locale.tr("field-name"))}</span>
             </label>
             <label class="w-full pb-8 flex flex-col-reverse">
                 <input
                     type="text"
-                    placeholder="Отчество"
+                    placeholder=locale.tr("field-patronym")
                     maxlength="250"
                     name="patronym"
                     value=move || user_data().unwrap_or_default().patronym
                     autocomplete="off"
                     class="w-full text-xl rounded p-4 !bg-transparent !text-inherit dark:!text-inherit border border-slate-500"/>
-                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">"Отчество:"</span>
+                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">{format!("{}:", locale.tr("field-patronym"))}</span>
             </label>
             <hr class="my-2"/>
-            <h3 class="text-lg mb-2">"Данные для входа:"</h3>
+            <h3 class="text-lg mb-2">{locale.tr("user-login-section")}</h3>
 
             <label class="w-full pb-8 flex flex-col-reverse">
                 <input
                     type="text"
-                    placeholder="Логин"
+                    placeholder=locale.tr("field-username")
                     maxlength="250"
                     name="username"
                     value=move || user_data().unwrap_or_default().username
                     autocomplete="off"
                     class="w-full text-xl rounded p-4 !bg-transparent !text-inherit dark:!text-inherit border border-slate-500"/>
-                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">"Логин:"</span>
+                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">{format!("{}:", locale.tr("field-username"))}</span>
             </label>
+            <FieldError value=action_value field="username"/>
 
             <label class="w-full pb-8 flex flex-col-reverse">
                 <input type="password"
-                    placeholder="Пароль"
+                    placeholder=locale.tr("field-password")
                     name="password"
                     autocomplete="new-password"
                     class="text-input-autofill w-full text-xl rounded p-4 !bg-transparent !text-inherit dark:!text-inherit border border-slate-500"/>
-                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">"Пароль:"</span>
+                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">{format!("{}:", locale.tr("field-password"))}</span>
             </label>
+            <FieldError value=action_value field="password"/>
+
+            <label class="w-full pb-8 flex flex-col-reverse">
+                <select
+                    name="settings[locale]"
+                    node_ref=locale_select
+                    class="w-full text-xl rounded p-4 !bg-transparent !text-inherit dark:!text-inherit border border-slate-500"
+                >
+                    <option value="" selected=move || user_data().unwrap_or_default().locale.is_none()>
+                        {locale.tr("user-locale-browser")}
+                    </option>
+                    {Locale::ALL
+                        .into_iter()
+                        .map(|option| view! {
+                            <option
+                                value=option.code()
+                                selected=move || user_data().unwrap_or_default().locale == Some(option)
+                            >
+                                {option.native_name()}
+                            </option>
+                        })
+                        .collect_view()}
+                </select>
+                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">{locale.tr("user-locale")}</span>
+            </label>
+            <FieldError value=action_value field="locale"/>
+
+            <label class="w-full pb-8 flex flex-col-reverse">
+                <small class="block pt-1 px-1 text-slate-500">
+                    {locale.message(&Message::new("user-timezone-hint").arg("default", tz::DEFAULT.name()))}
+                </small>
+                <input
+                    type="text"
+                    name="settings[timezone]"
+                    list="timezones"
+                    placeholder=tz::DEFAULT.name()
+                    prop:value=move || user_data().unwrap_or_default().timezone.map(|tz| tz.name()).unwrap_or_default()
+                    class="w-full text-xl rounded p-4 !bg-transparent !text-inherit dark:!text-inherit border border-slate-500"/>
+                <datalist id="timezones">
+                    {tz::SUGGESTED.map(|option| view! {<option value=option.name()/>}).collect_view()}
+                </datalist>
+                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">{locale.tr("user-timezone")}</span>
+            </label>
+            <FieldError value=action_value field="timezone"/>
 
             <Show when=can_change_perms>
                 <hr class="my-2"/>
-                <h3 class="text-lg mb-2">"Уровень доступа:"</h3>
+                <h3 class="text-lg mb-2">{locale.tr("user-access-section")}</h3>
                 <label class="w-full pb-8 flex items-center">
                     <input type="checkbox" checked={is_admin} name="is_admin" class="h-6 w-6"/>
                     <span class="pl-4 flex flex-col">
-                        <span class="block mb-1">"Администратор"</span>
-                        <small class="block">"Администратор может добавлять новых пользователей и просматривать всю отчетность."</small>
+                        <span class="block mb-1">{locale.tr("user-admin")}</span>
+                        <small class="block">{locale.tr("user-admin-hint")}</small>
                     </span>
                 </label>
             </Show>
@@ -130,49 +209,113 @@ pub fn EditUser() -> impl IntoView {
 
     view! {
         <Transition fallback=Loading>
-        {move || params.with(|params| { match params.as_ref().map(|p| p.id).ok().flatten() {
-            Some(id) => {
+        {move || params.with(|params| { match (params.as_ref().map(|p| p.id).ok().flatten(), user_result()) {
+            (Some(_), Some(Err(err))) => Err(err),
+            (Some(id), _) => Ok({
                 view! {
                     <ActionForm action=update_user
                         class="p-8 m-8 bg-slate-200 dark:bg-slate-800 rounded-lg"
                         attributes=vec![("autocomplete", Attribute::String("off".into()))]
                     >
-                        <h1 class="text-2xl mb-12">"Редактирование пользователя"</h1>
+                        <h1 class="text-2xl mb-12">{locale.tr("user-edit-title")}</h1>
                         <input type="hidden" name="id" value=id.to_string()/>
                         {form_content}
                         <Show when=has_update_error>
                             {move || {
-                                let err = format!("Ошибка: {}", update_value().unwrap().unwrap_err());
+                                let err = locale.error(&update_value().unwrap().unwrap_err());
                                 view! {<p class="text-pink-600 pb-2">{err}</p>}
                             }}
                         </Show>
                         <button type="submit" class="w-full mb-4 text-xl p-4 border border-solid border-slate-500 rounded">
-                            "Сохранить"
+                            {locale.tr("action-save")}
                         </button>
                     </ActionForm>
+                    <DigestSubscriptions user_id=id/>
                 }.into_view()
-            }
-            None => {
+            }),
+            (None, _) => Ok({
                 view! {
                     <ActionForm action=create_user
                         class="p-8 m-8 bg-slate-200 dark:bg-slate-800 rounded-lg"
                         attributes=vec![("autocomplete", Attribute::String("off".into()))]
                     >
-                        <h1 class="text-2xl mb-12">"Добавление нового пользователя"</h1>
+                        <h1 class="text-2xl mb-12">{locale.tr("user-new-title")}</h1>
                         {form_content}
                         <Show when=has_create_error>
                             {move || {
-                                let err = format!("Ошибка: {}", create_value().unwrap().unwrap_err());
+                                let err = locale.error(&create_value().unwrap().unwrap_err());
                                 view! {<p class="text-pink-600 pb-2">{err}</p>}
                             }}
                         </Show>
                         <button type="submit" class="w-full mb-4 text-xl p-4 border border-solid border-slate-500 rounded">
-                            "Добавить"
+                            {locale.tr("action-add")}
                         </button>
                     </ActionForm>
                 }.into_view()
-            }
+            }),
         }})}
     </Transition>
     }
 }
+
+/// Digests mailed to the user, saved apart from the rest of the user.
+#[component]
+fn DigestSubscriptions(user_id: IdType) -> impl IntoView {
+    let locale = use_locale();
+
+    let save_digests = create_server_action::<SaveDigests>();
+    let save_value = save_digests.value();
+    let has_save_error = move || save_value.with(is_form_error);
+
+    let subscriptions = create_resource(
+        move || save_digests.version().get(),
+        move |_| common::handlers::get_digests(user_id),
+    );
+    let subscriptions = move || subscriptions().and_then(Result::ok).unwrap_or_default();
+    let email = move || {
+        subscriptions()
+            .first()
+            .map(|s| s.email.clone())
+            .unwrap_or_default()
+    };
+
+    view! {
+        <ActionForm action=save_digests class="p-8 m-8 bg-slate-200 dark:bg-slate-800 rounded-lg">
+            <h3 class="text-lg mb-2">{locale.tr("digest-section")}</h3>
+            <input type="hidden" name="user_id" value=user_id.to_string()/>
+            <label class="w-full pb-8 flex flex-col-reverse">
+                <small class="block pt-1 px-1 text-slate-500">{locale.tr("digest-hint")}</small>
+                <input
+                    type="email"
+                    name="digests[email]"
+                    placeholder=locale.tr("digest-email")
+                    prop:value=email
+                    class="w-full text-xl rounded p-4 !bg-transparent !text-inherit dark:!text-inherit border border-slate-500"/>
+                <span class="z-10 ml-3 px-1 mr-auto -mb-3 bg-slate-200 dark:bg-slate-800 inline-block">{format!("{}:", locale.tr("digest-email"))}</span>
+            </label>
+            <FieldError value=save_value field="email"/>
+            {DigestFrequency::ALL.map(|frequency| view! {
+                <label class="w-full pb-4 flex items-center">
+                    <input
+                        type="checkbox"
+                        name=format!("digests[{}]", frequency.code())
+                        prop:checked=move || subscriptions().iter().any(|s| s.frequency == frequency)
+                        class="h-6 w-6"/>
+                    <span class="pl-4">{frequency.label(locale)}</span>
+                </label>
+            }).collect_view()}
+            <Show when=move || matches!(save_value(), Some(Ok(())))>
+                <p class="text-slate-500 pb-2">{locale.tr("digest-saved")}</p>
+            </Show>
+            <Show when=has_save_error>
+                {move || {
+                    let err = locale.error(&save_value().unwrap().unwrap_err());
+                    view! {<p class="text-pink-600 pb-2">{err}</p>}
+                }}
+            </Show>
+            <button type="submit" class="w-full mt-4 mb-4 text-xl p-4 border border-solid border-slate-500 rounded">
+                {locale.tr("action-save")}
+            </button>
+        </ActionForm>
+    }
+}
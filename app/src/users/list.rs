@@ -1,60 +1,226 @@
-use common::handlers::ListUsers;
+use common::{
+    handlers::SetUserActive,
+    i18n::Message,
+    perms,
+    user::{UserSort, USERS_PER_PAGE},
+};
 use leptos::*;
-use leptos_router::A;
+use leptos_router::{use_query_map, ActionForm, Form, A};
 
-use crate::loading::Loading;
+use crate::{i18n::use_locale, loading::Loading};
+
+fn permission_key(token: &str) -> &'static str {
+    match token {
+        perms::MANAGE_USERS => "perm-manage-users",
+        perms::VIEW_ALL => "perm-view-all",
+        perms::VIEW_OWNED => "perm-view-owned",
+        _ => "perm-edit-owned",
+    }
+}
 
 #[component]
 pub fn Users() -> impl IntoView {
-    let list_users = create_server_action::<ListUsers>();
+    let locale = use_locale();
+
+    // the list state lives in the URL, so it survives reloads and can be shared
+    let query = use_query_map();
+    let param = move |name: &str| query.with(|q| q.get(name).filter(|v| !v.is_empty()).cloned());
+    let search = move || param("q");
+    let permission = move || param("permission");
+    let active = move || param("active").and_then(|v| v.parse::<bool>().ok());
+    let sort = move || {
+        param("sort")
+            .and_then(|v| v.parse::<UserSort>().ok())
+            .unwrap_or_default()
+    };
+    let descending = move || param("order").as_deref() == Some("desc");
+    // pages count from 1 in the URL
+    let page = move || {
+        param("page")
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(1)
+            .max(1)
+    };
+
+    let set_active = create_server_action::<SetUserActive>();
+    let set_active_value = set_active.value();
 
     let users = create_resource(
-        move || list_users.version().get(),
-        move |_| common::handlers::list_users(false),
+        move || {
+            (
+                set_active.version().get(),
+                search(),
+                permission(),
+                active(),
+                sort(),
+                descending(),
+                page(),
+            )
+        },
+        move |(_, search, permission, active, sort, descending, page)| {
+            common::handlers::list_users_page(
+                search,
+                permission,
+                active,
+                sort,
+                descending,
+                page - 1,
+            )
+        },
     );
 
+    let href_with = move |changes: &[(&str, String)]| {
+        let mut params = query.get();
+        for (name, value) in changes {
+            params.insert(name.to_string(), value.clone());
+        }
+        format!("/users{}", params.to_query_string())
+    };
+
+    let sort_header = move |column: UserSort, key: &'static str, class: &'static str| {
+        let href = move || {
+            let order = match sort() == column && !descending() {
+                true => "desc",
+                false => "asc",
+            };
+            href_with(&[
+                ("sort", column.code().to_string()),
+                ("order", order.to_string()),
+                ("page", "1".to_string()),
+            ])
+        };
+        let icon = move || match (sort() == column, descending()) {
+            (true, false) => "fa-solid fa-sort-up ml-1",
+            (true, true) => "fa-solid fa-sort-down ml-1",
+            (false, _) => "fa-solid fa-sort ml-1 text-slate-400",
+        };
+
+        view! {
+            <th class=class>
+                <A href=href class="text-indigo-500">
+                    {locale.tr(key)}
+                    <i class=icon></i>
+                </A>
+            </th>
+        }
+    };
+
     view! {
+        <div class="w-full flex flex-wrap items-center gap-4 bg-slate-50 dark:bg-slate-700 pt-4 pb-2 px-6">
+            <A href="new-user" class="bg-indigo-100 dark:bg-indigo-800 text-lg px-2 py-1 border border-solid border-slate-500 rounded">
+                {locale.tr("users-add")}
+            </A>
+            <Form method="GET" action="" class="flex flex-wrap items-center gap-2 ml-auto">
+                <input type="hidden" name="sort" value=move || sort().code()/>
+                <input type="hidden" name="order" value=move || if descending() { "desc" } else { "asc" }/>
+                <input
+                    type="search"
+                    name="q"
+                    placeholder=locale.tr("users-search")
+                    prop:value=move || search().unwrap_or_default()
+                    class="px-2 py-1 rounded !bg-transparent border border-slate-500"/>
+                <select name="permission" class="px-2 py-1 rounded !bg-transparent border border-slate-500">
+                    <option value="" selected=move || permission().is_none()>{locale.tr("users-any-permission")}</option>
+                    {perms::ALL.map(|token| view! {
+                        <option value=token selected=move || permission().as_deref() == Some(token)>
+                            {locale.tr(permission_key(token))}
+                        </option>
+                    }).collect_view()}
+                </select>
+                <select name="active" class="px-2 py-1 rounded !bg-transparent border border-slate-500">
+                    <option value="" selected=move || active().is_none()>{locale.tr("users-any-state")}</option>
+                    <option value="true" selected=move || active() == Some(true)>{locale.tr("users-active")}</option>
+                    <option value="false" selected=move || active() == Some(false)>{locale.tr("users-inactive")}</option>
+                </select>
+                <button type="submit" class="bg-indigo-100 dark:bg-indigo-800 px-2 py-1 border border-solid border-slate-500 rounded">
+                    <i title=locale.tr("action-search") class="fa-solid fa-magnifying-glass"></i>
+                </button>
+            </Form>
+        </div>
+        {move || set_active_value().and_then(Result::err).map(|err| view! {
+            <p class="px-6 py-2 text-pink-600">{locale.error(&err)}</p>
+        })}
         <Transition fallback=Loading>
-            <div class="w-full bg-slate-50 dark:bg-slate-700 pt-4 pb-2 px-6">
-                <A href="new-user" class="bg-indigo-100 dark:bg-indigo-800 text-lg px-2 py-1 border border-solid border-slate-500 rounded">
-                    {"Добавить пользователя"}
-                </A>
-            </div>
             {move || match users() {
                     Some(Ok(users)) => {
+                        let total = users.total;
+                        let from = (page() - 1) * USERS_PER_PAGE;
+                        let to = from + users.users.len() as i64;
+                        let has_previous = page() > 1;
+                        let has_next = to < total;
+
                         view!{
                             <table class="w-full">
                                 <thead class="border-solid border-b border-slate-500 font-bold text-left">
                                     <tr>
-                                        <th class="p-2 pl-8">{"Фамилия"}</th>
-                                        <th class="p-2">{"Имя"}</th>
-                                        <th class="p-2">{"Отчество"}</th>
-                                        <th class="p-2">{"Логин"}</th>
+                                        {sort_header(UserSort::FamilyName, "field-family-name", "p-2 pl-8")}
+                                        <th class="p-2">{locale.tr("field-name")}</th>
+                                        <th class="p-2">{locale.tr("field-patronym")}</th>
+                                        {sort_header(UserSort::Username, "field-username", "p-2")}
+                                        {sort_header(UserSort::Created, "users-created", "p-2")}
+                                        <th class="p-2">{locale.tr("users-state")}</th>
                                         <th class="p-2 pr-8 text-right">
                                             <i class="fa-solid fa-ellipsis-vertical"></i>
                                         </th>
                                     </tr>
                                 </thead>
                                 <tbody>
-                                    <For each=move || users.clone() key=|u| u.id let:user>
-                                        <tr class="border-solid border-b border-slate-500">
+                                    <For each=move || users.users.clone() key=|u| (u.id, u.active) let:user>
+                                        <tr class="border-solid border-b border-slate-500" class=("text-slate-500", !user.active)>
                                             <td class="p-2 pl-8">{user.family_name}</td>
                                             <td class="p-2">{user.name}</td>
                                             <td class="p-2">{user.patronym}</td>
                                             <td class="p-2">{user.username}</td>
-                                            <td class="p-2 pr-6 text-right">
+                                            <td class="p-2">{user.created_at.format("%d.%m.%Y").to_string()}</td>
+                                            <td class="p-2">
+                                                {locale.tr(if user.active { "users-active" } else { "users-inactive" })}
+                                            </td>
+                                            <td class="p-2 pr-6 text-right whitespace-nowrap">
+                                                <ActionForm action=set_active class="inline">
+                                                    <input type="hidden" name="id" value=user.id.to_string()/>
+                                                    <input type="hidden" name="active" value=(!user.active).to_string()/>
+                                                    <button type="submit" class="px-2 py-1 mr-2 border border-solid border-slate-500 rounded-sm">
+                                                        {match user.active {
+                                                            true => view! {<i title=locale.tr("action-deactivate") class="fa-solid fa-user-slash"></i>},
+                                                            false => view! {<i title=locale.tr("action-activate") class="fa-solid fa-user-check"></i>},
+                                                        }}
+                                                    </button>
+                                                </ActionForm>
                                                 <A href=format!("{}",user.id) class="px-2 py-1 border border-solid border-slate-500 rounded-sm">
-                                                    <i title="Редактировать" class="fa-solid fa-pen-to-square"></i>
+                                                    <i title=locale.tr("action-edit") class="fa-solid fa-pen-to-square"></i>
                                                 </A>
                                             </td>
                                         </tr>
                                     </For>
                                 </tbody>
                             </table>
+                            <div class="w-full flex items-center gap-4 px-8 py-4">
+                                <span class="mr-auto">
+                                    {match total {
+                                        0 => locale.tr("users-none"),
+                                        _ => locale.message(
+                                            &Message::new("users-shown")
+                                                .arg("from", from + 1)
+                                                .arg("to", to)
+                                                .arg("total", total),
+                                        ),
+                                    }}
+                                </span>
+                                <Show when=move || has_previous>
+                                    <A href=move || href_with(&[("page", (page() - 1).to_string())]) class="px-2 py-1 border border-solid border-slate-500 rounded">
+                                        <i title=locale.tr("action-previous-page") class="fa-solid fa-chevron-left"></i>
+                                    </A>
+                                </Show>
+                                <Show when=move || has_next>
+                                    <A href=move || href_with(&[("page", (page() + 1).to_string())]) class="px-2 py-1 border border-solid border-slate-500 rounded">
+                                        <i title=locale.tr("action-next-page") class="fa-solid fa-chevron-right"></i>
+                                    </A>
+                                </Show>
+                            </div>
                         }.into_view()
                     },
                     Some(Err(err)) => {
-                        let err = format!("Ошибка: {}", err);
+                        let err = locale.error(&err);
                         view!{
                             <p class="text-pink-600 pb-2">{err}</p>
                         }.into_view()
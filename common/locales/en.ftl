@@ -0,0 +1,336 @@
+### English.
+
+app-title = Test assignment
+
+## Common
+
+error = Error: { $detail }
+err-request = The request failed: { $detail }
+action-save = Save
+action-save-anyway = Save anyway
+action-add = Add
+action-add-anyway = Add anyway
+action-edit = Edit
+action-delete = Delete
+action-upload = Upload
+action-search = Search
+action-activate = Activate
+action-deactivate = Deactivate
+action-previous-page = Previous page
+action-next-page = Next page
+action-sync = Send now
+action-keep-mine = Keep my version
+action-keep-server = Keep the server version
+field-family-name = Family name
+field-name = Name
+field-patronym = Patronymic
+field-username = Username
+field-password = Password
+field-date = Date
+field-address = Address
+field-revenue = Revenue
+field-currency = Currency
+field-manager = Manager
+field-plan = Plan
+field-shift = Shift
+unread-comments = Unread comments
+month-1 = January
+month-2 = February
+month-3 = March
+month-4 = April
+month-5 = May
+month-6 = June
+month-7 = July
+month-8 = August
+month-9 = September
+month-10 = October
+month-11 = November
+month-12 = December
+size-bytes = { $size } B
+size-kilobytes = { $size } KB
+size-megabytes = { $size } MB
+payment-cash = Cash
+payment-card = Card
+payment-online = Online
+payment-returns = Returns
+
+## Login and menu
+
+login-title = Sign in to start using the app
+login-username = Username:
+login-username-placeholder = Enter your username
+login-password = Password:
+login-password-placeholder = Enter your password
+login-remember = Remember me on this device
+login-submit = Sign in
+logout = Sign out
+menu-summary = Summary
+menu-reports = Reports by manager
+menu-plans = Plans
+menu-anomalies = Unusual reports
+menu-rates = Exchange rates
+menu-users = Managers
+menu-my-reports = My reports
+menu-new-report = Add a report
+search-placeholder = Search managers, addresses, dates
+search-searching = Searching…
+search-nothing = Nothing found
+search-managers = Managers
+search-addresses = Addresses
+search-dates = Reports on the date
+search-address-reports = { $count ->
+    [one] { $count } report
+   *[other] { $count } reports
+}, latest { $date }
+
+## Dashboard
+
+calendar-year = Year:
+calendar-month = Month:
+dashboard-entries = { $count ->
+    [one] Report
+   *[other] Reports
+}
+dashboard-managers = { $count ->
+    [one] Manager
+   *[other] Managers
+}
+missing-own = No reports for: { $dates }
+missing-title = Missing reports
+missing-days = { $count ->
+    [one] { $count } day
+   *[other] { $count } days
+}: { $dates }
+missing-add = Add a report
+
+## Reports
+
+reports-manager = Reports of:
+reports-discussion = Discussion
+summary-unsplit = No breakdown
+summary-completion = Completion
+summary-remaining = Remaining
+summary-projection = Projection
+summary-month-total = Month total
+summary-whole-day = Whole day
+statement-section = PDF statement:
+statement-from = From
+statement-to = To
+statement-selected = Selected manager
+statement-download = Download PDF
+report-edit-title = Edit report
+report-new-title = New report
+report-manager-section = Manager:
+report-section = Report:
+report-address = Location address
+report-breakdown = Revenue by payment method:
+report-shift = Shift:
+report-shift-hint = Leave empty for a whole day report. A shift ending before it starts ends the next day.
+report-shift-start = Start
+report-shift-end = End
+report-shift-label = Shift name
+report-unsplit = The report was saved without a breakdown, revenue: { $revenue }
+report-total = Total: { $total }
+report-currency-default = Location default
+report-anomaly-confirm = { $warning }. Check the amounts or save the report again.
+report-duplicate-open = Open it for editing
+offline-saved = The server can't be reached. The report is saved on this device and will be sent once the connection is back.
+offline-title = Unsent reports
+offline-report = { $address }, { $date }: { $revenue }
+offline-pending = Waiting to be sent
+offline-syncing = Sending…
+offline-conflict = The server has another version of the report: { $revenue }
+offline-failed = Refused by the server: { $error }
+anomaly-manager = Revenue is well { $direction ->
+    [higher] above
+   *[lower] below
+} the manager's usual (median { $median })
+anomaly-location = Revenue is well { $direction ->
+    [higher] above
+   *[lower] below
+} the location's usual (median { $median })
+anomaly-both = Revenue is well { $manager ->
+    [higher] above
+   *[lower] below
+} the manager's usual (median { $manager-median }) and well { $location ->
+    [higher] above
+   *[lower] below
+} the location's usual (median { $location-median })
+anomalies-empty = No unusual reports
+anomalies-reason = Reason
+anomalies-resolve = Checked
+attachments-title = Attachments (Z-reports, receipts, photos):
+attachments-uploaded = { $count ->
+    [one] { $count } file uploaded
+   *[other] { $count } files uploaded
+}
+attachments-empty = No files
+attachments-unnamed = file
+attachments-attach = Attach
+comments-empty = No comments yet
+comments-placeholder = Comment
+comments-reply-placeholder = Reply
+comments-send = Send
+comments-reply = Reply
+comments-edit = Edit
+comments-edited = (edited)
+
+## Plans and rates
+
+plans-month = Month:
+plans-scope = Manager or location
+plans-managers = Managers
+plans-locations = Locations
+plans-target-placeholder = Plan, 1,000,000
+plans-currency-default = Default currency
+plans-own = My plan
+plans-target = Plan: { $amount }
+plans-actual = Actual: { $amount }
+plans-remaining = Remaining: { $amount }
+plans-projection = Projection: { $amount }
+plans-projection-none = Projection: no data
+plans-unconverted = Without an exchange rate: { $amount }
+rates-file = Rates file (CSV: date,currency,rate[,base]):
+rates-imported = { $count ->
+    [one] { $count } rate imported
+   *[other] { $count } rates imported
+}
+rates-reporting-currency = Reporting currency: { $currency }
+rates-rate = Rate
+
+## Users
+
+users-add = Add a user
+users-search = Name or username
+users-any-permission = Any permission
+users-any-state = Any state
+users-active = Active
+users-inactive = Inactive
+users-created = Added
+users-state = State
+users-none = No users found
+users-shown = { $from }–{ $to } of { $total }
+perm-manage-users = Manages users
+perm-view-all = Views all reports
+perm-view-owned = Views own reports
+perm-edit-owned = Files reports
+user-edit-title = Edit user
+user-new-title = New user
+user-section = User:
+user-login-section = Sign-in:
+user-access-section = Access level:
+user-admin = Administrator
+user-admin-hint = Administrators can add new users and view all reports.
+user-locale = Interface language:
+user-locale-browser = Browser default
+user-timezone = Time zone:
+user-timezone-hint = Report dates are checked in it unless the location has its own. Empty means { $default }.
+digest-section = Email digests:
+digest-hint = Summaries of the reports you can see, mailed in the morning.
+digest-email = Email
+digest-daily = Daily, for the day before
+digest-weekly = Mondays, for the previous week
+digest-monthly = On the 1st, for the previous month
+digest-saved = Digest subscriptions saved
+
+## Digests
+
+digest-subject-daily = Daily summary for { $period }
+digest-subject-weekly = Weekly summary for { $period }
+digest-subject-monthly = Monthly summary for { $period }
+digest-title = Revenue for { $period }
+digest-counts = { $entries ->
+    [one] { $entries } report
+   *[other] { $entries } reports
+} by { $managers ->
+    [one] { $managers } manager
+   *[other] { $managers } managers
+}
+digest-revenue = Revenue: { $revenue }
+digest-month-to-date = Month to date: { $revenue }
+digest-total = Total
+digest-empty = No reports were filed.
+
+## Statements
+
+statement-title = Revenue statement for { $period }
+statement-manager = Manager: { $manager }
+statement-company = All managers
+statement-subtotals = Totals by manager
+statement-total = Total
+statement-empty = No reports were filed in the period.
+statement-generated = Generated { $at } by { $user }
+statement-generated-cli = Generated { $at } from the command line
+statement-signature = Signature
+statement-page = Page { $page } of { $pages }
+
+## Errors
+
+err-unauthorized = Sign in to continue
+err-internal = Internal server error, try again later
+err-page-not-found = Page not found
+err-report-not-found = Report not found
+err-username-empty = Enter a username
+err-username-taken = This username is taken
+err-password-empty = Enter a password
+err-email-empty = Enter the address digests are mailed to
+err-email-invalid = Invalid email address
+err-statement-period = The period ends before it starts
+err-statement-too-long = A statement covers at most { $days } days
+err-forbidden-view-reports = You are not allowed to view reports
+err-forbidden-view-report = You are not allowed to view this report
+err-forbidden-view-others-reports = You are not allowed to view other users' reports
+err-forbidden-add-reports = You are not allowed to add reports
+err-forbidden-review-reports = You are not allowed to change reports
+err-forbidden-discuss-report = You are not allowed to discuss this report
+err-forbidden-view-plans = You are not allowed to view plans
+err-forbidden-edit-plans = You are not allowed to change plans
+err-forbidden-view-rates = You are not allowed to view exchange rates
+err-forbidden-upload-rates = You are not allowed to upload exchange rates
+err-forbidden-view-locations = You are not allowed to view locations
+err-forbidden-manage-users = You are not allowed to manage other users
+err-forbidden-edit-user = You are not allowed to edit this user
+err-user-not-found = User not found
+err-login-unknown-user = User not found.
+err-login-failed = Check the username and password.
+err-login-inactive = This account is deactivated.
+err-deactivate-self = You can't deactivate your own account
+err-unknown-locale = Unknown language: { $code }
+err-unknown-timezone = Unknown time zone: { $name }
+err-report-date-range = The date is out of the allowed range
+err-report-not-editable = The report can't be edited
+err-report-changed = The report was changed since it was opened
+err-duplicate-entry = There is already a report for this location and date
+err-shift-overlap = The shift overlaps an existing report for this location and date
+err-shift-time = Invalid time: { $value }
+err-shift-incomplete = Enter both the start and the end of the shift
+err-shift-empty = The shift ends when it starts
+err-shift-label-long = The shift name is longer than { $max } characters
+err-breakdown-empty = Enter the revenue of at least one payment method
+err-unknown-payment-method = Unknown payment method: { $code }
+err-unknown-currency = Unknown currency: { $code }
+err-amount-empty = The amount is missing
+err-amount-character = Invalid character “{ $character }”
+err-amount-sign = The sign has to come before the amount
+err-amount-separator = Misplaced digit group separators
+err-amount-fraction = Too many decimal places, { $max } allowed
+err-amount-overflow = The amount is too large
+err-amount-negative = The amount can't be negative
+err-plan-month = Invalid month
+err-plan-scope = No manager or location chosen
+err-plan-not-positive = The plan has to be above zero
+err-comment-empty = The comment is empty
+err-comment-too-long = The comment is longer than { $max } { $max ->
+    [one] character
+   *[other] characters
+}
+err-comment-not-own = You can only edit your own comments
+err-comment-parent-missing = The comment you are replying to wasn't found
+err-file-missing = no file chosen
+err-file-too-large = the file is larger than { $max } MB
+err-file-type = { $file } is neither a PDF nor a JPEG, PNG or WebP image
+err-rates-line = line { $line }: { $detail }
+err-rates-rate = invalid rate { $rate }
+err-rates-not-positive = the rate has to be positive
+err-rates-same-currency = { $currency } is the base currency itself
+err-rates-empty = the file has no rates
@@ -0,0 +1,339 @@
+### Russian, the default locale: every key has to be here, other catalogs fall back to it.
+
+app-title = Тестовое задание
+
+## Common
+
+error = Ошибка: { $detail }
+err-request = Не удалось выполнить запрос: { $detail }
+action-save = Сохранить
+action-save-anyway = Сохранить всё равно
+action-add = Добавить
+action-add-anyway = Добавить всё равно
+action-edit = Редактировать
+action-delete = Удалить
+action-upload = Загрузить
+action-search = Найти
+action-activate = Активировать
+action-deactivate = Деактивировать
+action-previous-page = Предыдущая страница
+action-next-page = Следующая страница
+action-sync = Отправить сейчас
+action-keep-mine = Оставить мою версию
+action-keep-server = Оставить версию с сервера
+field-family-name = Фамилия
+field-name = Имя
+field-patronym = Отчество
+field-username = Логин
+field-password = Пароль
+field-date = Дата
+field-address = Адрес
+field-revenue = Выручка
+field-currency = Валюта
+field-manager = Менеджер
+field-plan = План
+field-shift = Смена
+unread-comments = Непрочитанные комментарии
+month-1 = Январь
+month-2 = Февраль
+month-3 = Март
+month-4 = Апрель
+month-5 = Май
+month-6 = Июнь
+month-7 = Июль
+month-8 = Август
+month-9 = Сентябрь
+month-10 = Октябрь
+month-11 = Ноябрь
+month-12 = Декабрь
+size-bytes = { $size } Б
+size-kilobytes = { $size } КБ
+size-megabytes = { $size } МБ
+payment-cash = Наличные
+payment-card = Карта
+payment-online = Онлайн
+payment-returns = Возвраты
+
+## Login and menu
+
+login-title = Войдите, чтобы начать пользоваться приложением
+login-username = Имя пользователя:
+login-username-placeholder = Введите ваш логин
+login-password = Пароль:
+login-password-placeholder = Введите ваш пароль
+login-remember = Запомнить меня на этом устройстве
+login-submit = Войти
+logout = Выйти
+menu-summary = Сводный отчет
+menu-reports = Индивидуальные отчеты
+menu-plans = Планы
+menu-anomalies = Подозрительные отчеты
+menu-rates = Курсы валют
+menu-users = Менеджеры
+menu-my-reports = Мои отчеты
+menu-new-report = Добавить отчет
+search-placeholder = Поиск менеджеров, адресов, дат
+search-searching = Поиск…
+search-nothing = Ничего не найдено
+search-managers = Менеджеры
+search-addresses = Адреса
+search-dates = Отчеты за дату
+search-address-reports = { $count ->
+    [one] { $count } отчет
+    [few] { $count } отчета
+   *[other] { $count } отчетов
+}, последний { $date }
+
+## Dashboard
+
+calendar-year = Год:
+calendar-month = Месяц:
+dashboard-entries = { $count ->
+    [one] Отчет
+    [few] Отчета
+   *[other] Отчетов
+}
+dashboard-managers = { $count ->
+    [one] Менеджер
+    [few] Менеджера
+   *[other] Менеджеров
+}
+missing-own = Нет отчетов за: { $dates }
+missing-title = Нет отчетов
+missing-days = { $count ->
+    [one] { $count } день
+    [few] { $count } дня
+   *[other] { $count } дней
+}: { $dates }
+missing-add = Добавить отчет
+
+## Reports
+
+reports-manager = Отчеты менеджера:
+reports-discussion = Обсуждение
+summary-unsplit = Без разбивки
+summary-completion = Выполнение
+summary-remaining = Осталось
+summary-projection = Прогноз
+summary-month-total = Итого за месяц
+summary-whole-day = Весь день
+statement-section = Выписка в PDF:
+statement-from = С
+statement-to = По
+statement-selected = Выбранный менеджер
+statement-download = Скачать PDF
+report-edit-title = Редактирование отчета
+report-new-title = Добавление нового отчета
+report-manager-section = Данные менеджера:
+report-section = Данные отчета:
+report-address = Адрес точки
+report-breakdown = Выручка по способам оплаты:
+report-shift = Смена:
+report-shift-hint = Не заполняйте для отчета за весь день. Смена, которая заканчивается раньше начала, заканчивается на следующий день.
+report-shift-start = Начало
+report-shift-end = Конец
+report-shift-label = Название смены
+report-unsplit = Отчет сохранен без разбивки, выручка: { $revenue }
+report-total = Итого: { $total }
+report-currency-default = По умолчанию для точки
+report-anomaly-confirm = { $warning }. Проверьте суммы или сохраните отчет еще раз.
+report-duplicate-open = Открыть его для редактирования
+offline-saved = Нет связи с сервером. Отчет сохранен на устройстве и будет отправлен, когда связь появится.
+offline-title = Неотправленные отчеты
+offline-report = { $address }, { $date }: { $revenue }
+offline-pending = Ждет отправки
+offline-syncing = Отправка…
+offline-conflict = На сервере другая версия отчета: { $revenue }
+offline-failed = Не принят сервером: { $error }
+anomaly-manager = Выручка заметно { $direction ->
+    [higher] выше
+   *[lower] ниже
+} обычной для менеджера (медиана { $median })
+anomaly-location = Выручка заметно { $direction ->
+    [higher] выше
+   *[lower] ниже
+} обычной для точки (медиана { $median })
+anomaly-both = Выручка заметно { $manager ->
+    [higher] выше
+   *[lower] ниже
+} обычной для менеджера (медиана { $manager-median }), заметно { $location ->
+    [higher] выше
+   *[lower] ниже
+} обычной для точки (медиана { $location-median })
+anomalies-empty = Подозрительных отчетов нет
+anomalies-reason = Причина
+anomalies-resolve = Проверено
+attachments-title = Вложения (Z-отчеты, чеки, фото):
+attachments-uploaded = Загружено файлов: { $count }
+attachments-empty = Файлов нет
+attachments-unnamed = файл
+attachments-attach = Прикрепить
+comments-empty = Комментариев пока нет
+comments-placeholder = Комментарий
+comments-reply-placeholder = Ответ
+comments-send = Отправить
+comments-reply = Ответить
+comments-edit = Изменить
+comments-edited = (изменено)
+
+## Plans and rates
+
+plans-month = Месяц:
+plans-scope = Менеджер или точка
+plans-managers = Менеджеры
+plans-locations = Точки
+plans-target-placeholder = План, 1 000 000
+plans-currency-default = Валюта по умолчанию
+plans-own = Мой план
+plans-target = План: { $amount }
+plans-actual = Факт: { $amount }
+plans-remaining = Осталось: { $amount }
+plans-projection = Прогноз: { $amount }
+plans-projection-none = Прогноз: нет данных
+plans-unconverted = Без курса валюты: { $amount }
+rates-file = Файл курсов (CSV: date,currency,rate[,base]):
+rates-imported = Загружено курсов: { $count }
+rates-reporting-currency = Отчетная валюта: { $currency }
+rates-rate = Курс
+
+## Users
+
+users-add = Добавить пользователя
+users-search = Имя или логин
+users-any-permission = Любые права
+users-any-state = Любое состояние
+users-active = Активен
+users-inactive = Неактивен
+users-created = Добавлен
+users-state = Состояние
+users-none = Пользователи не найдены
+users-shown = { $from }–{ $to } из { $total }
+perm-manage-users = Управляет пользователями
+perm-view-all = Видит все отчеты
+perm-view-owned = Видит свои отчеты
+perm-edit-owned = Подает отчеты
+user-edit-title = Редактирование пользователя
+user-new-title = Добавление нового пользователя
+user-section = Данные пользователя:
+user-login-section = Данные для входа:
+user-access-section = Уровень доступа:
+user-admin = Администратор
+user-admin-hint = Администратор может добавлять новых пользователей и просматривать всю отчетность.
+user-locale = Язык интерфейса:
+user-locale-browser = Как в браузере
+user-timezone = Часовой пояс:
+user-timezone-hint = По нему проверяются даты отчетов, если у точки нет своего. Если не указан — { $default }.
+digest-section = Сводки по почте:
+digest-hint = Сводки по доступным вам отчетам, приходят утром.
+digest-email = Эл. почта
+digest-daily = Ежедневно, за прошлый день
+digest-weekly = По понедельникам, за прошлую неделю
+digest-monthly = 1-го числа, за прошлый месяц
+digest-saved = Подписки на сводки сохранены
+
+## Digests
+
+digest-subject-daily = Сводка за день { $period }
+digest-subject-weekly = Сводка за неделю { $period }
+digest-subject-monthly = Сводка за месяц { $period }
+digest-title = Выручка за { $period }
+digest-counts = { $entries ->
+    [one] { $entries } отчет
+    [few] { $entries } отчета
+   *[other] { $entries } отчетов
+} от { $managers ->
+    [one] { $managers } менеджера
+   *[other] { $managers } менеджеров
+}
+digest-revenue = Выручка: { $revenue }
+digest-month-to-date = С начала месяца: { $revenue }
+digest-total = Итого
+digest-empty = Отчетов не было.
+
+## Statements
+
+statement-title = Выписка по выручке за { $period }
+statement-manager = Менеджер: { $manager }
+statement-company = Все менеджеры
+statement-subtotals = Итоги по менеджерам
+statement-total = Итого
+statement-empty = За период отчетов нет.
+statement-generated = Сформировано { $at }, пользователь { $user }
+statement-generated-cli = Сформировано { $at } из командной строки
+statement-signature = Подпись
+statement-page = Стр. { $page } из { $pages }
+
+## Errors
+
+err-unauthorized = Войдите, чтобы продолжить
+err-internal = Внутренняя ошибка сервера, попробуйте позже
+err-page-not-found = Страница не найдена
+err-report-not-found = Отчет не найден
+err-username-empty = Укажите логин
+err-username-taken = Этот логин уже занят
+err-password-empty = Укажите пароль
+err-email-empty = Укажите адрес, на который отправлять сводки
+err-email-invalid = Неверный адрес эл. почты
+err-statement-period = Конец периода раньше его начала
+err-statement-too-long = Выписка формируется не более чем за { $days ->
+    [one] { $days } день
+    [few] { $days } дня
+   *[other] { $days } дней
+}
+err-forbidden-view-reports = Пользователь не авторизован для просмотра отчетов
+err-forbidden-view-report = Пользователь не авторизован для просмотра данного отчета
+err-forbidden-view-others-reports = Пользователь не авторизован для просмотра отчетов других пользователей
+err-forbidden-add-reports = Пользователь не авторизован для добавления отчетов
+err-forbidden-review-reports = Пользователь не авторизован для изменения отчетов
+err-forbidden-discuss-report = Пользователь не авторизован для обсуждения данного отчета
+err-forbidden-view-plans = Пользователь не авторизован для просмотра планов
+err-forbidden-edit-plans = Пользователь не авторизован для изменения планов
+err-forbidden-view-rates = Пользователь не авторизован для просмотра курсов валют
+err-forbidden-upload-rates = Пользователь не авторизован для загрузки курсов валют
+err-forbidden-view-locations = Пользователь не авторизован для просмотра точек
+err-forbidden-manage-users = Пользователь не авторизован для управления другими пользователями
+err-forbidden-edit-user = Пользователь не авторизован для редактирования этого пользователя
+err-user-not-found = Пользователь не найден
+err-login-unknown-user = Пользователь не найден.
+err-login-failed = Проверьте введенные логин и пароль.
+err-login-inactive = Учетная запись деактивирована.
+err-deactivate-self = Нельзя деактивировать собственную учетную запись
+err-unknown-locale = Неизвестный язык: { $code }
+err-unknown-timezone = Неизвестный часовой пояс: { $name }
+err-report-date-range = Дата за пределами допустимой
+err-report-not-editable = Отчет недоступен для редактирования
+err-report-changed = Отчет изменен с тех пор, как его открыли
+err-duplicate-entry = Отчет по этой точке за эту дату уже есть
+err-shift-overlap = Смена пересекается с уже поданным отчетом по этой точке за эту дату
+err-shift-time = Неверное время: { $value }
+err-shift-incomplete = Укажите и начало, и конец смены
+err-shift-empty = Смена заканчивается в момент начала
+err-shift-label-long = Название смены длиннее { $max } символов
+err-breakdown-empty = Укажите выручку хотя бы по одному способу оплаты
+err-unknown-payment-method = Неизвестный способ оплаты: { $code }
+err-unknown-currency = Неизвестная валюта: { $code }
+err-amount-empty = Сумма не указана
+err-amount-character = Недопустимый символ «{ $character }»
+err-amount-sign = Знак должен стоять перед суммой
+err-amount-separator = Неверно расставлены разделители разрядов
+err-amount-fraction = Слишком много знаков после запятой, допустимо { $max }
+err-amount-overflow = Слишком большая сумма
+err-amount-negative = Сумма не может быть отрицательной
+err-plan-month = Неверный месяц
+err-plan-scope = Не выбран менеджер или точка
+err-plan-not-positive = План должен быть больше нуля
+err-comment-empty = Комментарий не может быть пустым
+err-comment-too-long = Комментарий длиннее { $max } { $max ->
+    [one] символа
+   *[other] символов
+}
+err-comment-not-own = Изменить можно только свой комментарий
+err-comment-parent-missing = Комментарий, на который вы отвечаете, не найден
+err-file-missing = файл не выбран
+err-file-too-large = файл больше { $max } МБ
+err-file-type = файл { $file } не PDF и не изображение JPEG, PNG или WebP
+err-rates-line = строка { $line }: { $detail }
+err-rates-rate = неверный курс { $rate }
+err-rates-not-positive = курс должен быть положительным
+err-rates-same-currency = валюта { $currency } совпадает с базовой
+err-rates-empty = в файле нет курсов
@@ -0,0 +1,26 @@
+-- one entry per manager, location and date; existing duplicates have to be merged
+-- or deleted by hand first, the migration lists them and stops
+DO $$
+DECLARE
+    duplicates TEXT;
+BEGIN
+    SELECT string_agg(
+        format('%s at %s on %s: %s', users.username, groups.address, groups.date, groups.ids),
+        E'\n'
+    )
+    INTO duplicates
+    FROM (
+        SELECT by_user_id, address, date, string_agg(id::TEXT, ', ' ORDER BY id) AS ids
+        FROM entries
+        GROUP BY by_user_id, address, date
+        HAVING count(*) > 1
+    ) AS groups
+    INNER JOIN users ON users.id = groups.by_user_id;
+
+    IF duplicates IS NOT NULL THEN
+        RAISE EXCEPTION E'duplicate entries, keep one of each and delete the others:\n%', duplicates;
+    END IF;
+END
+$$;
+
+CREATE UNIQUE INDEX entries_manager_location_date ON entries (by_user_id, address, date);
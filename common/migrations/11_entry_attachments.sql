@@ -0,0 +1,12 @@
+CREATE TABLE entry_attachments (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  entry_id UUID NOT NULL REFERENCES entries(id) ON DELETE CASCADE,
+  file_name TEXT NOT NULL,
+  content_type TEXT NOT NULL,
+  size BIGINT NOT NULL CHECK (size > 0),
+  storage_key TEXT NOT NULL UNIQUE,
+  uploaded_by UUID NOT NULL REFERENCES users(id),
+  uploaded_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX entry_attachments_entry ON entry_attachments (entry_id);
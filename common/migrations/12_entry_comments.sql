@@ -0,0 +1,19 @@
+CREATE TABLE entry_comments (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  entry_id UUID NOT NULL REFERENCES entries(id) ON DELETE CASCADE,
+  parent_id UUID REFERENCES entry_comments(id) ON DELETE CASCADE,
+  author_id UUID NOT NULL REFERENCES users(id),
+  body TEXT NOT NULL CHECK (length(body) BETWEEN 1 AND 4000),
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  edited_at TIMESTAMPTZ
+);
+
+CREATE INDEX entry_comments_entry ON entry_comments (entry_id, created_at);
+
+-- when each user last read the thread of an entry
+CREATE TABLE entry_comment_reads (
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  entry_id UUID NOT NULL REFERENCES entries(id) ON DELETE CASCADE,
+  read_at TIMESTAMPTZ NOT NULL,
+  PRIMARY KEY (user_id, entry_id)
+);
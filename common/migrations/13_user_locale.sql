@@ -0,0 +1,2 @@
+-- interface language chosen by the user, NULL follows the browser
+ALTER TABLE users ADD COLUMN locale TEXT;
@@ -0,0 +1,4 @@
+-- IANA zone report dates are checked in: the location's, else the manager's,
+-- Europe/Moscow when neither is set
+ALTER TABLE users ADD COLUMN timezone TEXT;
+ALTER TABLE locations ADD COLUMN timezone TEXT;
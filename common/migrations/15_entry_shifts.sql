@@ -0,0 +1,12 @@
+-- optional hours and name of the shift an entry covers, a location can have several
+-- entries a day as long as their shifts don't overlap; entries without hours cover
+-- the whole day and stay unique per manager, location and date
+ALTER TABLE entries
+  ADD COLUMN shift_start TIME,
+  ADD COLUMN shift_end TIME,
+  ADD COLUMN shift_label TEXT,
+  ADD CONSTRAINT entries_shift_hours CHECK ((shift_start IS NULL) = (shift_end IS NULL));
+
+DROP INDEX entries_manager_location_date;
+CREATE UNIQUE INDEX entries_manager_location_date_shift
+  ON entries (by_user_id, address, date, COALESCE(shift_start, '00:00'::TIME));
@@ -0,0 +1,7 @@
+-- when the account was added, users created before this migration get its time;
+-- inactive users can't sign in and are not reminded of missing reports
+ALTER TABLE users ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT now();
+ALTER TABLE users ADD COLUMN active BOOLEAN NOT NULL DEFAULT TRUE;
+
+CREATE INDEX users_family_name ON users (lower(family_name), lower(name));
+CREATE INDEX users_created_at ON users (created_at);
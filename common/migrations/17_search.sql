@@ -0,0 +1,8 @@
+-- trigram indexes behind the global search; pg_trgm is a trusted extension,
+-- the database owner can create it
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX users_full_name_trgm ON users
+    USING GIN ((family_name || ' ' || name || ' ' || COALESCE(patronym, '')) gin_trgm_ops);
+CREATE INDEX users_username_trgm ON users USING GIN (username gin_trgm_ops);
+CREATE INDEX entries_address_trgm ON entries USING GIN (address gin_trgm_ops);
@@ -0,0 +1,11 @@
+-- bumped on every change; a change made offline to an older revision is a conflict
+ALTER TABLE entries ADD COLUMN revision INTEGER NOT NULL DEFAULT 1;
+
+-- changes synced from a browser's offline queue by the id the browser gave them,
+-- syncing one again returns what it did the first time
+CREATE TABLE entry_syncs (
+    client_id UUID PRIMARY KEY,
+    entry_id UUID NOT NULL REFERENCES entries(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    synced_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,26 @@
+-- every change of an entry is announced to the server's listeners, whichever process
+-- made it, and delivered when its transaction commits
+CREATE FUNCTION notify_entry_change() RETURNS TRIGGER AS $$
+DECLARE
+    entry entries;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        entry := OLD;
+    ELSE
+        entry := NEW;
+    END IF;
+
+    PERFORM pg_notify('entry_changes', json_build_object(
+        'kind', CASE TG_OP WHEN 'INSERT' THEN 'created' WHEN 'UPDATE' THEN 'updated' ELSE 'deleted' END,
+        'entry_id', entry.id,
+        'user_id', entry.by_user_id,
+        'date', entry.date
+    )::TEXT);
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER entries_notify_change
+AFTER INSERT OR UPDATE OR DELETE ON entries
+FOR EACH ROW EXECUTE FUNCTION notify_entry_change();
@@ -0,0 +1,10 @@
+-- summaries mailed to a user on a schedule, one row per frequency they chose;
+-- last_sent_on keeps a restarted scheduler from sending a day's digest twice
+CREATE TABLE digest_subscriptions (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    frequency TEXT NOT NULL CHECK (frequency IN ('daily', 'weekly', 'monthly')),
+    email TEXT NOT NULL,
+    last_sent_on DATE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (user_id, frequency)
+);
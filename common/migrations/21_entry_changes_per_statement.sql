@@ -0,0 +1,39 @@
+-- a seed or an import changes thousands of entries in a few statements: announce each
+-- statement once per manager whose entries it changed, naming the entry and its date
+-- only when it changed a single one
+DROP TRIGGER entries_notify_change ON entries;
+DROP FUNCTION notify_entry_change();
+
+CREATE FUNCTION notify_entry_changes() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('entry_changes', json_build_object(
+        'kind', CASE TG_OP WHEN 'INSERT' THEN 'created' WHEN 'UPDATE' THEN 'updated' ELSE 'deleted' END,
+        'entry_id', CASE WHEN count = 1 THEN entry_id END,
+        'user_id', by_user_id,
+        'date', CASE WHEN count = 1 THEN date END
+    )::TEXT)
+    FROM (
+        SELECT by_user_id, COUNT(*) AS count, MIN(id::TEXT) AS entry_id, MIN(date) AS date
+        FROM changed
+        GROUP BY by_user_id
+    ) AS managers;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- transition tables take one event per trigger
+CREATE TRIGGER entries_notify_insert
+AFTER INSERT ON entries
+REFERENCING NEW TABLE AS changed
+FOR EACH STATEMENT EXECUTE FUNCTION notify_entry_changes();
+
+CREATE TRIGGER entries_notify_update
+AFTER UPDATE ON entries
+REFERENCING NEW TABLE AS changed
+FOR EACH STATEMENT EXECUTE FUNCTION notify_entry_changes();
+
+CREATE TRIGGER entries_notify_delete
+AFTER DELETE ON entries
+REFERENCING OLD TABLE AS changed
+FOR EACH STATEMENT EXECUTE FUNCTION notify_entry_changes();
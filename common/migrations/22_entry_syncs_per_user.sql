@@ -0,0 +1,12 @@
+-- client ids are made up by browsers, a synced change is only looked up for the user
+-- who synced it so a reused or colliding id can't return another user's entry
+ALTER TABLE entry_syncs ADD COLUMN user_id UUID REFERENCES users(id) ON DELETE CASCADE;
+
+UPDATE entry_syncs SET user_id = entries.by_user_id
+FROM entries
+WHERE entries.id = entry_syncs.entry_id;
+
+ALTER TABLE entry_syncs
+  ALTER COLUMN user_id SET NOT NULL,
+  DROP CONSTRAINT entry_syncs_pkey,
+  ADD PRIMARY KEY (user_id, client_id);
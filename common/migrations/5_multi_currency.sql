@@ -0,0 +1,16 @@
+CREATE TABLE locations (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  address VARCHAR NOT NULL UNIQUE,
+  currency CHAR(3) NOT NULL DEFAULT 'RUB'
+    CHECK (currency ~ '^[A-Z]{3}$')
+);
+
+INSERT INTO locations (address)
+SELECT DISTINCT address FROM entries;
+
+ALTER TABLE entries
+  ALTER COLUMN revenue DROP DEFAULT,
+  ALTER COLUMN revenue TYPE NUMERIC(19, 4) USING revenue::NUMERIC,
+  ALTER COLUMN revenue SET DEFAULT 0,
+  ADD COLUMN currency CHAR(3) NOT NULL DEFAULT 'RUB'
+    CHECK (currency ~ '^[A-Z]{3}$');
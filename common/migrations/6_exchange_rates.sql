@@ -0,0 +1,7 @@
+CREATE TABLE exchange_rates (
+  date DATE NOT NULL,
+  currency CHAR(3) NOT NULL CHECK (currency ~ '^[A-Z]{3}$'),
+  base CHAR(3) NOT NULL CHECK (base ~ '^[A-Z]{3}$'),
+  rate NUMERIC(19, 8) NOT NULL CHECK (rate > 0),
+  PRIMARY KEY (currency, base, date)
+);
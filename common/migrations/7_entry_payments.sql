@@ -0,0 +1,6 @@
+CREATE TABLE entry_payments (
+  entry_id UUID NOT NULL REFERENCES entries(id) ON DELETE CASCADE,
+  method TEXT NOT NULL CHECK (method IN ('cash', 'card', 'online', 'returns')),
+  amount NUMERIC(19, 4) NOT NULL CHECK (amount >= 0),
+  PRIMARY KEY (entry_id, method)
+);
@@ -0,0 +1,13 @@
+CREATE TABLE plans (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  year INTEGER NOT NULL,
+  month INTEGER NOT NULL CHECK (month BETWEEN 1 AND 12),
+  user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+  location_id UUID REFERENCES locations(id) ON DELETE CASCADE,
+  target NUMERIC(19, 4) NOT NULL CHECK (target > 0),
+  currency CHAR(3) NOT NULL,
+  CHECK ((user_id IS NULL) <> (location_id IS NULL))
+);
+
+CREATE UNIQUE INDEX plans_manager_month ON plans (user_id, year, month) WHERE user_id IS NOT NULL;
+CREATE UNIQUE INDEX plans_location_month ON plans (location_id, year, month) WHERE location_id IS NOT NULL;
@@ -0,0 +1,3 @@
+-- warning shown when the revenue was saved despite looking like an outlier,
+-- cleared once an admin has reviewed it
+ALTER TABLE entries ADD COLUMN anomaly TEXT;
@@ -0,0 +1,160 @@
+/// When a revenue counts as an outlier against the recent history.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Thresholds {
+    /// Modified z-score, `0.6745 * (x - median) / MAD`, above which a revenue is flagged.
+    pub score: f64,
+    /// Days before the entry's date the history is taken from.
+    pub window_days: u32,
+    /// Entries the history needs before anything is flagged.
+    pub min_samples: usize,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            score: 3.5,
+            window_days: 60,
+            min_samples: 10,
+        }
+    }
+}
+
+/// How far a value is from the history.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Outlier {
+    pub median: f64,
+    pub score: f64,
+}
+
+/// Compares `value` with `history` by the modified z-score, which unlike the plain
+/// z-score isn't skewed by earlier outliers. `None` when the value is usual or the
+/// history too short.
+pub fn outlier(history: &[f64], value: f64, thresholds: &Thresholds) -> Option<Outlier> {
+    if history.len() < thresholds.min_samples.max(1) {
+        return None;
+    }
+
+    let center = median(history.to_vec());
+    let mad = median(history.iter().map(|h| (h - center).abs()).collect());
+
+    let score = if mad > 0.0 {
+        0.6745 * (value - center) / mad
+    } else {
+        // more than half of the history is the same amount, fall back to the mean deviation
+        let mean_deviation =
+            history.iter().map(|h| (h - center).abs()).sum::<f64>() / history.len() as f64;
+
+        match mean_deviation {
+            d if d > 0.0 => (value - center) / (1.2533 * d),
+            _ if value == center => 0.0,
+            _ => f64::INFINITY.copysign(value - center),
+        }
+    };
+
+    (score.abs() > thresholds.score).then_some(Outlier {
+        median: center,
+        score,
+    })
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(f64::total_cmp);
+
+    let middle = values.len() / 2;
+
+    if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub mod ssr {
+    use chrono::{Days, NaiveDate};
+    use sqlx::PgExecutor;
+
+    use super::*;
+    use crate::{i18n::Message, moneys::Moneys, IdType};
+
+    /// Thresholds provided to server functions as context.
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct AnomalyThresholds(pub Thresholds);
+
+    /// Checks a revenue against the manager's and the location's entries in the same
+    /// currency before `date`, leaving out the entry itself when it's updated.
+    /// Returns a warning as a catalog message, `None` when the revenue looks usual.
+    pub async fn check_entry(
+        executor: impl PgExecutor<'_>,
+        thresholds: &Thresholds,
+        user_id: IdType,
+        address: &str,
+        date: NaiveDate,
+        revenue: Moneys,
+        entry_id: Option<IdType>,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let from = date
+            .checked_sub_days(Days::new(thresholds.window_days.into()))
+            .unwrap_or(NaiveDate::MIN);
+
+        let records = sqlx::query!(
+            r#"
+            SELECT revenue, currency, by_user_id = $1 AS "by_manager!", address = $2 AS "at_location!"
+            FROM entries
+            WHERE (by_user_id = $1 OR address = $2)
+            AND currency = $3
+            AND date >= $4 AND date <= $5
+            AND (($6::UUID IS NULL) OR (id <> $6::UUID))
+            "#,
+            user_id,
+            address,
+            revenue.currency.code(),
+            from,
+            date,
+            entry_id
+        )
+        .fetch_all(executor)
+        .await?;
+
+        let mut by_manager = vec![];
+        let mut at_location = vec![];
+
+        for r in records {
+            let amount = f64::from(Moneys::from_sql(r.revenue, &r.currency)?);
+
+            if r.by_manager {
+                by_manager.push(amount);
+            }
+            if r.at_location {
+                at_location.push(amount);
+            }
+        }
+
+        let value = f64::from(revenue);
+        let major = |amount: f64| {
+            Moneys::new(
+                (amount * 10f64.powi(revenue.currency.exponent() as i32)).round() as i64,
+                revenue.currency,
+            )
+        };
+
+        let direction = |o: &Outlier| if o.score > 0.0 { "higher" } else { "lower" };
+
+        let warning = match [by_manager, at_location].map(|h| outlier(&h, value, thresholds)) {
+            [Some(m), Some(l)] => Message::new("anomaly-both")
+                .arg("manager", direction(&m))
+                .arg("manager-median", major(m.median).to_string())
+                .arg("location", direction(&l))
+                .arg("location-median", major(l.median).to_string()),
+            [Some(o), None] => Message::new("anomaly-manager")
+                .arg("direction", direction(&o))
+                .arg("median", major(o.median).to_string()),
+            [None, Some(o)] => Message::new("anomaly-location")
+                .arg("direction", direction(&o))
+                .arg("median", major(o.median).to_string()),
+            [None, None] => return Ok(None),
+        };
+
+        Ok(Some(warning.to_string()))
+    }
+}
@@ -2,15 +2,55 @@ use axum_session::SessionPgPool;
 use axum_session_auth::AuthSession;
 use leptos::*;
 
-use crate::IdType;
+use crate::{
+    anomaly::{ssr::AnomalyThresholds, Thresholds},
+    error::AppError,
+    exchange::ssr::ReportingCurrency,
+    i18n::Locale,
+    moneys::Currency,
+    IdType,
+};
 
 pub type AppAuthSession = AuthSession<crate::user::User, IdType, SessionPgPool, sqlx::PgPool>;
 
-pub fn pool() -> Result<sqlx::PgPool, ServerFnError> {
-    use_context::<sqlx::PgPool>().ok_or_else(|| ServerFnError::ServerError("Pool missing.".into()))
+pub fn pool() -> Result<sqlx::PgPool, AppError> {
+    use_context::<sqlx::PgPool>().ok_or_else(|| {
+        log::error!("Pool missing.");
+        AppError::Internal
+    })
 }
 
-pub fn auth() -> Result<AppAuthSession, ServerFnError> {
-    use_context::<AppAuthSession>()
-        .ok_or_else(|| ServerFnError::ServerError("Auth session missing.".into()))
+/// Currency of converted totals, RUB unless the server provides another.
+pub fn reporting_currency() -> Currency {
+    use_context::<ReportingCurrency>()
+        .map(|c| c.0)
+        .unwrap_or_default()
+}
+
+/// Outlier thresholds of revenue checks, the defaults unless the server provides others.
+pub fn anomaly_thresholds() -> Thresholds {
+    use_context::<AnomalyThresholds>()
+        .map(|t| t.0)
+        .unwrap_or_default()
+}
+
+/// Language of the request, the user's choice or the browser's preference.
+pub fn locale() -> Locale {
+    use_context::<Locale>().unwrap_or_default()
+}
+
+pub fn auth() -> Result<AppAuthSession, AppError> {
+    use_context::<AppAuthSession>().ok_or_else(|| {
+        log::error!("Auth session missing.");
+        AppError::Internal
+    })
+}
+
+/// Refusal of a request: `message` for a signed in user without the permission,
+/// [`AppError::Unauthorized`] without a user.
+pub fn forbidden(auth: &AppAuthSession, message: &str) -> ServerFnError<AppError> {
+    match auth.current_user {
+        Some(_) => AppError::Forbidden(message.to_string()).into(),
+        None => AppError::Unauthorized.into(),
+    }
 }
@@ -0,0 +1,529 @@
+use std::{fmt::Write, str::FromStr};
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::AppError,
+    i18n::{Locale, Message},
+    models::{entry::month_range, EntryWithUser},
+    moneys::MoneyTotals,
+    summary::{by_manager, Totals},
+    IdType,
+};
+
+/// How often a digest is mailed, and so the period it covers.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestFrequency {
+    /// Every morning, for the day before.
+    Daily,
+    /// On Mondays, for the previous week.
+    Weekly,
+    /// On the 1st, for the previous month.
+    Monthly,
+}
+
+impl DigestFrequency {
+    pub const ALL: [DigestFrequency; 3] = [
+        DigestFrequency::Daily,
+        DigestFrequency::Weekly,
+        DigestFrequency::Monthly,
+    ];
+
+    /// Name in the subscriptions table and the user form.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DigestFrequency::Daily => "daily",
+            DigestFrequency::Weekly => "weekly",
+            DigestFrequency::Monthly => "monthly",
+        }
+    }
+
+    pub fn label(&self, locale: Locale) -> String {
+        locale.tr(&format!("digest-{}", self.code()))
+    }
+
+    /// First and last day of the period covered by the digest mailed on `today`,
+    /// `None` when it isn't mailed that day.
+    pub fn period(&self, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+        let yesterday = today.pred_opt()?;
+
+        match self {
+            DigestFrequency::Daily => Some((yesterday, yesterday)),
+            DigestFrequency::Weekly => {
+                (today.weekday() == Weekday::Mon).then(|| (today - Duration::days(7), yesterday))
+            }
+            DigestFrequency::Monthly => {
+                (today.day() == 1).then(|| month_range(yesterday.year(), yesterday.month()))
+            }
+        }
+    }
+}
+
+impl FromStr for DigestFrequency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DigestFrequency::ALL
+            .into_iter()
+            .find(|frequency| frequency.code() == s)
+            .ok_or_else(|| format!("unknown digest frequency {s}"))
+    }
+}
+
+/// A user's subscription to one of the digests.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct DigestSubscription {
+    pub user_id: IdType,
+    pub frequency: DigestFrequency,
+    pub email: String,
+    /// Latest day the digest was mailed on, it isn't mailed twice that day.
+    pub last_sent_on: Option<NaiveDate>,
+}
+
+/// Digest fields of the user form, submitted as `digests[email]` and a checkbox
+/// per frequency.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct DigestFields {
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub daily: Option<String>,
+    #[serde(default)]
+    pub weekly: Option<String>,
+    #[serde(default)]
+    pub monthly: Option<String>,
+}
+
+impl DigestFields {
+    /// The address and the chosen frequencies, none unsubscribes from every digest.
+    pub fn parse(&self) -> Result<(String, Vec<DigestFrequency>), AppError> {
+        let email = self.email.trim().to_string();
+        let frequencies = [&self.daily, &self.weekly, &self.monthly]
+            .into_iter()
+            .zip(DigestFrequency::ALL)
+            .filter(|(checked, _)| checked.is_some())
+            .map(|(_, frequency)| frequency)
+            .collect::<Vec<_>>();
+
+        if email.is_empty() && !frequencies.is_empty() {
+            return Err(AppError::validation("email", "err-email-empty"));
+        }
+        if !email.is_empty() && !is_email(&email) {
+            return Err(AppError::validation("email", "err-email-invalid"));
+        }
+
+        Ok((email, frequencies))
+    }
+}
+
+/// Roughly an address mail can be sent to: a single `@` between a local part and a
+/// dotted domain, no spaces.
+pub fn is_email(s: &str) -> bool {
+    match s.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.split('.').count() > 1
+                && domain.split('.').all(|part| !part.is_empty())
+                && !s.chars().any(|c| c.is_whitespace() || c.is_control())
+        }
+        None => false,
+    }
+}
+
+/// The dashboard's aggregates of a period as mailed to a subscriber.
+#[derive(Clone)]
+pub struct Digest {
+    pub frequency: DigestFrequency,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Entries of the period, with their converted revenues.
+    pub entries: Vec<EntryWithUser>,
+    /// Revenue from the 1st to the end of the period, for periods shorter than a month.
+    pub month_to_date: Option<MoneyTotals>,
+}
+
+impl Digest {
+    /// Dates the entries are loaded for: the period and the month it ends in.
+    pub fn range(
+        frequency: DigestFrequency,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> (NaiveDate, NaiveDate) {
+        match frequency {
+            DigestFrequency::Monthly => (from, to),
+            _ => (from.min(to.with_day(1).unwrap_or(to)), to),
+        }
+    }
+
+    /// The digest of the period from entries dated within [`Digest::range`].
+    pub fn new(
+        frequency: DigestFrequency,
+        from: NaiveDate,
+        to: NaiveDate,
+        entries: Vec<EntryWithUser>,
+    ) -> Self {
+        let month_start = to.with_day(1).unwrap_or(to);
+        let month_to_date = (frequency != DigestFrequency::Monthly).then(|| {
+            Totals::of(
+                entries
+                    .iter()
+                    .filter(|e| e.date >= month_start && e.date <= to),
+            )
+            .revenue
+        });
+        let entries = entries
+            .into_iter()
+            .filter(|e| e.date >= from && e.date <= to)
+            .collect();
+
+        Self {
+            frequency,
+            from,
+            to,
+            entries,
+            month_to_date,
+        }
+    }
+
+    pub fn period(&self) -> String {
+        period(self.from, self.to)
+    }
+
+    pub fn subject(&self, locale: Locale) -> String {
+        locale.message(
+            &Message::new(&format!("digest-subject-{}", self.frequency.code()))
+                .arg("period", self.period()),
+        )
+    }
+
+    fn counts(&self, locale: Locale) -> String {
+        let managers = by_manager(&self.entries).len();
+
+        locale.message(
+            &Message::new("digest-counts")
+                .arg("entries", self.entries.len())
+                .arg("managers", managers),
+        )
+    }
+
+    pub fn text(&self, locale: Locale) -> String {
+        let totals = Totals::of(&self.entries);
+        let mut text = String::new();
+
+        _ = writeln!(
+            text,
+            "{}\n\n{}",
+            locale.message(&Message::new("digest-title").arg("period", self.period())),
+            self.counts(locale)
+        );
+        _ = writeln!(
+            text,
+            "{}",
+            locale.message(
+                &Message::new("digest-revenue").arg("revenue", totals.revenue.to_string())
+            )
+        );
+        if let Some(month_to_date) = self.month_to_date.as_ref() {
+            _ = writeln!(
+                text,
+                "{}",
+                locale.message(
+                    &Message::new("digest-month-to-date").arg("revenue", month_to_date.to_string())
+                )
+            );
+        }
+
+        for (user, manager) in by_manager(&self.entries) {
+            let columns = manager
+                .columns(&totals, locale)
+                .into_iter()
+                .map(|(label, value)| format!("{label}: {value}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            _ = write!(
+                text,
+                "\n{}: {}\n  {columns}\n",
+                user.short_name(),
+                manager.revenue
+            );
+        }
+
+        if self.entries.is_empty() {
+            _ = write!(text, "\n{}\n", locale.tr("digest-empty"));
+        }
+
+        text
+    }
+
+    pub fn html(&self, locale: Locale) -> String {
+        let totals = Totals::of(&self.entries);
+        let cell = "padding:4px 8px;text-align:left;border-bottom:1px solid #94a3b8";
+        let mut html = String::new();
+
+        _ = write!(
+            html,
+            "<!DOCTYPE html><html><body style=\"font-family:sans-serif\"><h2>{}</h2><p>{}<br>{}",
+            escape(&locale.message(&Message::new("digest-title").arg("period", self.period()))),
+            escape(&self.counts(locale)),
+            escape(&locale.message(
+                &Message::new("digest-revenue").arg("revenue", totals.revenue.to_string())
+            ))
+        );
+        if let Some(month_to_date) = self.month_to_date.as_ref() {
+            _ = write!(
+                html,
+                "<br><b>{}</b>",
+                escape(&locale.message(
+                    &Message::new("digest-month-to-date").arg("revenue", month_to_date.to_string())
+                ))
+            );
+        }
+        html.push_str("</p>");
+
+        if self.entries.is_empty() {
+            _ = write!(html, "<p>{}</p>", escape(&locale.tr("digest-empty")));
+        } else {
+            let row = |tag: &str, name: &str, columns: Vec<(String, String)>, revenue: String| {
+                let cells = [name.to_string()]
+                    .into_iter()
+                    .chain(columns.into_iter().map(|(_, value)| value))
+                    .chain([revenue])
+                    .map(|value| format!("<{tag} style=\"{cell}\">{}</{tag}>", escape(&value)))
+                    .collect::<String>();
+
+                format!("<tr>{cells}</tr>")
+            };
+
+            let header = totals
+                .columns(&totals, locale)
+                .into_iter()
+                .map(|(label, _)| (String::new(), label))
+                .collect();
+
+            _ = write!(
+                html,
+                "<table style=\"border-collapse:collapse\"><thead>{}</thead><tbody>",
+                row(
+                    "th",
+                    &locale.tr("field-manager"),
+                    header,
+                    locale.tr("field-revenue")
+                )
+            );
+            for (user, manager) in by_manager(&self.entries) {
+                html.push_str(&row(
+                    "td",
+                    &user.short_name(),
+                    manager.columns(&totals, locale),
+                    manager.revenue.to_string(),
+                ));
+            }
+            _ = write!(
+                html,
+                "</tbody><tfoot>{}</tfoot></table>",
+                row(
+                    "th",
+                    &locale.tr("digest-total"),
+                    totals.columns(&totals, locale),
+                    totals.revenue.to_string()
+                )
+            );
+        }
+
+        html.push_str("</body></html>");
+
+        html
+    }
+}
+
+/// `18.10.2026`, or `12.10.2026 – 18.10.2026` for a longer period.
+pub fn period(from: NaiveDate, to: NaiveDate) -> String {
+    let format = |date: NaiveDate| date.format("%d.%m.%Y").to_string();
+
+    match from == to {
+        true => format(from),
+        false => format!("{} – {}", format(from), format(to)),
+    }
+}
+
+/// Text with the characters HTML gives a meaning escaped.
+fn escape(text: &str) -> String {
+    text.chars()
+        .fold(String::with_capacity(text.len()), |mut acc, c| {
+            match c {
+                '&' => acc.push_str("&amp;"),
+                '<' => acc.push_str("&lt;"),
+                '>' => acc.push_str("&gt;"),
+                '"' => acc.push_str("&quot;"),
+                '\'' => acc.push_str("&#39;"),
+                c => acc.push(c),
+            }
+            acc
+        })
+}
+
+#[cfg(feature = "ssr")]
+pub mod ssr {
+    use sqlx::PgPool;
+
+    use super::*;
+    use crate::{exchange::ssr::ExchangeRates, moneys::Currency};
+
+    impl Digest {
+        /// Entries of the period filed by `owner`, or by everyone when `None`, with
+        /// revenues converted to `currency`.
+        pub async fn load(
+            pool: &PgPool,
+            currency: Currency,
+            frequency: DigestFrequency,
+            (from, to): (NaiveDate, NaiveDate),
+            owner: Option<IdType>,
+        ) -> Result<Self, sqlx::Error> {
+            let (min_date, max_date) = Self::range(frequency, from, to);
+
+            let mut entries = EntryWithUser::list_range(pool, min_date, max_date, owner).await?;
+            ExchangeRates::load(pool, currency, to)
+                .await?
+                .convert_entries(&mut entries);
+
+            Ok(Self::new(frequency, from, to, entries))
+        }
+    }
+
+    fn frequency(code: &str) -> Result<DigestFrequency, sqlx::Error> {
+        code.parse()
+            .map_err(|err: String| sqlx::Error::Decode(err.into()))
+    }
+
+    impl DigestSubscription {
+        pub async fn for_user(pool: &PgPool, user_id: IdType) -> Result<Vec<Self>, sqlx::Error> {
+            let records = sqlx::query!(
+                r#"
+                SELECT user_id, frequency, email, last_sent_on FROM digest_subscriptions
+                WHERE user_id = $1
+                "#,
+                user_id
+            )
+            .fetch_all(pool)
+            .await?;
+
+            let mut subscriptions = records
+                .into_iter()
+                .map(|r| {
+                    Ok(Self {
+                        user_id: r.user_id,
+                        frequency: frequency(&r.frequency)?,
+                        email: r.email,
+                        last_sent_on: r.last_sent_on,
+                    })
+                })
+                .collect::<Result<Vec<_>, sqlx::Error>>()?;
+            subscriptions.sort_by_key(|s| s.frequency);
+
+            Ok(subscriptions)
+        }
+
+        /// Subscriptions of active users to the digest not yet mailed on `today`, the
+        /// latest day mailed unless an earlier one is sent again.
+        pub async fn list_due(
+            pool: &PgPool,
+            frequency: DigestFrequency,
+            today: NaiveDate,
+        ) -> Result<Vec<Self>, sqlx::Error> {
+            let records = sqlx::query!(
+                r#"
+                SELECT digest_subscriptions.user_id, digest_subscriptions.email, digest_subscriptions.last_sent_on
+                FROM digest_subscriptions
+                INNER JOIN users ON users.id = digest_subscriptions.user_id
+                WHERE digest_subscriptions.frequency = $1
+                AND users.active
+                AND digest_subscriptions.last_sent_on IS DISTINCT FROM $2
+                ORDER BY digest_subscriptions.created_at
+                "#,
+                frequency.code(),
+                today
+            )
+            .fetch_all(pool)
+            .await?;
+
+            Ok(records
+                .into_iter()
+                .map(|r| Self {
+                    user_id: r.user_id,
+                    frequency,
+                    email: r.email,
+                    last_sent_on: r.last_sent_on,
+                })
+                .collect())
+        }
+
+        /// Subscribes the user to exactly `frequencies` at `email`, keeping when the
+        /// digests already subscribed to were last mailed.
+        pub async fn replace(
+            pool: &PgPool,
+            user_id: IdType,
+            email: &str,
+            frequencies: &[DigestFrequency],
+        ) -> Result<(), sqlx::Error> {
+            let codes = frequencies
+                .iter()
+                .map(|f| f.code().to_string())
+                .collect::<Vec<_>>();
+
+            let mut tx = pool.begin().await?;
+
+            sqlx::query!(
+                r#"
+                DELETE FROM digest_subscriptions
+                WHERE user_id = $1 AND NOT (frequency = ANY($2))
+                "#,
+                user_id,
+                &codes
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                r#"
+                INSERT INTO digest_subscriptions (user_id, frequency, email)
+                SELECT $1, frequency, $3 FROM UNNEST($2::TEXT[]) AS frequency
+                ON CONFLICT (user_id, frequency) DO UPDATE
+                SET email = EXCLUDED.email
+                "#,
+                user_id,
+                &codes,
+                email
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            tx.commit().await
+        }
+
+        pub async fn mark_sent(
+            pool: &PgPool,
+            user_id: IdType,
+            frequency: DigestFrequency,
+            on: NaiveDate,
+        ) -> Result<(), sqlx::Error> {
+            sqlx::query!(
+                r#"
+                UPDATE digest_subscriptions
+                SET last_sent_on = GREATEST(last_sent_on, $3)
+                WHERE user_id = $1 AND frequency = $2
+                "#,
+                user_id,
+                frequency.code(),
+                on
+            )
+            .execute(pool)
+            .await?;
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::{fmt::Display, str::FromStr};
+
+use http::StatusCode;
+use leptos::ServerFnError;
+use serde::{Deserialize, Serialize};
+
+use crate::{i18n::Message, IdType};
+
+/// Failure of a server function. Messages are catalog messages, see [`Message`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AppError {
+    /// Nobody is signed in.
+    Unauthorized,
+    Forbidden(String),
+    NotFound(String),
+    /// Form input that can't be accepted, shown next to its field.
+    Validation {
+        field: String,
+        message: String,
+    },
+    /// The request clashes with stored data, e.g. a second report for the same day.
+    Conflict(String),
+    /// Nothing the user can fix, the details are in the server log.
+    Internal,
+}
+
+impl AppError {
+    pub fn validation(field: &str, message: impl Display) -> Self {
+        AppError::Validation {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// The manager already reported for the location and date, in the `existing` entry.
+    pub fn duplicate_entry(existing: IdType) -> Self {
+        AppError::Conflict(
+            Message::new("err-duplicate-entry")
+                .arg("entry", existing.to_string())
+                .to_string(),
+        )
+    }
+
+    /// The shift overlaps the `existing` entry of the location and date.
+    pub fn overlapping_shift(existing: IdType) -> Self {
+        AppError::Conflict(
+            Message::new("err-shift-overlap")
+                .arg("entry", existing.to_string())
+                .to_string(),
+        )
+    }
+
+    /// The `existing` entry was changed since the version a change was made to.
+    pub fn changed_meanwhile(existing: IdType) -> Self {
+        AppError::Conflict(
+            Message::new("err-report-changed")
+                .arg("entry", existing.to_string())
+                .to_string(),
+        )
+    }
+
+    /// The existing entry of a [`AppError::duplicate_entry`],
+    /// [`AppError::overlapping_shift`] or [`AppError::changed_meanwhile`] conflict.
+    pub fn duplicate_of(&self) -> Option<IdType> {
+        let AppError::Conflict(message) = self else {
+            return None;
+        };
+
+        message
+            .parse::<Message>()
+            .ok()?
+            .text_arg("entry")?
+            .parse()
+            .ok()
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::Unauthorized => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Validation { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Name of the form field the error belongs to.
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            AppError::Validation { field, .. } => Some(field),
+            _ => None,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::Unauthorized => "err-unauthorized",
+            AppError::Forbidden(message)
+            | AppError::NotFound(message)
+            | AppError::Validation { message, .. }
+            | AppError::Conflict(message) => message,
+            AppError::Internal => "err-internal",
+        }
+    }
+}
+
+// `ServerFnError` sends custom errors as their `Display` output and reads them back with `FromStr`
+impl Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let json = serde_json::to_string(self).map_err(|_| std::fmt::Error)?;
+        write!(f, "{json}")
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl FromStr for AppError {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+/// Unexpected failure for handlers, which `?` can't convert to [`AppError`]. The details
+/// only go to the log.
+pub fn server_error<E: Display>(err: E) -> ServerFnError<AppError> {
+    log::error!("server function failed: {err}");
+    ServerFnError::WrappedServerError(AppError::Internal)
+}
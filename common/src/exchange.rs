@@ -0,0 +1,148 @@
+use crate::moneys::{MoneyTotals, Moneys};
+
+/// Totals in the reporting currency. Amounts without a conversion keep their own
+/// currency line instead of being added to the converted sum.
+pub fn reporting_totals(
+    amounts: impl IntoIterator<Item = (Moneys, Option<Moneys>)>,
+) -> MoneyTotals {
+    amounts
+        .into_iter()
+        .map(|(original, converted)| converted.unwrap_or(original))
+        .collect()
+}
+
+#[cfg(feature = "ssr")]
+pub mod ssr {
+    use std::collections::{BTreeMap, HashMap};
+
+    use chrono::NaiveDate;
+    use rust_decimal::{Decimal, RoundingStrategy};
+    use sqlx::{PgExecutor, PgPool};
+
+    use crate::{models::EntryWithUser, moneys::Currency};
+
+    use super::*;
+
+    /// Currency totals are reported in, provided to server functions as context.
+    #[derive(Clone, Copy, Debug)]
+    pub struct ReportingCurrency(pub Currency);
+
+    /// One unit of `currency` costs `rate` units of `base` from `date` on.
+    #[derive(Clone, Debug)]
+    pub struct Rate {
+        pub date: NaiveDate,
+        pub currency: Currency,
+        pub base: Currency,
+        pub rate: Decimal,
+    }
+
+    impl Rate {
+        /// Inserts the rates, replacing existing ones for the same currency pair and date.
+        pub async fn upsert(
+            executor: impl PgExecutor<'_>,
+            rates: &[Rate],
+        ) -> Result<u64, sqlx::Error> {
+            let dates = rates.iter().map(|r| r.date).collect::<Vec<_>>();
+            let currencies = rates
+                .iter()
+                .map(|r| r.currency.code().to_string())
+                .collect::<Vec<_>>();
+            let bases = rates
+                .iter()
+                .map(|r| r.base.code().to_string())
+                .collect::<Vec<_>>();
+            let values = rates.iter().map(|r| r.rate).collect::<Vec<_>>();
+
+            let result = sqlx::query!(
+                r#"
+                INSERT INTO exchange_rates (date, currency, base, rate)
+                SELECT * FROM UNNEST($1::DATE[], $2::CHAR(3)[], $3::CHAR(3)[], $4::NUMERIC[])
+                ON CONFLICT (currency, base, date) DO UPDATE
+                SET rate = EXCLUDED.rate
+                "#,
+                &dates,
+                &currencies,
+                &bases,
+                &values
+            )
+            .execute(executor)
+            .await?;
+
+            Ok(result.rows_affected())
+        }
+    }
+
+    /// Rates into one base currency, looked up by the date of the converted amount.
+    pub struct ExchangeRates {
+        base: Currency,
+        rates: HashMap<Currency, BTreeMap<NaiveDate, Decimal>>,
+    }
+
+    impl ExchangeRates {
+        /// Loads every rate into `base` effective on or before `until`.
+        pub async fn load(
+            pool: &PgPool,
+            base: Currency,
+            until: NaiveDate,
+        ) -> Result<Self, sqlx::Error> {
+            let records = sqlx::query!(
+                r#"
+                SELECT date, currency, rate FROM exchange_rates
+                WHERE base = $1 AND date <= $2
+                "#,
+                base.code(),
+                until
+            )
+            .fetch_all(pool)
+            .await?;
+
+            let mut rates = HashMap::<Currency, BTreeMap<NaiveDate, Decimal>>::new();
+
+            for record in records {
+                let currency = record
+                    .currency
+                    .parse()
+                    .map_err(|err| sqlx::Error::Decode(Box::new(err)))?;
+
+                rates
+                    .entry(currency)
+                    .or_default()
+                    .insert(record.date, record.rate);
+            }
+
+            Ok(Self { base, rates })
+        }
+
+        /// The amount in the base currency at the latest rate known on `date`,
+        /// `None` when there is no such rate.
+        pub fn convert(&self, moneys: Moneys, date: NaiveDate) -> Option<Moneys> {
+            if moneys.currency == self.base {
+                return Some(moneys);
+            }
+
+            let (_, rate) = self
+                .rates
+                .get(&moneys.currency)?
+                .range(..=date)
+                .next_back()?;
+
+            let converted = (moneys.to_numeric() * rate).round_dp_with_strategy(
+                self.base.exponent(),
+                RoundingStrategy::MidpointNearestEven,
+            );
+
+            Moneys::from_sql(converted, self.base.code()).ok()
+        }
+
+        /// Fills in the converted revenues and payments of the entries.
+        pub fn convert_entries(&self, entries: &mut [EntryWithUser]) {
+            for entry in entries.iter_mut() {
+                entry.converted = self.convert(entry.revenue, entry.date);
+
+                for payment in entry.payments.iter_mut() {
+                    payment.converted = self.convert(payment.amount, entry.date);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use leptos::*;
+
+#[server(DeletePlan, "/api")]
+pub async fn delete_plan(id: crate::IdType) -> Result<(), ServerFnError<crate::error::AppError>> {
+    use axum_session_auth::HasPermission;
+
+    use crate::{
+        ctx::{auth, forbidden, pool},
+        error::server_error,
+        models::Plan,
+        perms::MANAGE_USERS,
+    };
+
+    let pool = pool()?;
+    let auth = auth()?;
+
+    if let Some(user) = auth.current_user.as_ref() {
+        if user.has(MANAGE_USERS, &Some(&pool)).await {
+            Plan::delete(&pool, id).await.map_err(server_error)?;
+
+            return Ok(());
+        }
+    }
+
+    Err(forbidden(&auth, "err-forbidden-edit-plans"))
+}
@@ -0,0 +1,42 @@
+use leptos::*;
+
+/// Changes the text of the user's own comment.
+#[server(EditComment, "/api")]
+pub async fn edit_comment(
+    id: crate::IdType,
+    body: String,
+) -> Result<(), ServerFnError<crate::error::AppError>> {
+    use crate::ctx::{auth, forbidden, pool};
+    use crate::error::{server_error, AppError};
+    use crate::models::{Comment, Entry};
+
+    let body = match Comment::validate_body(&body) {
+        Ok(body) => body,
+        Err(message) => return Err(AppError::validation("body", message).into()),
+    };
+
+    let pool = pool()?;
+    let auth = auth()?;
+
+    if let Some(user) = auth.current_user.as_ref() {
+        let entry_id = Comment::entry_id(&pool, id).await.map_err(server_error)?;
+
+        // the entry may have become unavailable to the author since
+        if let (Some(entry_id), Some(owner_filter)) =
+            (entry_id, Entry::owner_filter(user, &pool).await)
+        {
+            if Entry::get_visible(&pool, entry_id, owner_filter)
+                .await
+                .map_err(server_error)?
+                .is_some()
+                && Comment::update(&pool, id, user.id, body)
+                    .await
+                    .map_err(server_error)?
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    Err(forbidden(&auth, "err-comment-not-own"))
+}
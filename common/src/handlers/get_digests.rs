@@ -0,0 +1,26 @@
+use leptos::*;
+
+#[server(GetDigests, "/api", "GetJson")]
+pub async fn get_digests(
+    user_id: crate::IdType,
+) -> Result<Vec<crate::digest::DigestSubscription>, ServerFnError<crate::error::AppError>> {
+    use axum_session_auth::HasPermission;
+
+    use crate::ctx::{auth, forbidden, pool};
+    use crate::digest::DigestSubscription;
+    use crate::error::server_error;
+    use crate::perms::MANAGE_USERS;
+
+    let pool = pool()?;
+    let auth = auth()?;
+
+    if let Some(user) = auth.current_user.as_ref() {
+        if user.id == user_id || user.has(MANAGE_USERS, &Some(&pool)).await {
+            return DigestSubscription::for_user(&pool, user_id)
+                .await
+                .map_err(server_error);
+        }
+    }
+
+    Err(forbidden(&auth, "err-forbidden-edit-user"))
+}
@@ -1,48 +1,34 @@
 use leptos::*;
 
 #[server(GetReport, "/api", "GetJson")]
-pub async fn get_report(id: crate::IdType) -> Result<crate::models::Entry, ServerFnError> {
-    use axum_session_auth::HasPermission;
-    use sqlx::Postgres;
-
-    use crate::ctx::{auth, pool};
-    use crate::{
-        models,
-        perms::{VIEW_ALL, VIEW_OWNED},
-    };
+pub async fn get_report(
+    id: crate::IdType,
+) -> Result<crate::models::Entry, ServerFnError<crate::error::AppError>> {
+    use crate::ctx::{auth, forbidden, pool};
+    use crate::error::{server_error, AppError};
+    use crate::models;
 
     let pool = pool()?;
     let auth = auth()?;
 
     if let Some(user) = auth.current_user.as_ref() {
-        let can_view_others = user.has(VIEW_ALL, &Some(&pool)).await;
-        let can_view_owned = user.has(VIEW_OWNED, &Some(&pool)).await;
-
-        let user_id_filter = if can_view_others {
-            None
-        } else if can_view_owned {
-            Some(user.id)
-        } else {
-            return Err(ServerFnError::ServerError(
-                "Пользователь не авторизован для просмотра отчетов".to_string(),
-            ));
+        let Some(owner_filter) = models::Entry::owner_filter(user, &pool).await else {
+            return Err(AppError::Forbidden("err-forbidden-view-reports".to_string()).into());
         };
 
-        let report = sqlx::query_as::<Postgres, models::Entry>(
-            r#"
-            SELECT * FROM entries
-            WHERE id = $1 AND (($2::UUID IS NULL) OR (by_user_id = $2::UUID))
-            "#,
-        )
-        .bind(id)
-        .bind(user_id_filter)
-        .fetch_one(&pool)
-        .await?;
+        let mut report = models::Entry::get_visible(&pool, id, owner_filter)
+            .await
+            .map_err(server_error)?
+            .ok_or_else(|| AppError::NotFound("err-report-not-found".to_string()))?;
+
+        report.payments = models::Payment::for_entries(&pool, &[report.id])
+            .await
+            .map_err(server_error)?
+            .remove(&report.id)
+            .unwrap_or_default();
 
         return Ok(report);
     }
 
-    Err(ServerFnError::ServerError(
-        "Пользователь не авторизован для просмотра данного отчета".to_string(),
-    ))
+    Err(forbidden(&auth, "err-forbidden-view-report"))
 }
@@ -1,10 +1,13 @@
 use leptos::*;
 
 #[server(GetUser, "/api", "GetJson")]
-pub async fn get_user(id: crate::IdType) -> Result<crate::user::User, ServerFnError> {
+pub async fn get_user(
+    id: crate::IdType,
+) -> Result<crate::user::User, ServerFnError<crate::error::AppError>> {
     use axum_session_auth::HasPermission;
 
-    use crate::ctx::{auth, pool};
+    use crate::ctx::{auth, forbidden, pool};
+    use crate::error::{server_error, AppError};
     use crate::{models, perms::MANAGE_USERS};
 
     let pool = pool()?;
@@ -24,7 +27,8 @@ pub async fn get_user(id: crate::IdType) -> Result<crate::user::User, ServerFnEr
                 id
             )
             .fetch_all(&pool)
-            .await?;
+            .await
+            .map_err(server_error)?;
 
             let perms = records.iter().map(|r| r.token.clone()).collect::<Vec<_>>();
 
@@ -36,18 +40,18 @@ pub async fn get_user(id: crate::IdType) -> Result<crate::user::User, ServerFnEr
                     patronym: data.patronym.clone(),
                     username: data.username.clone(),
                     password: data.password.clone(),
+                    locale: data.locale.clone(),
+                    timezone: data.timezone.clone(),
+                    created_at: data.created_at,
+                    active: data.active,
                 }
                 .into_user_with_password(Some(perms))
                 .0);
             } else {
-                return Err(ServerFnError::ServerError(
-                    "Пользователь не найден".to_string(),
-                ));
+                return Err(AppError::NotFound("err-user-not-found".to_string()).into());
             }
         }
     }
 
-    Err(ServerFnError::ServerError(
-        "Пользователь не авторизован для редактирования этого пользователя".to_string(),
-    ))
+    Err(forbidden(&auth, "err-forbidden-edit-user"))
 }
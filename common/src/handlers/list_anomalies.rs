@@ -0,0 +1,32 @@
+use leptos::*;
+
+/// Latest entries flagged as unusual on save.
+#[server(ListAnomalies, "/api", "GetJson")]
+pub async fn list_anomalies(
+) -> Result<Vec<crate::models::EntryWithUser>, ServerFnError<crate::error::AppError>> {
+    use axum_session_auth::HasPermission;
+
+    use crate::{
+        ctx::{auth, forbidden, pool},
+        error::server_error,
+        models::EntryWithUser,
+        perms::VIEW_ALL,
+    };
+
+    const LIMIT: i64 = 200;
+
+    let pool = pool()?;
+    let auth = auth()?;
+
+    if let Some(user) = auth.current_user.as_ref() {
+        if user.has(VIEW_ALL, &Some(&pool)).await {
+            let flagged = EntryWithUser::list_flagged(&pool, LIMIT)
+                .await
+                .map_err(server_error)?;
+
+            return Ok(flagged);
+        }
+    }
+
+    Err(forbidden(&auth, "err-forbidden-view-reports"))
+}
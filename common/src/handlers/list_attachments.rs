@@ -0,0 +1,32 @@
+use leptos::*;
+
+/// Files attached to an entry, for users who can view the entry.
+#[server(ListAttachments, "/api", "GetJson")]
+pub async fn list_attachments(
+    entry_id: crate::IdType,
+) -> Result<Vec<crate::models::Attachment>, ServerFnError<crate::error::AppError>> {
+    use crate::ctx::{auth, forbidden, pool};
+    use crate::error::server_error;
+    use crate::models::{Attachment, Entry};
+
+    let pool = pool()?;
+    let auth = auth()?;
+
+    if let Some(user) = auth.current_user.as_ref() {
+        if let Some(owner_filter) = Entry::owner_filter(user, &pool).await {
+            if Entry::get_visible(&pool, entry_id, owner_filter)
+                .await
+                .map_err(server_error)?
+                .is_some()
+            {
+                let attachments = Attachment::list(&pool, entry_id)
+                    .await
+                    .map_err(server_error)?;
+
+                return Ok(attachments);
+            }
+        }
+    }
+
+    Err(forbidden(&auth, "err-forbidden-view-report"))
+}
@@ -0,0 +1,34 @@
+use leptos::*;
+
+/// Thread of an entry for its owner and users who view all reports. Marks the
+/// thread as read by the user.
+#[server(ListComments, "/api")]
+pub async fn list_comments(
+    entry_id: crate::IdType,
+) -> Result<Vec<crate::models::Comment>, ServerFnError<crate::error::AppError>> {
+    use crate::ctx::{auth, forbidden, pool};
+    use crate::error::server_error;
+    use crate::models::{Comment, Entry};
+
+    let pool = pool()?;
+    let auth = auth()?;
+
+    if let Some(user) = auth.current_user.as_ref() {
+        if let Some(owner_filter) = Entry::owner_filter(user, &pool).await {
+            if Entry::get_visible(&pool, entry_id, owner_filter)
+                .await
+                .map_err(server_error)?
+                .is_some()
+            {
+                let comments = Comment::list(&pool, entry_id).await.map_err(server_error)?;
+                Comment::mark_read(&pool, user.id, entry_id)
+                    .await
+                    .map_err(server_error)?;
+
+                return Ok(comments);
+            }
+        }
+    }
+
+    Err(forbidden(&auth, "err-forbidden-view-report"))
+}
@@ -3,14 +3,15 @@ use leptos::*;
 #[server(ListDates, "/api", "GetJson")]
 pub async fn list_dates(
     by_user_id: Option<crate::IdType>,
-) -> Result<Vec<(i32, Vec<u32>)>, ServerFnError> {
+) -> Result<Vec<(i32, Vec<u32>)>, ServerFnError<crate::error::AppError>> {
     use std::collections::BTreeMap;
 
     use axum_session_auth::HasPermission;
     use chrono::Datelike;
 
     use crate::{
-        ctx::{auth, pool},
+        ctx::{auth, forbidden, pool},
+        error::{server_error, AppError},
         perms::{VIEW_ALL, VIEW_OWNED},
     };
 
@@ -28,9 +29,7 @@ pub async fn list_dates(
         } else if can_view_owned {
             Some(user_id)
         } else {
-            return Err(ServerFnError::ServerError(
-                "Пользователь не авторизован для просмотра отчетов".to_string(),
-            ));
+            return Err(AppError::Forbidden("err-forbidden-view-reports".to_string()).into());
         };
 
         let entries = sqlx::query!(
@@ -42,7 +41,8 @@ pub async fn list_dates(
             user_id_filter
         )
         .fetch_all(&pool)
-        .await?;
+        .await
+        .map_err(server_error)?;
 
         let dates: Vec<(i32, Vec<u32>)> = entries
             .into_iter()
@@ -64,7 +64,5 @@ pub async fn list_dates(
         return Ok(dates);
     }
 
-    Err(ServerFnError::ServerError(
-        "Пользователь не авторизован для просмотра отчетов".to_string(),
-    ))
+    Err(forbidden(&auth, "err-forbidden-view-reports"))
 }
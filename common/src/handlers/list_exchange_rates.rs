@@ -0,0 +1,31 @@
+use leptos::*;
+
+#[server(ListExchangeRates, "/api", "GetJson")]
+pub async fn list_exchange_rates() -> Result<
+    (crate::moneys::Currency, Vec<crate::models::ExchangeRate>),
+    ServerFnError<crate::error::AppError>,
+> {
+    use axum_session_auth::HasPermission;
+
+    use crate::{
+        ctx::{auth, forbidden, pool, reporting_currency},
+        error::server_error,
+        models::ExchangeRate,
+        perms::VIEW_ALL,
+    };
+
+    let pool = pool()?;
+    let auth = auth()?;
+
+    if let Some(user) = auth.current_user.as_ref() {
+        if user.has(VIEW_ALL, &Some(&pool)).await {
+            let rates = ExchangeRate::list_recent(&pool, 200)
+                .await
+                .map_err(server_error)?;
+
+            return Ok((reporting_currency(), rates));
+        }
+    }
+
+    Err(forbidden(&auth, "err-forbidden-view-rates"))
+}
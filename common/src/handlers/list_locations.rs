@@ -0,0 +1,27 @@
+use leptos::*;
+
+#[server(ListLocations, "/api", "GetJson")]
+pub async fn list_locations(
+) -> Result<Vec<crate::models::Location>, ServerFnError<crate::error::AppError>> {
+    use axum_session_auth::HasPermission;
+
+    use crate::{
+        ctx::{auth, forbidden, pool},
+        error::server_error,
+        models::Location,
+        perms::VIEW_ALL,
+    };
+
+    let pool = pool()?;
+    let auth = auth()?;
+
+    if let Some(user) = auth.current_user.as_ref() {
+        if user.has(VIEW_ALL, &Some(&pool)).await {
+            let locations = Location::list(&pool).await.map_err(server_error)?;
+
+            return Ok(locations);
+        }
+    }
+
+    Err(forbidden(&auth, "err-forbidden-view-locations"))
+}
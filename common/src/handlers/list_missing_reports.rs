@@ -0,0 +1,42 @@
+use leptos::*;
+
+/// Days of the current month without a report, of every manager for users who view
+/// all reports and of the user themselves for managers.
+#[server(ListMissingReports, "/api", "GetJson")]
+pub async fn list_missing_reports(
+) -> Result<Vec<crate::models::MissingReports>, ServerFnError<crate::error::AppError>> {
+    use crate::{
+        ctx::{auth, forbidden, pool},
+        error::server_error,
+        models::MissingReports,
+        perms::{EDIT_OWNED, VIEW_ALL},
+        tz,
+    };
+    use axum_session_auth::HasPermission;
+
+    let pool = pool()?;
+    let auth = auth()?;
+
+    if let Some(user) = auth.current_user.as_ref() {
+        let user_id_filter = if user.has(VIEW_ALL, &Some(&pool)).await {
+            None
+        } else if user.has(EDIT_OWNED, &Some(&pool)).await {
+            Some(user.id)
+        } else {
+            return Ok(vec![]);
+        };
+
+        let Some((from, to)) = MissingReports::window(tz::today(tz::resolve(None, user.timezone)))
+        else {
+            return Ok(vec![]);
+        };
+
+        let missing = MissingReports::list(&pool, from, to, user_id_filter)
+            .await
+            .map_err(server_error)?;
+
+        return Ok(missing);
+    }
+
+    Err(forbidden(&auth, "err-forbidden-view-reports"))
+}
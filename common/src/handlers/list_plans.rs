@@ -0,0 +1,30 @@
+use leptos::*;
+
+#[server(ListPlans, "/api", "GetJson")]
+pub async fn list_plans(
+    year: i32,
+    month: u32,
+) -> Result<Vec<crate::models::Plan>, ServerFnError<crate::error::AppError>> {
+    use crate::{
+        ctx::{auth, forbidden, pool},
+        error::{server_error, AppError},
+        models::{Entry, Plan},
+    };
+
+    let pool = pool()?;
+    let auth = auth()?;
+
+    if let Some(user) = auth.current_user.as_ref() {
+        let Some(user_id_filter) = Entry::owner_filter(user, &pool).await else {
+            return Err(AppError::Forbidden("err-forbidden-view-plans".to_string()).into());
+        };
+
+        let plans = Plan::list_month(&pool, year, month, user_id_filter)
+            .await
+            .map_err(server_error)?;
+
+        return Ok(plans);
+    }
+
+    Err(forbidden(&auth, "err-forbidden-view-plans"))
+}
@@ -5,10 +5,12 @@ pub async fn list_reports(
     year: i32,
     month: u32,
     owner_id: Option<crate::IdType>,
-) -> Result<Vec<crate::models::EntryWithUser>, ServerFnError> {
+) -> Result<Vec<crate::models::EntryWithUser>, ServerFnError<crate::error::AppError>> {
     use crate::{
-        ctx::{auth, pool},
-        models::{self, entry::month_range},
+        ctx::{auth, forbidden, pool, reporting_currency},
+        error::{server_error, AppError},
+        exchange::ssr::ExchangeRates,
+        models::{self, Comment},
         perms::{VIEW_ALL, VIEW_OWNED},
     };
     use axum_session_auth::HasPermission;
@@ -20,10 +22,10 @@ pub async fn list_reports(
         let user_id_filter = if user.has(VIEW_OWNED, &Some(&pool)).await {
             if let Some(owner_id) = owner_id.as_ref() {
                 if *owner_id != user.id {
-                    return Err(ServerFnError::ServerError(
-                        "Пользователь не авторизован для просмотра отчетов других пользователей"
-                            .to_string(),
-                    ));
+                    return Err(AppError::Forbidden(
+                        "err-forbidden-view-others-reports".to_string(),
+                    )
+                    .into());
                 } else {
                     Some(*owner_id)
                 }
@@ -36,47 +38,31 @@ pub async fn list_reports(
             Some(user.id)
         };
 
-        let (min_date, max_date) = month_range(year, month);
+        let mut reports = models::EntryWithUser::list_month(&pool, year, month, user_id_filter)
+            .await
+            .map_err(server_error)?;
 
-        let records = sqlx::query!(
-            r#"
-            SELECT entries.address, entries.revenue, entries.date, entries.by_user_id, entries.id as entry_id, users.*
-            FROM entries
-            INNER JOIN users ON entries.by_user_id = users.id
-            WHERE entries.date >= $1
-            AND entries.date <= $2
-            AND (($3::UUID IS NULL) OR (users.id = $3::UUID))
-            ORDER BY entries.date DESC
-            "#,
-            min_date,
-            max_date,
-            user_id_filter
-        ).fetch_all(&pool).await?;
+        if let Some(until) = reports.iter().map(|r| r.date).max() {
+            let rates = ExchangeRates::load(&pool, reporting_currency(), until)
+                .await
+                .map_err(server_error)?;
 
-        let entries_w_users = records.into_iter().map(|r| {
-            (
-                models::Entry {
-                    id: r.entry_id,
-                    address: r.address,
-                    revenue: r.revenue.into(),
-                    date: r.date,
-                    by_user_id: r.by_user_id,
-                },
-                models::User {
-                    id: r.id,
-                    name: r.name,
-                    family_name: r.family_name,
-                    patronym: r.patronym,
-                    username: r.username,
-                    password: r.password,
-                },
-            )
-        });
+            rates.convert_entries(&mut reports);
+        }
 
-        return Ok(entries_w_users.map(|d| d.into()).collect());
+        let ids = reports.iter().map(|r| r.id).collect::<Vec<_>>();
+        let counts = Comment::counts(&pool, user.id, &ids)
+            .await
+            .map_err(server_error)?;
+
+        for report in reports.iter_mut() {
+            let counts = counts.get(&report.id).copied().unwrap_or_default();
+            report.comments = counts.total;
+            report.unread_comments = counts.unread;
+        }
+
+        return Ok(reports);
     }
 
-    Err(ServerFnError::ServerError(
-        "Пользователь не авторизован для просмотра отчетов".to_string(),
-    ))
+    Err(forbidden(&auth, "err-forbidden-view-reports"))
 }
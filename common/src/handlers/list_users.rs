@@ -1,12 +1,13 @@
 use leptos::*;
 
 #[server(ListUsers, "/api", "GetJson")]
-pub async fn list_users(managers_only: bool) -> Result<Vec<crate::user::User>, ServerFnError> {
-    use std::collections::HashMap;
-
+pub async fn list_users(
+    managers_only: bool,
+) -> Result<Vec<crate::user::User>, ServerFnError<crate::error::AppError>> {
     use axum_session_auth::HasPermission;
 
-    use crate::ctx::{auth, pool};
+    use crate::ctx::{auth, forbidden, pool};
+    use crate::error::server_error;
     use crate::{
         models,
         perms::{EDIT_OWNED, MANAGE_USERS},
@@ -21,40 +22,39 @@ pub async fn list_users(managers_only: bool) -> Result<Vec<crate::user::User>, S
 
             let result_users = sqlx::query!(
                 r#"
-                SELECT users.*, permissions.token
+                SELECT users.*,
+                    COALESCE(ARRAY_AGG(permissions.token) FILTER (WHERE permissions.token IS NOT NULL), '{}') AS "tokens!"
                 FROM users
                 LEFT JOIN permissions
                 ON permissions.user_id = users.id
                 WHERE users.id != $1
+                GROUP BY users.id
+                HAVING NOT $2 OR $3 = ANY(ARRAY_AGG(permissions.token))
+                ORDER BY LOWER(users.family_name), LOWER(users.name), users.id
                 "#,
-                current_user_id
+                current_user_id,
+                managers_only,
+                EDIT_OWNED
             )
             .fetch_all(&pool)
-            .await?
-            .into_iter()
-            .fold(HashMap::new(), |mut acc, row| {
-                let entry = acc.entry(row.id).or_insert((
-                    models::User {
-                        id: row.id,
-                        name: row.name,
-                        family_name: row.family_name,
-                        patronym: row.patronym,
-                        username: row.username,
-                        password: row.password,
-                    },
-                    vec![],
-                ));
-                entry.1.push(row.token);
-
-                acc
-            })
+            .await
+            .map_err(server_error)?
             .into_iter()
-            .filter_map(|(_, e)| {
-                if !managers_only || e.1.contains(&EDIT_OWNED.to_string()) {
-                    Some(e.0.into_user_with_password(Some(e.1)).0)
-                } else {
-                    None
+            .map(|row| {
+                models::User {
+                    id: row.id,
+                    name: row.name,
+                    family_name: row.family_name,
+                    patronym: row.patronym,
+                    username: row.username,
+                    password: row.password,
+                    locale: row.locale,
+                    timezone: row.timezone,
+                    created_at: row.created_at,
+                    active: row.active,
                 }
+                .into_user_with_password(Some(row.tokens))
+                .0
             })
             .collect::<Vec<_>>();
 
@@ -66,7 +66,5 @@ pub async fn list_users(managers_only: bool) -> Result<Vec<crate::user::User>, S
         log::error!("no user");
     }
 
-    Err(ServerFnError::ServerError(
-        "Пользователь не авторизован для управления другими пользователями".to_string(),
-    ))
+    Err(forbidden(&auth, "err-forbidden-manage-users"))
 }
@@ -0,0 +1,105 @@
+use leptos::*;
+
+/// Page of the users list, `search` matching names and usernames, `permission` a token
+/// the users must have and `active` their state. Pages count from 0.
+#[server(ListUsersPage, "/api", "GetJson")]
+pub async fn list_users_page(
+    search: Option<String>,
+    permission: Option<String>,
+    active: Option<bool>,
+    sort: crate::user::UserSort,
+    descending: bool,
+    page: i64,
+) -> Result<crate::user::UserPage, ServerFnError<crate::error::AppError>> {
+    use axum_session_auth::HasPermission;
+
+    use crate::ctx::{auth, forbidden, pool};
+    use crate::error::server_error;
+    use crate::{
+        models,
+        perms::MANAGE_USERS,
+        search::like_pattern,
+        user::{UserPage, USERS_PER_PAGE},
+    };
+
+    let pool = pool()?;
+    let auth = auth()?;
+
+    if let Some(user) = auth.current_user.as_ref() {
+        if user.has(MANAGE_USERS, &Some(&pool)).await {
+            let pattern = search
+                .as_deref()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(like_pattern);
+            let permission = permission.filter(|p| !p.is_empty());
+
+            let records = sqlx::query!(
+                r#"
+                SELECT users.*,
+                    COALESCE(ARRAY_AGG(permissions.token) FILTER (WHERE permissions.token IS NOT NULL), '{}') AS "tokens!",
+                    COUNT(*) OVER () AS "total!"
+                FROM users
+                LEFT JOIN permissions
+                ON permissions.user_id = users.id
+                WHERE users.id != $1
+                AND ($2::TEXT IS NULL
+                    OR users.username ILIKE $2
+                    OR CONCAT_WS(' ', users.family_name, users.name, users.patronym) ILIKE $2
+                    OR CONCAT_WS(' ', users.name, users.family_name) ILIKE $2)
+                AND ($3::TEXT IS NULL OR EXISTS (
+                    SELECT 1 FROM permissions
+                    WHERE permissions.user_id = users.id AND permissions.token = $3
+                ))
+                AND ($4::BOOL IS NULL OR users.active = $4)
+                GROUP BY users.id
+                ORDER BY
+                    CASE WHEN $5 = 'username' AND NOT $6 THEN LOWER(users.username) END,
+                    CASE WHEN $5 = 'username' AND $6 THEN LOWER(users.username) END DESC,
+                    CASE WHEN $5 = 'created' AND NOT $6 THEN users.created_at END,
+                    CASE WHEN $5 = 'created' AND $6 THEN users.created_at END DESC,
+                    CASE WHEN NOT $6 THEN LOWER(users.family_name) END,
+                    CASE WHEN $6 THEN LOWER(users.family_name) END DESC,
+                    LOWER(users.name), users.id
+                LIMIT $7 OFFSET $8
+                "#,
+                user.id,
+                pattern,
+                permission,
+                active,
+                sort.code(),
+                descending,
+                USERS_PER_PAGE,
+                page.max(0) * USERS_PER_PAGE,
+            )
+            .fetch_all(&pool)
+            .await
+            .map_err(server_error)?;
+
+            let total = records.first().map(|r| r.total).unwrap_or_default();
+            let users = records
+                .into_iter()
+                .map(|row| {
+                    models::User {
+                        id: row.id,
+                        name: row.name,
+                        family_name: row.family_name,
+                        patronym: row.patronym,
+                        username: row.username,
+                        password: row.password,
+                        locale: row.locale,
+                        timezone: row.timezone,
+                        created_at: row.created_at,
+                        active: row.active,
+                    }
+                    .into_user_with_password(Some(row.tokens))
+                    .0
+                })
+                .collect();
+
+            return Ok(UserPage { users, total });
+        }
+    }
+
+    Err(forbidden(&auth, "err-forbidden-manage-users"))
+}
@@ -5,10 +5,12 @@ pub async fn login(
     username: String,
     password: String,
     remember: Option<String>,
-) -> Result<(), ServerFnError> {
+) -> Result<(), ServerFnError<crate::error::AppError>> {
     use bcrypt::verify;
 
     use crate::ctx::{auth, pool};
+    use crate::error::{server_error, AppError};
+    use crate::metrics;
     use crate::user::{User, UserPasshash};
 
     let pool = pool()?;
@@ -17,17 +19,26 @@ pub async fn login(
     let (user, UserPasshash(expected_passhash)) =
         User::get_from_username_with_passhash(username, &pool)
             .await
-            .ok_or_else(|| ServerFnError::new("Пользователь не найден."))?;
+            .ok_or_else(|| {
+                metrics::login_failed();
+                AppError::validation("username", "err-login-unknown-user")
+            })?;
 
-    match verify(password, &expected_passhash)? {
+    match verify(password, &expected_passhash).map_err(server_error)? {
+        true if !user.active => {
+            metrics::login_failed();
+            Err(AppError::validation("username", "err-login-inactive").into())
+        }
         true => {
+            metrics::login_succeeded();
             auth.login_user(user.id);
             auth.remember_user(remember.is_some());
             leptos_axum::redirect("/");
             Ok(())
         }
-        false => Err(ServerFnError::ServerError(
-            "Проверьте введенные логин и пароль.".to_string(),
-        )),
+        false => {
+            metrics::login_failed();
+            Err(AppError::validation("password", "err-login-failed").into())
+        }
     }
 }
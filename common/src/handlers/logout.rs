@@ -1,7 +1,7 @@
 use leptos::*;
 
 #[server(Logout, "/api")]
-pub async fn logout() -> Result<(), ServerFnError> {
+pub async fn logout() -> Result<(), ServerFnError<crate::error::AppError>> {
     use crate::ctx::auth;
 
     let auth = auth()?;
@@ -1,23 +1,59 @@
+mod delete_plan;
+mod edit_comment;
+mod get_digests;
 mod get_report;
 mod get_user;
+mod list_anomalies;
+mod list_attachments;
+mod list_comments;
 mod list_dates;
+mod list_exchange_rates;
+mod list_locations;
+mod list_missing_reports;
+mod list_plans;
 mod list_reports;
 mod list_users;
+mod list_users_page;
 mod login;
 mod logout;
 mod new_report;
 mod new_user;
+mod post_comment;
+mod resolve_anomaly;
+mod save_digests;
+mod save_plan;
+mod search;
+mod set_user_active;
+mod sync_report;
 mod update_report;
 mod update_user;
 
+pub use delete_plan::*;
+pub use edit_comment::*;
+pub use get_digests::*;
 pub use get_report::*;
 pub use get_user::*;
+pub use list_anomalies::*;
+pub use list_attachments::*;
+pub use list_comments::*;
 pub use list_dates::*;
+pub use list_exchange_rates::*;
+pub use list_locations::*;
+pub use list_missing_reports::*;
+pub use list_plans::*;
 pub use list_reports::*;
 pub use list_users::*;
+pub use list_users_page::*;
 pub use login::*;
 pub use logout::*;
 pub use new_report::*;
 pub use new_user::*;
+pub use post_comment::*;
+pub use resolve_anomaly::*;
+pub use save_digests::*;
+pub use save_plan::*;
+pub use search::*;
+pub use set_user_active::*;
+pub use sync_report::*;
 pub use update_report::*;
 pub use update_user::*;
@@ -2,51 +2,34 @@ use leptos::*;
 
 #[server(NewReport, "/api")]
 pub async fn new_report(
-    revenue: String,
+    payments: crate::models::PaymentFields,
+    shift: crate::models::ShiftFields,
+    currency: String,
     address: String,
     date: chrono::NaiveDate,
-) -> Result<(), ServerFnError> {
-    use std::str::FromStr;
-
+    confirm: bool,
+) -> Result<(), ServerFnError<crate::error::AppError>> {
     use axum_session_auth::HasPermission;
-    use chrono::{Datelike, NaiveDate, Utc};
-    use sqlx_postgres::types::PgMoney;
 
-    use crate::moneys::Moneys;
     use crate::{
-        ctx::{auth, pool},
+        ctx::{auth, forbidden, pool},
+        models::ReportFields,
         perms::EDIT_OWNED,
     };
 
-    let revenue = Moneys::from_str(revenue.as_str())?;
     let pool = pool()?;
     let auth = auth()?;
 
     if let Some(user) = auth.current_user.as_ref() {
         if user.has(EDIT_OWNED, &Some(&pool)).await {
-            let user_id = user.id;
-
-            let now = Utc::now().date_naive();
-            let min_date = NaiveDate::from_ymd_opt(now.year(), now.month(), 1).unwrap();
-
-            if date < min_date || date > now {
-                return Err(ServerFnError::Request(
-                    "Дата за пределами допустимой".to_string(),
-                ));
-            }
-
-            sqlx::query!(
-                r#"
-                INSERT INTO entries (date, revenue, by_user_id, address)
-                VALUES ($1, $2, $3, $4)
-                "#,
+            let report = ReportFields {
+                payments,
+                shift,
+                currency,
+                address,
                 date,
-                PgMoney(revenue.0),
-                user_id,
-                address
-            )
-            .execute(&pool)
-            .await?;
+            };
+            insert_report(&pool, user, report, confirm, None).await?;
 
             leptos_axum::redirect("/");
 
@@ -54,7 +37,198 @@ pub async fn new_report(
         }
     }
 
-    Err(ServerFnError::ServerError(
-        "Пользователь не авторизован для добавления отчетов".to_string(),
-    ))
+    Err(forbidden(&auth, "err-forbidden-add-reports"))
+}
+
+/// Files the user's report, without the unusual revenue warning when `confirm`. A report
+/// from the offline queue is recorded as synced under its `client_id` along with it.
+#[cfg(feature = "ssr")]
+pub(crate) async fn insert_report(
+    pool: &sqlx::PgPool,
+    user: &crate::user::User,
+    report: crate::models::ReportFields,
+    confirm: bool,
+    client_id: Option<crate::IdType>,
+) -> Result<crate::IdType, ServerFnError<crate::error::AppError>> {
+    use crate::anomaly::ssr::check_entry;
+    use crate::error::{server_error, AppError};
+    use crate::models::{Entry, Payment};
+    use crate::moneys::Currency;
+    use crate::offline::ssr::EntrySync;
+    use crate::{ctx::anomaly_thresholds, metrics, models::Location, tz};
+
+    let crate::models::ReportFields {
+        payments,
+        shift,
+        currency,
+        address,
+        date,
+    } = report;
+
+    let currency = match currency.trim() {
+        "" => None,
+        code => Some(
+            code.parse::<Currency>()
+                .map_err(|err| AppError::validation("currency", err.message()))?,
+        ),
+    };
+    let (shift, shift_label) = shift.parse()?;
+    let clash = |existing| match shift {
+        Some(_) => AppError::overlapping_shift(existing),
+        None => AppError::duplicate_entry(existing),
+    };
+    let user_id = user.id;
+
+    let timezone = tz::resolve(
+        Location::timezone(pool, &address)
+            .await
+            .map_err(server_error)?,
+        user.timezone,
+    );
+
+    if !tz::in_report_window(date, tz::today(timezone)) {
+        return Err(AppError::validation("date", "err-report-date-range").into());
+    }
+
+    let default_currency = Location::default_currency(pool, &address, currency.unwrap_or_default())
+        .await
+        .map_err(server_error)?;
+    let (payments, revenue) = payments.parse(currency.unwrap_or(default_currency))?;
+
+    if let Some(existing) = Entry::find_overlapping(pool, user_id, &address, date, shift, None)
+        .await
+        .map_err(server_error)?
+    {
+        return Err(clash(existing).into());
+    }
+
+    let anomaly = check_entry(
+        pool,
+        &anomaly_thresholds(),
+        user_id,
+        &address,
+        date,
+        revenue,
+        None,
+    )
+    .await
+    .map_err(server_error)?;
+
+    if let (Some(anomaly), false) = (anomaly.as_ref(), confirm) {
+        return Err(AppError::validation("anomaly", anomaly).into());
+    }
+
+    let mut tx = pool.begin().await.map_err(server_error)?;
+
+    // checked again against managers filing at the location concurrently
+    Entry::lock_location(&mut *tx, &address)
+        .await
+        .map_err(server_error)?;
+    if let Some(existing) = Entry::find_overlapping(&mut *tx, user_id, &address, date, shift, None)
+        .await
+        .map_err(server_error)?
+    {
+        return Err(clash(existing).into());
+    }
+
+    let entry_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO entries (date, revenue, currency, by_user_id, address, anomaly, shift_start, shift_end, shift_label)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        ON CONFLICT DO NOTHING
+        RETURNING id
+        "#,
+        date,
+        revenue.to_numeric(),
+        revenue.currency.code(),
+        user_id,
+        address,
+        anomaly,
+        shift.map(|s| s.start),
+        shift.map(|s| s.end),
+        shift_label
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(server_error)?;
+
+    // filed concurrently since the check above
+    let Some(entry_id) = entry_id else {
+        let existing = Entry::find_overlapping(&mut *tx, user_id, &address, date, shift, None)
+            .await
+            .map_err(server_error)?
+            .unwrap_or_default();
+        return Err(clash(existing).into());
+    };
+
+    Payment::replace(&mut tx, entry_id, &payments)
+        .await
+        .map_err(server_error)?;
+
+    if let Some(client_id) = client_id {
+        EntrySync::record(&mut *tx, client_id, user_id, entry_id, 1)
+            .await
+            .map_err(server_error)?;
+    }
+
+    tx.commit().await.map_err(server_error)?;
+
+    metrics::entry_created();
+
+    Ok(entry_id)
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+    use crate::{
+        migrations::ScratchDatabase,
+        models::{self, PaymentFields, ReportFields, ShiftFields},
+        tz,
+        user::User,
+    };
+
+    #[tokio::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn concurrent_overlapping_shifts_are_refused() {
+        let db = ScratchDatabase::create().await;
+        let pool = &db.pool;
+
+        let mut managers = vec![];
+        for username in ["ivanov", "petrov"] {
+            let id = models::User::insert(pool, username, "-", "Иван", "Иванов", None)
+                .await
+                .unwrap();
+            managers.push(User::get(id, pool).await.unwrap());
+        }
+
+        let report = |start: &str, end: &str| ReportFields {
+            payments: PaymentFields {
+                cash: "100".to_string(),
+                ..Default::default()
+            },
+            shift: ShiftFields {
+                start: start.to_string(),
+                end: end.to_string(),
+                label: String::new(),
+            },
+            address: "Lenina 1".to_string(),
+            date: tz::today(tz::DEFAULT),
+            ..Default::default()
+        };
+
+        let (morning, day) = tokio::join!(
+            insert_report(pool, &managers[0], report("08:00", "14:00"), true, None),
+            insert_report(pool, &managers[1], report("12:00", "20:00"), true, None),
+        );
+
+        assert!(morning.is_ok() != day.is_ok(), "{morning:?} {day:?}");
+        let count: i64 = sqlx::query_scalar("SELECT count(*) FROM entries")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+
+        db.drop().await;
+    }
 }
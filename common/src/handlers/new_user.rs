@@ -8,55 +8,59 @@ pub async fn new_user(
     family_name: String,
     patronym: Option<String>,
     is_admin: Option<String>,
-) -> Result<(), ServerFnError> {
+    settings: crate::user::SettingsFields,
+) -> Result<(), ServerFnError<crate::error::AppError>> {
     use axum_session_auth::HasPermission;
     use bcrypt::{hash, DEFAULT_COST};
 
     use crate::{
-        ctx::{auth, pool},
-        perms::{EDIT_OWNED, MANAGE_USERS, VIEW_ALL, VIEW_OWNED},
+        ctx::{auth, forbidden, pool},
+        error::{server_error, AppError},
+        models,
+        perms::{ADMIN, MANAGER, MANAGE_USERS},
     };
 
+    let (locale, timezone) = settings.parse()?;
+
+    if username.trim().is_empty() {
+        return Err(AppError::validation("username", "err-username-empty").into());
+    }
+    if password.is_empty() {
+        return Err(AppError::validation("password", "err-password-empty").into());
+    }
+
     let pool = pool()?;
     let auth = auth()?;
 
     if let Some(user) = auth.current_user.as_ref() {
         if user.has(MANAGE_USERS, &Some(&pool)).await {
-            let pwd = hash(password, DEFAULT_COST)?;
+            let pwd = hash(password, DEFAULT_COST).map_err(server_error)?;
 
-            let user_id = sqlx::query!(
-                r#"
-                INSERT INTO users (name, family_name, patronym, username, password)
-                VALUES ($1, $2, $3, $4, $5)
-                RETURNING id
-                "#,
-                name,
-                family_name,
-                patronym,
-                username,
-                pwd,
+            let user_id = models::User::insert(
+                &pool,
+                &username,
+                &pwd,
+                &name,
+                &family_name,
+                patronym.as_deref(),
             )
-            .fetch_one(&pool)
-            .await?
-            .id;
+            .await
+            .map_err(|err| match models::User::is_username_taken(&err) {
+                true => AppError::validation("username", "err-username-taken").into(),
+                false => server_error(err),
+            })?;
 
-            let permissions = if is_admin.is_some() {
-                [MANAGE_USERS, VIEW_ALL]
-            } else {
-                [EDIT_OWNED, VIEW_OWNED]
-            };
+            let permissions = if is_admin.is_some() { ADMIN } else { MANAGER };
 
-            sqlx::query!(
-                r#"
-                INSERT INTO permissions (user_id, token)
-                VALUES ($1, $2), ($1, $3)
-                "#,
-                user_id,
-                permissions[0],
-                permissions[1],
-            )
-            .execute(&pool)
-            .await?;
+            models::User::grant(&pool, user_id, &permissions)
+                .await
+                .map_err(server_error)?;
+            models::User::set_locale(&pool, user_id, locale)
+                .await
+                .map_err(server_error)?;
+            models::User::set_timezone(&pool, user_id, timezone)
+                .await
+                .map_err(server_error)?;
 
             leptos_axum::redirect("/users");
 
@@ -64,7 +68,5 @@ pub async fn new_user(
         }
     }
 
-    Err(ServerFnError::ServerError(
-        "Пользователь не авторизован для управления другими пользователями".to_string(),
-    ))
+    Err(forbidden(&auth, "err-forbidden-manage-users"))
 }
@@ -0,0 +1,43 @@
+use leptos::*;
+
+/// Adds a comment to an entry's thread, a reply when `parent_id` is given.
+#[server(PostComment, "/api")]
+pub async fn post_comment(
+    entry_id: crate::IdType,
+    parent_id: Option<crate::IdType>,
+    body: String,
+) -> Result<(), ServerFnError<crate::error::AppError>> {
+    use crate::ctx::{auth, forbidden, pool};
+    use crate::error::{server_error, AppError};
+    use crate::models::{Comment, Entry};
+
+    let body = match Comment::validate_body(&body) {
+        Ok(body) => body,
+        Err(message) => return Err(AppError::validation("body", message).into()),
+    };
+
+    let pool = pool()?;
+    let auth = auth()?;
+
+    if let Some(user) = auth.current_user.as_ref() {
+        if let Some(owner_filter) = Entry::owner_filter(user, &pool).await {
+            if Entry::get_visible(&pool, entry_id, owner_filter)
+                .await
+                .map_err(server_error)?
+                .is_some()
+            {
+                if Comment::insert(&pool, entry_id, parent_id, user.id, body)
+                    .await
+                    .map_err(server_error)?
+                    .is_none()
+                {
+                    return Err(AppError::NotFound("err-comment-parent-missing".to_string()).into());
+                }
+
+                return Ok(());
+            }
+        }
+    }
+
+    Err(forbidden(&auth, "err-forbidden-discuss-report"))
+}
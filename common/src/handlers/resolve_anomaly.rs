@@ -0,0 +1,30 @@
+use leptos::*;
+
+#[server(ResolveAnomaly, "/api")]
+pub async fn resolve_anomaly(
+    id: crate::IdType,
+) -> Result<(), ServerFnError<crate::error::AppError>> {
+    use axum_session_auth::HasPermission;
+
+    use crate::{
+        ctx::{auth, forbidden, pool},
+        error::server_error,
+        models::EntryWithUser,
+        perms::MANAGE_USERS,
+    };
+
+    let pool = pool()?;
+    let auth = auth()?;
+
+    if let Some(user) = auth.current_user.as_ref() {
+        if user.has(MANAGE_USERS, &Some(&pool)).await {
+            EntryWithUser::resolve_anomaly(&pool, id)
+                .await
+                .map_err(server_error)?;
+
+            return Ok(());
+        }
+    }
+
+    Err(forbidden(&auth, "err-forbidden-review-reports"))
+}
@@ -0,0 +1,32 @@
+use leptos::*;
+
+/// Subscribes the user to the checked digests, unsubscribing from the others.
+#[server(SaveDigests, "/api")]
+pub async fn save_digests(
+    user_id: crate::IdType,
+    digests: crate::digest::DigestFields,
+) -> Result<(), ServerFnError<crate::error::AppError>> {
+    use axum_session_auth::HasPermission;
+
+    use crate::ctx::{auth, forbidden, pool};
+    use crate::digest::DigestSubscription;
+    use crate::error::server_error;
+    use crate::perms::MANAGE_USERS;
+
+    let (email, frequencies) = digests.parse()?;
+
+    let pool = pool()?;
+    let auth = auth()?;
+
+    if let Some(user) = auth.current_user.as_ref() {
+        if user.id == user_id || user.has(MANAGE_USERS, &Some(&pool)).await {
+            DigestSubscription::replace(&pool, user_id, &email, &frequencies)
+                .await
+                .map_err(server_error)?;
+
+            return Ok(());
+        }
+    }
+
+    Err(forbidden(&auth, "err-forbidden-edit-user"))
+}
@@ -0,0 +1,83 @@
+use leptos::*;
+
+/// Sets a monthly target. `scope` is `manager:<user id>` or `location:<location id>`,
+/// an empty `currency` means the location's default or the reporting currency.
+#[server(SavePlan, "/api")]
+pub async fn save_plan(
+    year: i32,
+    month: u32,
+    scope: String,
+    target: String,
+    currency: String,
+) -> Result<(), ServerFnError<crate::error::AppError>> {
+    use axum_session_auth::HasPermission;
+
+    use crate::{
+        ctx::{auth, forbidden, pool, reporting_currency},
+        error::{server_error, AppError},
+        models::{Location, Plan, PlanScope},
+        moneys::{Currency, Moneys},
+        perms::MANAGE_USERS,
+        IdType,
+    };
+
+    let pool = pool()?;
+    let auth = auth()?;
+
+    if let Some(user) = auth.current_user.as_ref() {
+        if user.has(MANAGE_USERS, &Some(&pool)).await {
+            if !(1..=12).contains(&month) {
+                return Err(AppError::validation("month", "err-plan-month").into());
+            }
+
+            let invalid_scope =
+                || ServerFnError::from(AppError::validation("scope", "err-plan-scope"));
+
+            let (kind, id) = scope.split_once(':').ok_or_else(invalid_scope)?;
+            let id = id.parse::<IdType>().map_err(|_| invalid_scope())?;
+
+            let (scope, default_currency) = match kind {
+                "manager" => (PlanScope::Manager(id), reporting_currency()),
+                "location" => {
+                    let location = Location::get(&pool, id)
+                        .await
+                        .map_err(server_error)?
+                        .ok_or_else(invalid_scope)?;
+                    (
+                        PlanScope::Location {
+                            id,
+                            address: location.address,
+                        },
+                        location.currency,
+                    )
+                }
+                _ => return Err(invalid_scope()),
+            };
+
+            let currency = match currency.trim() {
+                "" => default_currency,
+                code => match code.parse::<Currency>() {
+                    Ok(currency) => currency,
+                    Err(err) => return Err(AppError::validation("currency", err.message()).into()),
+                },
+            };
+
+            let target = match Moneys::parse(&target, currency) {
+                Ok(target) => target,
+                Err(err) => return Err(AppError::validation("target", err.message()).into()),
+            };
+
+            if target.amount <= 0 {
+                return Err(AppError::validation("target", "err-plan-not-positive").into());
+            }
+
+            Plan::upsert(&pool, year, month, &scope, target)
+                .await
+                .map_err(server_error)?;
+
+            return Ok(());
+        }
+    }
+
+    Err(forbidden(&auth, "err-forbidden-edit-plans"))
+}
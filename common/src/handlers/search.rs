@@ -0,0 +1,34 @@
+use leptos::*;
+
+/// Managers, addresses and dates matching `query`. Users who only see their own reports
+/// search those and get no managers.
+#[server(Search, "/api", "GetJson")]
+pub async fn search(
+    query: String,
+) -> Result<crate::search::SearchResults, ServerFnError<crate::error::AppError>> {
+    use crate::{
+        ctx::{auth, forbidden, pool},
+        error::server_error,
+        models::Entry,
+        search::{SearchResults, MIN_QUERY_CHARS},
+    };
+
+    let pool = pool()?;
+    let auth = auth()?;
+
+    if let Some(user) = auth.current_user.as_ref() {
+        if let Some(owner) = Entry::owner_filter(user, &pool).await {
+            if query.trim().chars().count() < MIN_QUERY_CHARS {
+                return Ok(SearchResults::default());
+            }
+
+            let results = SearchResults::find(&pool, &query, owner)
+                .await
+                .map_err(server_error)?;
+
+            return Ok(results);
+        }
+    }
+
+    Err(forbidden(&auth, "err-forbidden-view-reports"))
+}
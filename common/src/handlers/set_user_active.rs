@@ -0,0 +1,39 @@
+use leptos::*;
+
+#[server(SetUserActive, "/api")]
+pub async fn set_user_active(
+    id: crate::IdType,
+    active: bool,
+) -> Result<(), ServerFnError<crate::error::AppError>> {
+    use axum_session_auth::HasPermission;
+
+    use crate::{
+        ctx::{auth, forbidden, pool},
+        error::{server_error, AppError},
+        models,
+        perms::MANAGE_USERS,
+    };
+
+    let pool = pool()?;
+    let auth = auth()?;
+
+    if let Some(user) = auth.current_user.as_ref() {
+        if user.has(MANAGE_USERS, &Some(&pool)).await {
+            if user.id == id && !active {
+                return Err(AppError::Conflict("err-deactivate-self".to_string()).into());
+            }
+
+            let found = models::User::set_active(&pool, id, active)
+                .await
+                .map_err(server_error)?;
+            if !found {
+                return Err(AppError::NotFound("err-user-not-found".to_string()).into());
+            }
+            auth.cache_clear_user(id);
+
+            return Ok(());
+        }
+    }
+
+    Err(forbidden(&auth, "err-forbidden-manage-users"))
+}
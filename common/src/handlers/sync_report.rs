@@ -0,0 +1,87 @@
+use leptos::*;
+
+/// Applies a report queued offline, see [`crate::offline::QueuedReport`]. A change
+/// synced before isn't applied again, and one the server's version conflicts with
+/// returns that version.
+#[server(SyncReport, "/api")]
+pub async fn sync_report(
+    client_id: crate::IdType,
+    entry_id: Option<crate::IdType>,
+    base_revision: Option<i32>,
+    report: crate::models::ReportFields,
+) -> Result<crate::offline::SyncOutcome, ServerFnError<crate::error::AppError>> {
+    use axum_session_auth::HasPermission;
+
+    use super::{new_report::insert_report, update_report::change_report};
+    use crate::{
+        ctx::{auth, forbidden, pool},
+        error::server_error,
+        models::{Entry, Payment},
+        offline::{ssr::EntrySync, SyncOutcome},
+        perms::EDIT_OWNED,
+    };
+
+    let pool = pool()?;
+    let auth = auth()?;
+
+    if let Some(user) = auth.current_user.as_ref() {
+        if user.has(EDIT_OWNED, &Some(&pool)).await {
+            let applied = |(entry_id, revision)| SyncOutcome::Applied { entry_id, revision };
+
+            if let Some(synced) = EntrySync::find(&pool, client_id, user.id)
+                .await
+                .map_err(server_error)?
+            {
+                return Ok(applied(synced));
+            }
+
+            // anomalies can't be confirmed offline, they are flagged for review instead
+            let result = match (entry_id, base_revision) {
+                (Some(id), Some(base)) => {
+                    change_report(&pool, user, id, report, true, Some((client_id, base)))
+                        .await
+                        .map(|revision| (id, revision))
+                }
+                _ => insert_report(&pool, user, report, true, Some(client_id))
+                    .await
+                    .map(|id| (id, 1)),
+            };
+
+            let err = match result {
+                Ok(synced) => return Ok(applied(synced)),
+                Err(err) => err,
+            };
+
+            // the same change synced concurrently
+            if let Some(synced) = EntrySync::find(&pool, client_id, user.id)
+                .await
+                .map_err(server_error)?
+            {
+                return Ok(applied(synced));
+            }
+
+            // the manager's own report of the location and date, or of the change, differs
+            let existing = match &err {
+                ServerFnError::WrappedServerError(app_err) => app_err.duplicate_of(),
+                _ => None,
+            };
+            if let Some(existing) = existing {
+                if let Some(mut server) = Entry::get_visible(&pool, existing, Some(user.id))
+                    .await
+                    .map_err(server_error)?
+                {
+                    server.payments = Payment::for_entries(&pool, &[server.id])
+                        .await
+                        .map_err(server_error)?
+                        .remove(&server.id)
+                        .unwrap_or_default();
+                    return Ok(SyncOutcome::Conflict(server));
+                }
+            }
+
+            return Err(err);
+        }
+    }
+
+    Err(forbidden(&auth, "err-forbidden-add-reports"))
+}
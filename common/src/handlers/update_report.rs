@@ -3,56 +3,34 @@ use leptos::*;
 #[server(UpdateReport, "/api")]
 pub async fn update_report(
     id: crate::IdType,
-    revenue: String,
+    payments: crate::models::PaymentFields,
+    shift: crate::models::ShiftFields,
+    currency: String,
     address: String,
     date: chrono::NaiveDate,
-) -> Result<(), ServerFnError> {
-    use std::str::FromStr;
-
+    confirm: bool,
+) -> Result<(), ServerFnError<crate::error::AppError>> {
     use axum_session_auth::HasPermission;
-    use chrono::{Datelike, NaiveDate, Utc};
-    use sqlx_postgres::types::PgMoney;
 
-    use crate::moneys::Moneys;
     use crate::{
-        ctx::{auth, pool},
+        ctx::{auth, forbidden, pool},
+        models::ReportFields,
         perms::EDIT_OWNED,
     };
 
-    let revenue = Moneys::from_str(revenue.as_str())?;
     let pool = pool()?;
     let auth = auth()?;
 
     if let Some(user) = auth.current_user.as_ref() {
         if user.has(EDIT_OWNED, &Some(&pool)).await {
-            let now = Utc::now().date_naive();
-            let min_date = NaiveDate::from_ymd_opt(now.year(), now.month(), 1).unwrap();
-
-            if date < min_date || date > now {
-                return Err(ServerFnError::Request(
-                    "Дата за пределами допустимой".to_string(),
-                ));
-            }
-
-            let user_id = user.id;
-
-            sqlx::query!(
-                r#"
-                UPDATE entries
-                SET revenue = $1,
-                address = $2
-                WHERE by_user_id = $3 AND
-                id = $4 AND
-                date >= $5
-                "#,
-                PgMoney(revenue.0),
+            let report = ReportFields {
+                payments,
+                shift,
+                currency,
                 address,
-                user_id,
-                id,
-                min_date
-            )
-            .execute(&pool)
-            .await?;
+                date,
+            };
+            change_report(&pool, user, id, report, confirm, None).await?;
 
             leptos_axum::redirect("/reports");
 
@@ -60,7 +38,259 @@ pub async fn update_report(
         }
     }
 
-    Err(ServerFnError::ServerError(
-        "Пользователь не авторизован для добавления отчетов".to_string(),
-    ))
+    Err(forbidden(&auth, "err-forbidden-add-reports"))
+}
+
+/// Changes the user's report `id` and returns its new revision, without the unusual
+/// revenue warning when `confirm`. A change from the offline queue comes with its
+/// `client_id` and the revision it was made to: it's refused when the report changed
+/// since, and recorded as synced otherwise.
+#[cfg(feature = "ssr")]
+pub(crate) async fn change_report(
+    pool: &sqlx::PgPool,
+    user: &crate::user::User,
+    id: crate::IdType,
+    report: crate::models::ReportFields,
+    confirm: bool,
+    sync: Option<(crate::IdType, i32)>,
+) -> Result<i32, ServerFnError<crate::error::AppError>> {
+    use crate::anomaly::ssr::check_entry;
+    use crate::error::{server_error, AppError};
+    use crate::models::{Entry, Payment};
+    use crate::moneys::Currency;
+    use crate::offline::ssr::EntrySync;
+    use crate::{ctx::anomaly_thresholds, models::Location, tz};
+
+    let crate::models::ReportFields {
+        payments,
+        shift,
+        currency,
+        address,
+        // the date of a report isn't changed
+        date: _,
+    } = report;
+
+    let currency = match currency.trim() {
+        "" => None,
+        code => Some(
+            code.parse::<Currency>()
+                .map_err(|err| AppError::validation("currency", err.message()))?,
+        ),
+    };
+    let (shift, shift_label) = shift.parse()?;
+    let clash = |existing| match shift {
+        Some(_) => AppError::overlapping_shift(existing),
+        None => AppError::duplicate_entry(existing),
+    };
+
+    let timezone = tz::resolve(
+        Location::timezone(pool, &address)
+            .await
+            .map_err(server_error)?,
+        user.timezone,
+    );
+
+    let user_id = user.id;
+
+    let Some(current) = Entry::get_visible(pool, id, Some(user_id))
+        .await
+        .map_err(server_error)?
+    else {
+        return Err(AppError::Forbidden("err-report-not-editable".to_string()).into());
+    };
+
+    let today = tz::today(timezone);
+    let (min_date, _) = tz::report_window(today);
+
+    if !tz::in_report_window(current.date, today) {
+        return Err(AppError::validation("date", "err-report-date-range").into());
+    }
+
+    let default_currency = Location::default_currency(pool, &address, currency.unwrap_or_default())
+        .await
+        .map_err(server_error)?;
+    let (payments, revenue) = payments.parse(currency.unwrap_or(default_currency))?;
+
+    let base_revision = sync.map(|(_, revision)| revision);
+    if base_revision.is_some_and(|base| base != current.revision) {
+        return Err(AppError::changed_meanwhile(id).into());
+    }
+
+    if let Some(existing) =
+        Entry::find_overlapping(pool, user_id, &address, current.date, shift, Some(id))
+            .await
+            .map_err(server_error)?
+    {
+        return Err(clash(existing).into());
+    }
+
+    let anomaly = check_entry(
+        pool,
+        &anomaly_thresholds(),
+        user_id,
+        &address,
+        current.date,
+        revenue,
+        Some(id),
+    )
+    .await
+    .map_err(server_error)?;
+
+    if let (Some(anomaly), false) = (anomaly.as_ref(), confirm) {
+        return Err(AppError::validation("anomaly", anomaly).into());
+    }
+
+    let mut tx = pool.begin().await.map_err(server_error)?;
+
+    // checked again against managers filing at the location concurrently
+    Entry::lock_location(&mut *tx, &address)
+        .await
+        .map_err(server_error)?;
+    if let Some(existing) =
+        Entry::find_overlapping(&mut *tx, user_id, &address, current.date, shift, Some(id))
+            .await
+            .map_err(server_error)?
+    {
+        return Err(clash(existing).into());
+    }
+
+    let updated = sqlx::query!(
+        r#"
+        UPDATE entries
+        SET revenue = $1,
+        currency = $2,
+        address = $3,
+        anomaly = $7,
+        shift_start = $8,
+        shift_end = $9,
+        shift_label = $10,
+        revision = revision + 1
+        WHERE by_user_id = $4 AND
+        id = $5 AND
+        date >= $6 AND
+        ($11::INTEGER IS NULL OR revision = $11)
+        RETURNING id, revision
+        "#,
+        revenue.to_numeric(),
+        revenue.currency.code(),
+        address,
+        user_id,
+        id,
+        min_date,
+        anomaly,
+        shift.map(|s| s.start),
+        shift.map(|s| s.end),
+        shift_label,
+        base_revision
+    )
+    .fetch_optional(&mut *tx)
+    .await;
+
+    let updated = match updated {
+        // moved to the location or shift concurrently since the check above
+        Err(err)
+            if err
+                .as_database_error()
+                .is_some_and(|e| e.is_unique_violation()) =>
+        {
+            let existing =
+                Entry::find_overlapping(pool, user_id, &address, current.date, shift, Some(id))
+                    .await
+                    .map_err(server_error)?
+                    .unwrap_or_default();
+            return Err(clash(existing).into());
+        }
+        // changed concurrently since the revision was checked
+        Ok(None) if base_revision.is_some() => {
+            return Err(AppError::changed_meanwhile(id).into());
+        }
+        updated => updated,
+    }
+    .map_err(server_error)?
+    .ok_or_else(|| AppError::Forbidden("err-report-not-editable".to_string()))?;
+
+    Payment::replace(&mut tx, updated.id, &payments)
+        .await
+        .map_err(server_error)?;
+
+    if let Some((client_id, _)) = sync {
+        EntrySync::record(&mut *tx, client_id, user_id, updated.id, updated.revision)
+            .await
+            .map_err(server_error)?;
+    }
+
+    tx.commit().await.map_err(server_error)?;
+
+    Ok(updated.revision)
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use chrono::{Days, NaiveDate};
+
+    use super::*;
+    use crate::{
+        migrations::ScratchDatabase,
+        models::{self, PaymentFields, ReportFields},
+        tz,
+        user::User,
+    };
+
+    #[tokio::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn entries_before_the_window_are_not_changed() {
+        let db = ScratchDatabase::create().await;
+        let pool = &db.pool;
+
+        let user_id = models::User::insert(pool, "ivanov", "-", "Иван", "Иванов", None)
+            .await
+            .unwrap();
+        let user = User::get(user_id, pool).await.unwrap();
+
+        let today = tz::today(tz::DEFAULT);
+        let (first, _) = tz::report_window(today);
+        let last_month = first - Days::new(1);
+        let id: crate::IdType = sqlx::query_scalar(
+            r#"
+            INSERT INTO entries (address, revenue, date, by_user_id)
+            VALUES ('Lenina 1', 100, $1, $2)
+            RETURNING id
+            "#,
+        )
+        .bind(last_month)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+
+        // the date sent in the form is within the window, the stored one isn't
+        let report = ReportFields {
+            payments: PaymentFields {
+                cash: "200".to_string(),
+                ..Default::default()
+            },
+            address: "Lenina 1".to_string(),
+            date: today,
+            ..Default::default()
+        };
+        let err = change_report(pool, &user, id, report, true, None)
+            .await
+            .unwrap_err();
+
+        let ServerFnError::WrappedServerError(err) = err else {
+            panic!("{err}");
+        };
+        assert_eq!(err.field(), Some("date"));
+        assert_eq!(err.message(), "err-report-date-range");
+
+        let stored: (NaiveDate, i32) =
+            sqlx::query_as("SELECT date, revision FROM entries WHERE id = $1")
+                .bind(id)
+                .fetch_one(pool)
+                .await
+                .unwrap();
+        assert_eq!(stored, (last_month, 1));
+
+        db.drop().await;
+    }
 }
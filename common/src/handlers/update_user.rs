@@ -9,12 +9,20 @@ pub async fn update_user(
     family_name: String,
     patronym: Option<String>,
     // is_admin: Option<String>,
-) -> Result<(), ServerFnError> {
+    settings: crate::user::SettingsFields,
+) -> Result<(), ServerFnError<crate::error::AppError>> {
     use axum_session_auth::HasPermission;
     use bcrypt::{hash, DEFAULT_COST};
 
-    use crate::ctx::{auth, pool};
-    use crate::perms::MANAGE_USERS;
+    use crate::ctx::{auth, forbidden, pool};
+    use crate::error::{server_error, AppError};
+    use crate::{models, perms::MANAGE_USERS};
+
+    let (locale, timezone) = settings.parse()?;
+
+    if username.trim().is_empty() {
+        return Err(AppError::validation("username", "err-username-empty").into());
+    }
 
     let pool = pool()?;
     let auth = auth()?;
@@ -24,7 +32,7 @@ pub async fn update_user(
         let is_updating_self = user.id == id;
         if can_manage_users || is_updating_self {
             if let Some(password) = password {
-                let pwd = hash(password, DEFAULT_COST)?;
+                let pwd = hash(password, DEFAULT_COST).map_err(server_error)?;
 
                 sqlx::query!(
                     r#"UPDATE users
@@ -34,7 +42,8 @@ pub async fn update_user(
                     user.id
                 )
                 .execute(&pool)
-                .await?;
+                .await
+                .map_err(server_error)?;
             }
 
             sqlx::query!(
@@ -51,7 +60,20 @@ pub async fn update_user(
                 user.id
             )
             .execute(&pool)
-            .await?;
+            .await
+            .map_err(|err| match models::User::is_username_taken(&err) {
+                true => AppError::validation("username", "err-username-taken").into(),
+                false => server_error(err),
+            })?;
+
+            models::User::set_locale(&pool, user.id, locale)
+                .await
+                .map_err(server_error)?;
+            models::User::set_timezone(&pool, user.id, timezone)
+                .await
+                .map_err(server_error)?;
+            // the session keeps loaded users, the next request has to see the changes
+            auth.cache_clear_user(user.id);
 
             if is_updating_self {
                 leptos_axum::redirect("/");
@@ -63,7 +85,5 @@ pub async fn update_user(
         }
     }
 
-    Err(ServerFnError::ServerError(
-        "Пользователь не авторизован для редактирования этого пользователя".to_string(),
-    ))
+    Err(forbidden(&auth, "err-forbidden-edit-user"))
 }
@@ -0,0 +1,363 @@
+//! Interface languages. Texts live in the Fluent catalogs under `locales/`, code refers
+//! to them by message keys. Server functions fail with a [`Message`] encoded in the
+//! error string and the client renders it in the user's language.
+
+use std::{fmt::Display, str::FromStr, sync::OnceLock};
+
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource, FluentValue};
+use leptos::ServerFnError;
+use serde::{Deserialize, Serialize};
+use unic_langid::LanguageIdentifier;
+
+use crate::error::AppError;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    Ru,
+    En,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::Ru, Locale::En];
+
+    /// BCP 47 language tag, also stored in `users.locale`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::Ru => "ru",
+            Locale::En => "en",
+        }
+    }
+
+    /// Name of the language in the language itself, for the language picker.
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Locale::Ru => "Русский",
+            Locale::En => "English",
+        }
+    }
+
+    pub fn decimal_separator(&self) -> char {
+        match self {
+            Locale::Ru => ',',
+            Locale::En => '.',
+        }
+    }
+
+    /// Language picker value: empty to follow the browser, or a locale code.
+    pub fn parse_choice(choice: Option<&str>) -> Result<Option<Locale>, Message> {
+        match choice.map(str::trim).unwrap_or_default() {
+            "" => Ok(None),
+            code => code
+                .parse()
+                .map(Some)
+                .map_err(|_| Message::new("err-unknown-locale").arg("code", code)),
+        }
+    }
+
+    /// The most preferred supported language of an `Accept-Language` header.
+    pub fn negotiate(accept_language: &str) -> Option<Locale> {
+        let mut ranges = accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                let language = tag.split('-').next()?.to_ascii_lowercase();
+
+                (quality > 0.0).then_some((language, quality))
+            })
+            .collect::<Vec<_>>();
+
+        // stable, so equally preferred languages keep the header order
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranges
+            .into_iter()
+            .find_map(|(language, _)| language.parse().ok())
+    }
+
+    /// Text of a message without arguments.
+    pub fn tr(&self, key: &str) -> String {
+        self.format(key, None).unwrap_or_else(|| key.to_string())
+    }
+
+    pub fn message(&self, message: &Message) -> String {
+        let args = message.fluent_args();
+
+        self.format(&message.key, Some(&args))
+            .unwrap_or_else(|| message.key.clone())
+    }
+
+    /// Text of a string from the server: the translation when it's an encoded [`Message`]
+    /// with a known key, the string itself otherwise.
+    pub fn text(&self, s: &str) -> String {
+        s.parse::<Message>()
+            .ok()
+            .and_then(|message| {
+                let args = message.fluent_args();
+                self.format(&message.key, Some(&args))
+            })
+            .unwrap_or_else(|| s.to_string())
+    }
+
+    /// Error line about a failed server function call.
+    pub fn error(&self, err: &ServerFnError<AppError>) -> String {
+        let detail = match err {
+            ServerFnError::WrappedServerError(err) => self.text(err.message()),
+            ServerFnError::ServerError(message) => self.text(message),
+            err => self.message(&Message::new("err-request").arg("detail", err.to_string())),
+        };
+
+        self.message(&Message::new("error").arg("detail", detail))
+    }
+
+    /// Error line about a failure the server reported as a message or plain text.
+    pub fn error_text(&self, s: &str) -> String {
+        self.message(&Message::new("error").arg("detail", self.text(s)))
+    }
+
+    /// Falls back to the default locale for keys a catalog misses.
+    fn format(&self, key: &str, args: Option<&FluentArgs>) -> Option<String> {
+        [*self, Locale::default()].into_iter().find_map(|locale| {
+            let bundle = bundle(locale);
+            let pattern = bundle.get_message(key)?.value()?;
+            let mut errors = vec![];
+            let text = bundle.format_pattern(pattern, args, &mut errors);
+
+            if !errors.is_empty() {
+                log::warn!("message {key} in {}: {errors:?}", locale.code());
+            }
+
+            Some(text.into_owned())
+        })
+    }
+
+    fn catalog(&self) -> &'static str {
+        match self {
+            Locale::Ru => include_str!("../locales/ru.ftl"),
+            Locale::En => include_str!("../locales/en.ftl"),
+        }
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Locale::ALL
+            .into_iter()
+            .find(|locale| locale.code() == s)
+            .ok_or(())
+    }
+}
+
+fn bundle(locale: Locale) -> &'static FluentBundle<FluentResource> {
+    static BUNDLES: OnceLock<Vec<FluentBundle<FluentResource>>> = OnceLock::new();
+
+    let bundles = BUNDLES.get_or_init(|| {
+        Locale::ALL
+            .into_iter()
+            .map(|locale| {
+                let language: LanguageIdentifier =
+                    locale.code().parse().expect("locale codes are valid tags");
+                let resource = FluentResource::try_new(locale.catalog().to_string())
+                    .unwrap_or_else(|(_, errors)| panic!("{} catalog: {errors:?}", locale.code()));
+
+                let mut bundle = FluentBundle::new_concurrent(vec![language]);
+                // texts are plain strings in the DOM, the isolation marks would show up in inputs
+                bundle.set_use_isolating(false);
+                bundle
+                    .add_resource(resource)
+                    .unwrap_or_else(|errors| panic!("{} catalog: {errors:?}", locale.code()));
+
+                bundle
+            })
+            .collect()
+    });
+
+    &bundles[locale as usize]
+}
+
+/// A catalog message with its arguments. Travels as a string: the key, then the
+/// arguments as a query, `err-comment-too-long?max=#4000`, with `#` marking numbers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Message {
+    pub key: String,
+    pub args: Vec<(String, Arg)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Arg {
+    Text(String),
+    /// Numbers pick plural forms.
+    Number(f64),
+}
+
+impl Message {
+    pub fn new(key: &str) -> Self {
+        Message {
+            key: key.to_string(),
+            args: vec![],
+        }
+    }
+
+    pub fn arg(mut self, name: &str, value: impl Into<Arg>) -> Self {
+        self.args.push((name.to_string(), value.into()));
+        self
+    }
+
+    pub fn text_arg(&self, name: &str) -> Option<&str> {
+        self.args.iter().find_map(|(n, value)| match value {
+            Arg::Text(text) if n == name => Some(text.as_str()),
+            _ => None,
+        })
+    }
+
+    fn fluent_args(&self) -> FluentArgs<'_> {
+        let mut args = FluentArgs::new();
+
+        for (name, value) in &self.args {
+            let value = match value {
+                Arg::Text(text) => FluentValue::from(text.as_str()),
+                Arg::Number(number) => FluentValue::from(*number),
+            };
+            args.set(name.as_str(), value);
+        }
+
+        args
+    }
+}
+
+impl Display for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.key)?;
+
+        for (i, (name, value)) in self.args.iter().enumerate() {
+            let separator = if i == 0 { '?' } else { '&' };
+            match value {
+                Arg::Text(text) => write!(f, "{separator}{name}={}", escape(text))?,
+                Arg::Number(number) => write!(f, "{separator}{name}=#{number}")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Message {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, query) = s.split_once('?').unwrap_or((s, ""));
+
+        let is_key = key.starts_with(|c: char| c.is_ascii_lowercase())
+            && key
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if !is_key {
+            return Err(());
+        }
+
+        let mut message = Message::new(key);
+
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair.split_once('=').ok_or(())?;
+            let value = match value.strip_prefix('#') {
+                Some(number) => Arg::Number(number.parse().map_err(|_| ())?),
+                None => Arg::Text(unescape(value).ok_or(())?),
+            };
+            message.args.push((name.to_string(), value));
+        }
+
+        Ok(message)
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '%' => escaped.push_str("%25"),
+            '&' => escaped.push_str("%26"),
+            '#' => escaped.push_str("%23"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+fn unescape(text: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some((before, after)) = rest.split_once('%') {
+        unescaped.push_str(before);
+        let c = match after.get(..2)? {
+            "25" => '%',
+            "26" => '&',
+            "23" => '#',
+            _ => return None,
+        };
+        unescaped.push(c);
+        rest = &after[2..];
+    }
+    unescaped.push_str(rest);
+
+    Some(unescaped)
+}
+
+impl From<&str> for Arg {
+    fn from(value: &str) -> Self {
+        Arg::Text(value.to_string())
+    }
+}
+
+impl From<String> for Arg {
+    fn from(value: String) -> Self {
+        Arg::Text(value)
+    }
+}
+
+macro_rules! number_arg {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Arg {
+            fn from(value: $t) -> Self {
+                Arg::Number(value as f64)
+            }
+        })*
+    };
+}
+
+number_arg!(i32, i64, u32, u64, usize, f64);
+
+#[cfg(feature = "ssr")]
+pub mod ssr {
+    use axum::http::{header::ACCEPT_LANGUAGE, HeaderMap};
+
+    use super::Locale;
+    use crate::user::User;
+
+    /// The user's own choice, otherwise the browser's preference.
+    pub fn request_locale(user: Option<&User>, headers: &HeaderMap) -> Locale {
+        user.and_then(|user| user.locale)
+            .or_else(|| {
+                headers
+                    .get(ACCEPT_LANGUAGE)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(Locale::negotiate)
+            })
+            .unwrap_or_default()
+    }
+}
@@ -1,13 +1,26 @@
 pub type IdType = uuid::Uuid;
 
+pub mod anomaly;
 #[cfg(feature = "ssr")]
 pub mod ctx;
+pub mod digest;
+pub mod error;
+pub mod exchange;
 pub mod handlers;
+pub mod i18n;
+pub mod live;
+#[cfg(feature = "ssr")]
+pub mod metrics;
 #[cfg(feature = "ssr")]
 pub mod migrations;
 pub mod models;
 pub mod moneys;
+pub mod offline;
 pub mod perms;
+pub mod search;
+pub mod statement;
+pub mod summary;
+pub mod tz;
 pub mod user;
 
 pub use chrono::Datelike;
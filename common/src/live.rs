@@ -0,0 +1,36 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::IdType;
+
+/// Channel the database notifies entry changes on, see the `entry_changes` migrations.
+pub const CHANNEL: &str = "entry_changes";
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+/// Entries of a manager created, changed or deleted by a statement, as sent to the
+/// browsers that can see them.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct EntryChange {
+    pub kind: ChangeKind,
+    /// The entry when the statement changed a single one, `None` for a bulk change
+    /// such as an import.
+    pub entry_id: Option<IdType>,
+    /// Manager the entries are filed by.
+    pub user_id: IdType,
+    /// Date of the single entry changed.
+    pub date: Option<NaiveDate>,
+}
+
+impl EntryChange {
+    /// Whether a subscriber seeing the entries of `owner` only, or all when `None`, is told.
+    pub fn visible_to(&self, owner: Option<IdType>) -> bool {
+        owner.is_none_or(|owner| owner == self.user_id)
+    }
+}
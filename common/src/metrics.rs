@@ -0,0 +1,34 @@
+//! Application counters, registered in the default prometheus registry
+//! and exposed by the server on `/metrics`.
+
+use std::sync::LazyLock;
+
+use prometheus::{register_int_counter, register_int_counter_vec, IntCounter, IntCounterVec};
+
+pub static LOGINS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!("logins_total", "Login attempts by result", &["result"]).unwrap()
+});
+
+pub static ENTRIES_CREATED: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!("entries_created_total", "Revenue entries created").unwrap()
+});
+
+pub fn login_succeeded() {
+    LOGINS.with_label_values(&["success"]).inc();
+}
+
+pub fn login_failed() {
+    LOGINS.with_label_values(&["failure"]).inc();
+}
+
+pub fn entry_created() {
+    ENTRIES_CREATED.inc();
+}
+
+/// Registers the counters up front so they are exported before the first event.
+pub fn register() {
+    for result in ["success", "failure"] {
+        LOGINS.with_label_values(&[result]);
+    }
+    LazyLock::force(&ENTRIES_CREATED);
+}
@@ -1,5 +1,73 @@
-use sqlx::PgPool;
+use sqlx::{
+    migrate::{MigrateError, Migrator},
+    PgPool,
+};
 
-pub async fn run_migrations(pool: &PgPool) {
-    sqlx::migrate!().run(pool).await.unwrap();
+static MIGRATOR: Migrator = sqlx::migrate!();
+
+pub async fn run_migrations(pool: &PgPool) -> Result<(), MigrateError> {
+    MIGRATOR.run(pool).await
+}
+
+/// Number of bundled migrations not yet successfully applied to the database.
+pub async fn pending_migrations(pool: &PgPool) -> Result<usize, sqlx::Error> {
+    let applied: Vec<i64> =
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+            .fetch_all(pool)
+            .await?;
+
+    Ok(MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration() && !applied.contains(&m.version))
+        .count())
+}
+
+/// A migrated database of its own for a test, created next to the one of
+/// `DATABASE_URL` under a unique name.
+#[cfg(test)]
+pub(crate) struct ScratchDatabase {
+    admin: sqlx::PgConnection,
+    name: String,
+    pub pool: PgPool,
+}
+
+#[cfg(test)]
+impl ScratchDatabase {
+    pub async fn create() -> Self {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use sqlx::{postgres::PgConnectOptions, Connection, Executor};
+
+        static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL");
+        let name = format!(
+            "test_{}_{}_{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_micros(),
+            CREATED.fetch_add(1, Ordering::Relaxed)
+        );
+
+        let mut admin = sqlx::PgConnection::connect(&url).await.unwrap();
+        admin
+            .execute(format!(r#"CREATE DATABASE "{name}""#).as_str())
+            .await
+            .unwrap();
+
+        let options = url.parse::<PgConnectOptions>().unwrap().database(&name);
+        let pool = PgPool::connect_with(options).await.unwrap();
+        run_migrations(&pool).await.unwrap();
+
+        Self { admin, name, pool }
+    }
+
+    pub async fn drop(mut self) {
+        use sqlx::Executor;
+
+        self.pool.close().await;
+        self.admin
+            .execute(format!(r#"DROP DATABASE "{}" WITH (FORCE)"#, self.name).as_str())
+            .await
+            .unwrap();
+    }
 }
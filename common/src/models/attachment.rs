@@ -0,0 +1,155 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::{Locale, Message},
+    IdType,
+};
+
+/// A file behind an entry's numbers: a Z-report, a receipt or a photo.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Attachment {
+    pub id: IdType,
+    pub entry_id: IdType,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+    pub uploaded_at: DateTime<Utc>,
+}
+
+impl Attachment {
+    /// Content types accepted for upload.
+    pub const CONTENT_TYPES: [&'static str; 4] =
+        ["application/pdf", "image/jpeg", "image/png", "image/webp"];
+
+    /// Content type told by the leading bytes of a file, `None` for anything but
+    /// the accepted ones, whatever its name or declared type.
+    pub fn sniff(bytes: &[u8]) -> Option<&'static str> {
+        match bytes {
+            [b'%', b'P', b'D', b'F', b'-', ..] => Some("application/pdf"),
+            [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+            [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n', ..] => Some("image/png"),
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+            _ => None,
+        }
+    }
+
+    pub fn is_image(&self) -> bool {
+        self.content_type.starts_with("image/")
+    }
+
+    /// Size for display, `1,5 МБ`.
+    pub fn size_label(&self, locale: Locale) -> String {
+        let (key, size) = match self.size {
+            size if size < 1024 => ("size-bytes", size.to_string()),
+            size if size < 1024 * 1024 => {
+                ("size-kilobytes", format!("{:.0}", size as f64 / 1024.0))
+            }
+            size => (
+                "size-megabytes",
+                format!("{:.1}", size as f64 / 1024.0 / 1024.0)
+                    .replace('.', &locale.decimal_separator().to_string()),
+            ),
+        };
+
+        locale.message(&Message::new(key).arg("size", size))
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub mod ssr {
+    use sqlx::{PgExecutor, PgPool};
+
+    use super::*;
+
+    /// Attachment with where its content is stored.
+    pub struct StoredAttachment {
+        pub attachment: Attachment,
+        pub storage_key: String,
+    }
+
+    impl Attachment {
+        pub async fn list(pool: &PgPool, entry_id: IdType) -> Result<Vec<Self>, sqlx::Error> {
+            sqlx::query_as!(
+                Attachment,
+                r#"
+                SELECT id, entry_id, file_name, content_type, size, uploaded_at
+                FROM entry_attachments
+                WHERE entry_id = $1
+                ORDER BY uploaded_at, id
+                "#,
+                entry_id
+            )
+            .fetch_all(pool)
+            .await
+        }
+
+        pub async fn get(
+            executor: impl PgExecutor<'_>,
+            id: IdType,
+        ) -> Result<Option<StoredAttachment>, sqlx::Error> {
+            let record = sqlx::query!(
+                r#"
+                SELECT id, entry_id, file_name, content_type, size, uploaded_at, storage_key
+                FROM entry_attachments
+                WHERE id = $1
+                "#,
+                id
+            )
+            .fetch_optional(executor)
+            .await?;
+
+            Ok(record.map(|r| StoredAttachment {
+                attachment: Attachment {
+                    id: r.id,
+                    entry_id: r.entry_id,
+                    file_name: r.file_name,
+                    content_type: r.content_type,
+                    size: r.size,
+                    uploaded_at: r.uploaded_at,
+                },
+                storage_key: r.storage_key,
+            }))
+        }
+
+        pub async fn insert(
+            executor: impl PgExecutor<'_>,
+            attachment: &Attachment,
+            storage_key: &str,
+            uploaded_by: IdType,
+        ) -> Result<(), sqlx::Error> {
+            sqlx::query!(
+                r#"
+                INSERT INTO entry_attachments (id, entry_id, file_name, content_type, size, storage_key, uploaded_by, uploaded_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                "#,
+                attachment.id,
+                attachment.entry_id,
+                attachment.file_name,
+                attachment.content_type,
+                attachment.size,
+                storage_key,
+                uploaded_by,
+                attachment.uploaded_at
+            )
+            .execute(executor)
+            .await?;
+
+            Ok(())
+        }
+
+        pub async fn delete(executor: impl PgExecutor<'_>, id: IdType) -> Result<u64, sqlx::Error> {
+            let result = sqlx::query!(
+                r#"
+                DELETE FROM entry_attachments
+                WHERE id = $1
+                "#,
+                id
+            )
+            .execute(executor)
+            .await?;
+
+            Ok(result.rows_affected())
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{i18n::Message, user::User, IdType};
+
+/// Longest accepted comment, in characters.
+pub const MAX_COMMENT_LENGTH: usize = 4000;
+
+/// A message in the discussion of an entry, possibly a reply to another one.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Comment {
+    pub id: IdType,
+    pub entry_id: IdType,
+    pub parent_id: Option<IdType>,
+    pub author: User,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+impl Comment {
+    /// Orders comments as a thread: every comment followed by its replies, oldest
+    /// first, with its depth. Replies to missing comments start threads of their own.
+    pub fn thread(comments: &[Comment]) -> Vec<(usize, Comment)> {
+        fn push_replies(
+            comments: &[Comment],
+            parent_id: Option<IdType>,
+            depth: usize,
+            thread: &mut Vec<(usize, Comment)>,
+        ) {
+            let mut replies = comments
+                .iter()
+                .filter(|c| c.parent_id == parent_id)
+                .collect::<Vec<_>>();
+            replies.sort_by_key(|c| (c.created_at, c.id));
+
+            for reply in replies {
+                thread.push((depth, reply.clone()));
+                push_replies(comments, Some(reply.id), depth + 1, thread);
+            }
+        }
+
+        let mut thread = vec![];
+        let roots = comments.iter().filter(|c| {
+            c.parent_id
+                .is_none_or(|parent_id| !comments.iter().any(|p| p.id == parent_id))
+        });
+
+        let mut roots = roots.collect::<Vec<_>>();
+        roots.sort_by_key(|c| (c.created_at, c.id));
+
+        for root in roots {
+            thread.push((0, root.clone()));
+            push_replies(comments, Some(root.id), 1, &mut thread);
+        }
+
+        thread
+    }
+
+    /// Trimmed comment text, or why it can't be posted.
+    pub fn validate_body(body: &str) -> Result<&str, String> {
+        let body = body.trim();
+
+        if body.is_empty() {
+            Err("err-comment-empty".to_string())
+        } else if body.chars().count() > MAX_COMMENT_LENGTH {
+            Err(Message::new("err-comment-too-long")
+                .arg("max", MAX_COMMENT_LENGTH)
+                .to_string())
+        } else {
+            Ok(body)
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub mod ssr {
+    use std::collections::HashMap;
+
+    use sqlx::{PgExecutor, PgPool};
+
+    use super::*;
+
+    /// Comments on an entry and how many of them a user hasn't read.
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct CommentCounts {
+        pub total: i64,
+        pub unread: i64,
+    }
+
+    impl Comment {
+        pub async fn list(pool: &PgPool, entry_id: IdType) -> Result<Vec<Self>, sqlx::Error> {
+            let records = sqlx::query!(
+                r#"
+                SELECT entry_comments.id as comment_id, entry_comments.entry_id, entry_comments.parent_id,
+                    entry_comments.body, entry_comments.created_at as comment_created_at, entry_comments.edited_at,
+                    users.id, users.name, users.family_name, users.patronym, users.username, users.password,
+                    users.locale, users.timezone, users.created_at, users.active
+                FROM entry_comments
+                INNER JOIN users ON users.id = entry_comments.author_id
+                WHERE entry_comments.entry_id = $1
+                ORDER BY entry_comments.created_at, entry_comments.id
+                "#,
+                entry_id
+            )
+            .fetch_all(pool)
+            .await?;
+
+            Ok(records
+                .into_iter()
+                .map(|r| Self {
+                    id: r.comment_id,
+                    entry_id: r.entry_id,
+                    parent_id: r.parent_id,
+                    author: crate::models::User {
+                        id: r.id,
+                        name: r.name,
+                        family_name: r.family_name,
+                        patronym: r.patronym,
+                        username: r.username,
+                        password: r.password,
+                        locale: r.locale,
+                        timezone: r.timezone,
+                        created_at: r.created_at,
+                        active: r.active,
+                    }
+                    .into_user_with_password(None)
+                    .0,
+                    body: r.body,
+                    created_at: r.comment_created_at,
+                    edited_at: r.edited_at,
+                })
+                .collect())
+        }
+
+        /// Adds a comment, a reply when `parent_id` is a comment on the same entry.
+        pub async fn insert(
+            executor: impl PgExecutor<'_>,
+            entry_id: IdType,
+            parent_id: Option<IdType>,
+            author_id: IdType,
+            body: &str,
+        ) -> Result<Option<IdType>, sqlx::Error> {
+            sqlx::query_scalar!(
+                r#"
+                INSERT INTO entry_comments (entry_id, parent_id, author_id, body)
+                SELECT $1, $2, $3, $4
+                WHERE ($2::UUID IS NULL) OR EXISTS (
+                    SELECT 1 FROM entry_comments WHERE id = $2 AND entry_id = $1
+                )
+                RETURNING id
+                "#,
+                entry_id,
+                parent_id,
+                author_id,
+                body
+            )
+            .fetch_optional(executor)
+            .await
+        }
+
+        /// Changes the text of a comment by its author, `false` when there's no such comment.
+        pub async fn update(
+            executor: impl PgExecutor<'_>,
+            id: IdType,
+            author_id: IdType,
+            body: &str,
+        ) -> Result<bool, sqlx::Error> {
+            let result = sqlx::query!(
+                r#"
+                UPDATE entry_comments
+                SET body = $3, edited_at = now()
+                WHERE id = $1 AND author_id = $2
+                "#,
+                id,
+                author_id,
+                body
+            )
+            .execute(executor)
+            .await?;
+
+            Ok(result.rows_affected() > 0)
+        }
+
+        /// Entry of a comment.
+        pub async fn entry_id(
+            executor: impl PgExecutor<'_>,
+            id: IdType,
+        ) -> Result<Option<IdType>, sqlx::Error> {
+            sqlx::query_scalar!(
+                r#"
+                SELECT entry_id FROM entry_comments WHERE id = $1
+                "#,
+                id
+            )
+            .fetch_optional(executor)
+            .await
+        }
+
+        /// Remembers the user has read the entry's thread up to now.
+        pub async fn mark_read(
+            executor: impl PgExecutor<'_>,
+            user_id: IdType,
+            entry_id: IdType,
+        ) -> Result<(), sqlx::Error> {
+            sqlx::query!(
+                r#"
+                INSERT INTO entry_comment_reads (user_id, entry_id, read_at)
+                VALUES ($1, $2, now())
+                ON CONFLICT (user_id, entry_id) DO UPDATE SET read_at = EXCLUDED.read_at
+                "#,
+                user_id,
+                entry_id
+            )
+            .execute(executor)
+            .await?;
+
+            Ok(())
+        }
+
+        /// Comment counts of the entries that have comments. Comments of the user
+        /// themselves are never unread.
+        pub async fn counts(
+            pool: &PgPool,
+            user_id: IdType,
+            entry_ids: &[IdType],
+        ) -> Result<HashMap<IdType, CommentCounts>, sqlx::Error> {
+            let records = sqlx::query!(
+                r#"
+                SELECT entry_comments.entry_id,
+                    count(*) as "total!",
+                    count(*) FILTER (
+                        WHERE entry_comments.author_id <> $1
+                        AND (reads.read_at IS NULL OR entry_comments.created_at > reads.read_at)
+                    ) as "unread!"
+                FROM entry_comments
+                LEFT JOIN entry_comment_reads reads
+                    ON reads.entry_id = entry_comments.entry_id AND reads.user_id = $1
+                WHERE entry_comments.entry_id = ANY($2)
+                GROUP BY entry_comments.entry_id
+                "#,
+                user_id,
+                entry_ids
+            )
+            .fetch_all(pool)
+            .await?;
+
+            Ok(records
+                .into_iter()
+                .map(|r| {
+                    (
+                        r.entry_id,
+                        CommentCounts {
+                            total: r.total,
+                            unread: r.unread,
+                        },
+                    )
+                })
+                .collect())
+        }
+    }
+}
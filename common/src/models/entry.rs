@@ -1,67 +1,472 @@
-use chrono::{NaiveDate, Utc};
+use chrono::{NaiveDate, NaiveTime};
 use serde::{Deserialize, Serialize};
 
-use crate::{moneys::Moneys, IdType};
+use crate::{
+    models::{Payment, PaymentFields, Shift, ShiftFields},
+    moneys::Moneys,
+    tz::{self, Tz},
+    IdType,
+};
 
 #[derive(Deserialize, Serialize, Clone)]
 pub struct Entry {
     pub id: IdType,
     pub address: String,
+    /// Sum of the payments with returns subtracted, or the amount filed before
+    /// the breakdown was introduced when there are none.
     pub revenue: Moneys,
+    #[serde(default)]
+    pub payments: Vec<Payment>,
     pub date: NaiveDate,
+    /// Hours of the entry, `None` when it covers the whole day.
+    #[serde(default)]
+    pub shift: Option<Shift>,
+    #[serde(default)]
+    pub shift_label: Option<String>,
     pub by_user_id: IdType,
+    /// Why the revenue looked like an outlier when it was confirmed, until reviewed.
+    #[serde(default)]
+    pub anomaly: Option<String>,
+    /// Zone the date is checked in, see [`tz::resolve`].
+    pub timezone: Tz,
+    /// Bumped on every change, offline changes are checked against it.
+    #[serde(default)]
+    pub revision: i32,
 }
 
 #[cfg(feature = "ssr")]
 impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for Entry {
     fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
         use sqlx::Row;
-        let revenue: sqlx_postgres::types::PgMoney = row.try_get("revenue")?;
+        let revenue = Moneys::from_sql(row.try_get("revenue")?, row.try_get("currency")?)?;
 
         Ok(Self {
             id: row.try_get("id")?,
             address: row.try_get("address")?,
-            revenue: revenue.into(),
+            revenue,
+            payments: vec![],
             date: row.try_get("date")?,
+            shift: shift_from_sql(row.try_get("shift_start")?, row.try_get("shift_end")?),
+            shift_label: row.try_get("shift_label")?,
             by_user_id: row.try_get("by_user_id")?,
+            anomaly: row.try_get("anomaly")?,
+            timezone: tz::from_stored(row.try_get("timezone")?),
+            revision: row.try_get("revision")?,
         })
     }
 }
 
+/// Report form fields as submitted, the whole report kept in the offline queue.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct ReportFields {
+    pub payments: PaymentFields,
+    pub shift: ShiftFields,
+    pub currency: String,
+    pub address: String,
+    pub date: NaiveDate,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct EntryWithUser {
     pub id: IdType,
     pub address: String,
     pub revenue: Moneys,
+    /// Revenue in the reporting currency, `None` while no exchange rate is known.
+    #[serde(default)]
+    pub converted: Option<Moneys>,
+    #[serde(default)]
+    pub payments: Vec<Payment>,
     pub date: NaiveDate,
+    #[serde(default)]
+    pub shift: Option<Shift>,
+    #[serde(default)]
+    pub shift_label: Option<String>,
     pub user: crate::user::User,
+    #[serde(default)]
+    pub anomaly: Option<String>,
+    pub timezone: Tz,
+    /// Comments in the entry's thread, as counted by `list_reports`.
+    #[serde(default)]
+    pub comments: i64,
+    /// Comments by others the viewing user hasn't read.
+    #[serde(default)]
+    pub unread_comments: i64,
 }
 
 #[cfg(feature = "ssr")]
 pub mod ssr {
+    use sqlx::PgPool;
+
     use super::*;
 
+    impl EntryWithUser {
+        /// Entries dated within the month, newest first, optionally of a single user.
+        pub async fn list_month(
+            pool: &PgPool,
+            year: i32,
+            month: u32,
+            user_id_filter: Option<IdType>,
+        ) -> Result<Vec<Self>, sqlx::Error> {
+            let (min_date, max_date) = month_range(year, month);
+
+            Self::list_range(pool, min_date, max_date, user_id_filter).await
+        }
+
+        /// Entries dated from `min_date` to `max_date` inclusive, newest first,
+        /// optionally of a single user.
+        pub async fn list_range(
+            pool: &PgPool,
+            min_date: NaiveDate,
+            max_date: NaiveDate,
+            user_id_filter: Option<IdType>,
+        ) -> Result<Vec<Self>, sqlx::Error> {
+            let records = sqlx::query!(
+                r#"
+                SELECT entries.address, entries.revenue, entries.currency, entries.date, entries.shift_start, entries.shift_end, entries.shift_label, entries.by_user_id, entries.anomaly, entries.revision, entries.id as entry_id, COALESCE(locations.timezone, users.timezone) as entry_timezone, users.*
+                FROM entries
+                INNER JOIN users ON entries.by_user_id = users.id
+                LEFT JOIN locations ON locations.address = entries.address
+                WHERE entries.date >= $1
+                AND entries.date <= $2
+                AND (($3::UUID IS NULL) OR (users.id = $3::UUID))
+                ORDER BY entries.date DESC, entries.shift_start DESC NULLS LAST
+                "#,
+                min_date,
+                max_date,
+                user_id_filter
+            ).fetch_all(pool).await?;
+
+            let entries_w_users = records.into_iter().map(|r| {
+                Ok((
+                    Entry {
+                        id: r.entry_id,
+                        address: r.address,
+                        revenue: Moneys::from_sql(r.revenue, &r.currency)?,
+                        payments: vec![],
+                        date: r.date,
+                        shift: shift_from_sql(r.shift_start, r.shift_end),
+                        shift_label: r.shift_label,
+                        by_user_id: r.by_user_id,
+                        anomaly: r.anomaly,
+                        timezone: tz::from_stored(r.entry_timezone.as_deref()),
+                        revision: r.revision,
+                    },
+                    crate::models::User {
+                        id: r.id,
+                        name: r.name,
+                        family_name: r.family_name,
+                        patronym: r.patronym,
+                        username: r.username,
+                        password: r.password,
+                        locale: r.locale,
+                        timezone: r.timezone,
+                        created_at: r.created_at,
+                        active: r.active,
+                    },
+                ))
+            });
+
+            let mut entries = entries_w_users
+                .map(|d: Result<_, sqlx::Error>| d.map(Into::into))
+                .collect::<Result<Vec<Self>, _>>()?;
+
+            let ids = entries.iter().map(|e| e.id).collect::<Vec<_>>();
+            let mut payments = Payment::for_entries(pool, &ids).await?;
+
+            for entry in entries.iter_mut() {
+                entry.payments = payments.remove(&entry.id).unwrap_or_default();
+            }
+
+            Ok(entries)
+        }
+
+        /// Entries flagged as unusual on save, newest first.
+        pub async fn list_flagged(pool: &PgPool, limit: i64) -> Result<Vec<Self>, sqlx::Error> {
+            let records = sqlx::query!(
+                r#"
+                SELECT entries.address, entries.revenue, entries.currency, entries.date, entries.shift_start, entries.shift_end, entries.shift_label, entries.by_user_id, entries.anomaly, entries.revision, entries.id as entry_id, COALESCE(locations.timezone, users.timezone) as entry_timezone, users.*
+                FROM entries
+                INNER JOIN users ON entries.by_user_id = users.id
+                LEFT JOIN locations ON locations.address = entries.address
+                WHERE entries.anomaly IS NOT NULL
+                ORDER BY entries.date DESC, entries.id
+                LIMIT $1
+                "#,
+                limit
+            ).fetch_all(pool).await?;
+
+            records
+                .into_iter()
+                .map(|r| {
+                    Ok((
+                        Entry {
+                            id: r.entry_id,
+                            address: r.address,
+                            revenue: Moneys::from_sql(r.revenue, &r.currency)?,
+                            payments: vec![],
+                            date: r.date,
+                            shift: shift_from_sql(r.shift_start, r.shift_end),
+                            shift_label: r.shift_label,
+                            by_user_id: r.by_user_id,
+                            anomaly: r.anomaly,
+                            timezone: tz::from_stored(r.entry_timezone.as_deref()),
+                            revision: r.revision,
+                        },
+                        crate::models::User {
+                            id: r.id,
+                            name: r.name,
+                            family_name: r.family_name,
+                            patronym: r.patronym,
+                            username: r.username,
+                            password: r.password,
+                            locale: r.locale,
+                            timezone: r.timezone,
+                            created_at: r.created_at,
+                            active: r.active,
+                        },
+                    )
+                        .into())
+                })
+                .collect()
+        }
+
+        /// Drops the flag of an entry once it was looked at.
+        pub async fn resolve_anomaly(pool: &PgPool, id: IdType) -> Result<u64, sqlx::Error> {
+            let result = sqlx::query!(
+                r#"
+                UPDATE entries
+                SET anomaly = NULL
+                WHERE id = $1
+                "#,
+                id
+            )
+            .execute(pool)
+            .await?;
+
+            Ok(result.rows_affected())
+        }
+    }
+
+    impl Entry {
+        /// Whose entries the user may view: `Some` of their own id with `ViewOwned`,
+        /// everyone's with `ViewAll` and `None` without either. `ViewOwned` comes first
+        /// like in `list_reports`, a user granted both sees their own.
+        pub async fn owner_filter(
+            user: &crate::user::User,
+            pool: &PgPool,
+        ) -> Option<Option<IdType>> {
+            use axum_session_auth::HasPermission;
+
+            use crate::perms::{VIEW_ALL, VIEW_OWNED};
+
+            if user.has(VIEW_OWNED, &Some(pool)).await {
+                Some(Some(user.id))
+            } else if user.has(VIEW_ALL, &Some(pool)).await {
+                Some(None)
+            } else {
+                None
+            }
+        }
+
+        /// The entry, if filed by the owner the filter allows.
+        pub async fn get_visible(
+            pool: &PgPool,
+            id: IdType,
+            owner_filter: Option<IdType>,
+        ) -> Result<Option<Self>, sqlx::Error> {
+            sqlx::query_as::<sqlx::Postgres, Self>(
+                r#"
+                SELECT entries.*, COALESCE(locations.timezone, users.timezone) AS timezone
+                FROM entries
+                INNER JOIN users ON entries.by_user_id = users.id
+                LEFT JOIN locations ON locations.address = entries.address
+                WHERE entries.id = $1 AND (($2::UUID IS NULL) OR (by_user_id = $2::UUID))
+                "#,
+            )
+            .bind(id)
+            .bind(owner_filter)
+            .fetch_optional(pool)
+            .await
+        }
+
+        /// Whether the user may change the entry: their own, with `EditOwned`, dated
+        /// within the report window of its zone like `update_report` allows.
+        pub async fn is_editable_by(
+            pool: &PgPool,
+            id: IdType,
+            user: &crate::user::User,
+        ) -> Result<bool, sqlx::Error> {
+            use axum_session_auth::HasPermission;
+
+            if !user.has(crate::perms::EDIT_OWNED, &Some(pool)).await {
+                return Ok(false);
+            }
+
+            Ok(Self::get_visible(pool, id, Some(user.id))
+                .await?
+                .is_some_and(|entry| tz::in_report_window(entry.date, tz::today(entry.timezone))))
+        }
+
+        /// Holds off other writers of the location's entries until the transaction
+        /// ends, so a clash checked with [`Entry::find_overlapping`] within it can't be
+        /// filed meanwhile. Locks are per location rather than per date, an import takes
+        /// one for each location it touches.
+        pub async fn lock_location(
+            executor: impl sqlx::PgExecutor<'_>,
+            address: &str,
+        ) -> Result<(), sqlx::Error> {
+            sqlx::query!(
+                r#"
+                SELECT pg_advisory_xact_lock(hashtext('entries'), hashtext($1))
+                "#,
+                address
+            )
+            .execute(executor)
+            .await?;
+
+            Ok(())
+        }
+
+        /// An entry of the location and date the manager's `shift` would clash with,
+        /// see [`Shift::clashes`], other than entry `except`.
+        pub async fn find_overlapping(
+            executor: impl sqlx::PgExecutor<'_>,
+            user_id: IdType,
+            address: &str,
+            date: NaiveDate,
+            shift: Option<Shift>,
+            except: Option<IdType>,
+        ) -> Result<Option<IdType>, sqlx::Error> {
+            let records = sqlx::query!(
+                r#"
+                SELECT id, by_user_id, shift_start, shift_end FROM entries
+                WHERE address = $1 AND date = $2 AND id IS DISTINCT FROM $3
+                ORDER BY id
+                "#,
+                address,
+                date,
+                except
+            )
+            .fetch_all(executor)
+            .await?;
+
+            Ok(records
+                .into_iter()
+                .find(|r| {
+                    let other = shift_from_sql(r.shift_start, r.shift_end);
+                    Shift::clashes(shift, other, r.by_user_id == user_id)
+                })
+                .map(|r| r.id))
+        }
+
+        /// Stores the manager's revenue for the location, date and shift start, replacing
+        /// the entry already there and dropping its unusual revenue flag. Returns the
+        /// entry id and whether it was created.
+        pub async fn upsert(
+            executor: impl sqlx::PgExecutor<'_>,
+            user_id: IdType,
+            address: &str,
+            date: NaiveDate,
+            shift: Option<Shift>,
+            shift_label: Option<&str>,
+            revenue: Moneys,
+        ) -> Result<(IdType, bool), sqlx::Error> {
+            let record = sqlx::query!(
+                r#"
+                INSERT INTO entries (date, revenue, currency, by_user_id, address, shift_start, shift_end, shift_label)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ON CONFLICT (by_user_id, address, date, COALESCE(shift_start, '00:00'::TIME)) DO UPDATE
+                SET revenue = EXCLUDED.revenue,
+                    currency = EXCLUDED.currency,
+                    shift_end = EXCLUDED.shift_end,
+                    shift_label = EXCLUDED.shift_label,
+                    anomaly = NULL,
+                    revision = entries.revision + 1
+                RETURNING id, (xmax = 0) AS "created!"
+                "#,
+                date,
+                revenue.to_numeric(),
+                revenue.currency.code(),
+                user_id,
+                address,
+                shift.map(|s| s.start),
+                shift.map(|s| s.end),
+                shift_label
+            )
+            .fetch_one(executor)
+            .await?;
+
+            Ok((record.id, record.created))
+        }
+    }
+
     impl From<(Entry, crate::models::User)> for EntryWithUser {
         fn from((entry, user): (Entry, crate::models::User)) -> Self {
             Self {
                 id: entry.id,
                 address: entry.address,
-                revenue: entry.revenue.into(),
+                revenue: entry.revenue,
+                converted: None,
+                payments: entry.payments,
                 date: entry.date,
+                shift: entry.shift,
+                shift_label: entry.shift_label,
                 user: user.into_user_with_password(None).0,
+                anomaly: entry.anomaly,
+                timezone: entry.timezone,
+                comments: 0,
+                unread_comments: 0,
             }
         }
     }
 }
 
+/// Shift of stored hours, both set or neither.
+pub fn shift_from_sql(start: Option<NaiveTime>, end: Option<NaiveTime>) -> Option<Shift> {
+    Some(Shift {
+        start: start?,
+        end: end?,
+    })
+}
+
+impl EntryWithUser {
+    /// Name the entry's shift is shown and totalled under: its label, else its hours.
+    /// `None` for a whole day.
+    pub fn shift_name(&self) -> Option<String> {
+        self.shift_label
+            .clone()
+            .or_else(|| self.shift.map(|shift| shift.to_string()))
+    }
+}
+
+impl Entry {
+    /// New report of the user, dated today in their zone.
+    pub fn blank(user: &crate::user::User) -> Self {
+        let timezone = tz::resolve(None, user.timezone);
+
+        Self {
+            date: tz::today(timezone),
+            by_user_id: user.id,
+            timezone,
+            ..Default::default()
+        }
+    }
+}
+
 impl Default for Entry {
     fn default() -> Self {
         Self {
             id: uuid::Uuid::nil(),
             address: Default::default(),
             revenue: Default::default(),
-            date: Utc::now().date_naive(),
+            payments: vec![],
+            date: tz::today(tz::DEFAULT),
+            shift: None,
+            shift_label: None,
             by_user_id: Default::default(),
+            anomaly: None,
+            timezone: tz::DEFAULT,
+            revision: 0,
         }
     }
 }
@@ -0,0 +1,50 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::moneys::Currency;
+
+/// One unit of `currency` costs `rate` units of `base` from `date` on.
+#[derive(Deserialize, Serialize, Clone)]
+pub struct ExchangeRate {
+    pub date: NaiveDate,
+    pub currency: Currency,
+    pub base: Currency,
+    pub rate: String,
+}
+
+#[cfg(feature = "ssr")]
+pub mod ssr {
+    use sqlx::PgPool;
+
+    use super::*;
+
+    impl ExchangeRate {
+        /// Latest rates first.
+        pub async fn list_recent(pool: &PgPool, limit: i64) -> Result<Vec<Self>, sqlx::Error> {
+            let records = sqlx::query!(
+                r#"
+                SELECT date, currency, base, rate FROM exchange_rates
+                ORDER BY date DESC, currency, base
+                LIMIT $1
+                "#,
+                limit
+            )
+            .fetch_all(pool)
+            .await?;
+
+            records
+                .into_iter()
+                .map(|r| {
+                    let decode = |err| sqlx::Error::Decode(Box::new(err));
+
+                    Ok(Self {
+                        date: r.date,
+                        currency: r.currency.parse().map_err(decode)?,
+                        base: r.base.parse().map_err(decode)?,
+                        rate: r.rate.normalize().to_string(),
+                    })
+                })
+                .collect()
+        }
+    }
+}
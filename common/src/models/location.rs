@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{moneys::Currency, tz::Tz, IdType};
+
+/// A point of sale, identified by the address managers report for it.
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Location {
+    pub id: IdType,
+    pub address: String,
+    pub currency: Currency,
+    /// Zone report dates are checked in, before the manager's.
+    #[serde(default)]
+    pub timezone: Option<Tz>,
+}
+
+#[cfg(feature = "ssr")]
+pub mod ssr {
+    use sqlx::{PgExecutor, PgPool};
+
+    use super::*;
+
+    impl Location {
+        pub async fn list(pool: &PgPool) -> Result<Vec<Self>, sqlx::Error> {
+            let records = sqlx::query!(
+                r#"
+                SELECT id, address, currency, timezone FROM locations
+                ORDER BY address
+                "#
+            )
+            .fetch_all(pool)
+            .await?;
+
+            records
+                .into_iter()
+                .map(|r| {
+                    Ok(Self {
+                        id: r.id,
+                        address: r.address,
+                        currency: r
+                            .currency
+                            .parse()
+                            .map_err(|err| sqlx::Error::Decode(Box::new(err)))?,
+                        timezone: r.timezone.and_then(|tz| tz.parse().ok()),
+                    })
+                })
+                .collect()
+        }
+
+        pub async fn get(pool: &PgPool, id: IdType) -> Result<Option<Self>, sqlx::Error> {
+            let record = sqlx::query!(
+                r#"
+                SELECT id, address, currency, timezone FROM locations
+                WHERE id = $1
+                "#,
+                id
+            )
+            .fetch_optional(pool)
+            .await?;
+
+            record
+                .map(|r| {
+                    Ok(Self {
+                        id: r.id,
+                        address: r.address,
+                        currency: r
+                            .currency
+                            .parse()
+                            .map_err(|err| sqlx::Error::Decode(Box::new(err)))?,
+                        timezone: r.timezone.and_then(|tz| tz.parse().ok()),
+                    })
+                })
+                .transpose()
+        }
+
+        /// Default currency of the location, registering the address with `currency`
+        /// when it's reported for the first time.
+        pub async fn default_currency(
+            executor: impl PgExecutor<'_>,
+            address: &str,
+            currency: Currency,
+        ) -> Result<Currency, sqlx::Error> {
+            let record = sqlx::query!(
+                r#"
+                INSERT INTO locations (address, currency)
+                VALUES ($1, $2)
+                ON CONFLICT (address) DO UPDATE
+                SET address = EXCLUDED.address
+                RETURNING currency
+                "#,
+                address,
+                currency.code()
+            )
+            .fetch_one(executor)
+            .await?;
+
+            record
+                .currency
+                .parse()
+                .map_err(|err| sqlx::Error::Decode(Box::new(err)))
+        }
+
+        /// Sets the default currency of a location, creating it if needed.
+        pub async fn set_currency(
+            executor: impl PgExecutor<'_>,
+            address: &str,
+            currency: Currency,
+        ) -> Result<(), sqlx::Error> {
+            sqlx::query!(
+                r#"
+                INSERT INTO locations (address, currency)
+                VALUES ($1, $2)
+                ON CONFLICT (address) DO UPDATE
+                SET currency = EXCLUDED.currency
+                "#,
+                address,
+                currency.code()
+            )
+            .execute(executor)
+            .await?;
+
+            Ok(())
+        }
+
+        /// Zone of the location, `None` while it has none or isn't registered yet.
+        pub async fn timezone(
+            executor: impl PgExecutor<'_>,
+            address: &str,
+        ) -> Result<Option<Tz>, sqlx::Error> {
+            let timezone = sqlx::query_scalar!(
+                r#"
+                SELECT timezone FROM locations
+                WHERE address = $1
+                "#,
+                address
+            )
+            .fetch_optional(executor)
+            .await?
+            .flatten();
+
+            Ok(timezone.and_then(|tz| tz.parse().ok()))
+        }
+
+        /// Sets the zone of a location, creating it if needed. `None` leaves the choice
+        /// to the managers' zones.
+        pub async fn set_timezone(
+            executor: impl PgExecutor<'_>,
+            address: &str,
+            timezone: Option<Tz>,
+        ) -> Result<(), sqlx::Error> {
+            sqlx::query!(
+                r#"
+                INSERT INTO locations (address, timezone)
+                VALUES ($1, $2)
+                ON CONFLICT (address) DO UPDATE
+                SET timezone = EXCLUDED.timezone
+                "#,
+                address,
+                timezone.map(|tz| tz.name())
+            )
+            .execute(executor)
+            .await?;
+
+            Ok(())
+        }
+    }
+}
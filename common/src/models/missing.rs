@@ -0,0 +1,90 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Days a manager filed no report for.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct MissingReports {
+    pub user: crate::user::User,
+    pub dates: Vec<NaiveDate>,
+}
+
+impl MissingReports {
+    /// Days checked on `today`: from the start of the month, as earlier reports can't be
+    /// filed anymore, to yesterday, as today's one may still come. `None` on the first.
+    pub fn window(today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+        let from = today.with_day(1)?;
+        let to = today.pred_opt()?;
+
+        (from <= to).then_some((from, to))
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub mod ssr {
+    use sqlx::PgPool;
+
+    use super::*;
+    use crate::{perms::EDIT_OWNED, IdType};
+
+    impl MissingReports {
+        /// Active managers allowed to file reports with the days in `[from, to]` they have no
+        /// entry for, optionally only one manager. Managers without gaps are left out.
+        pub async fn list(
+            pool: &PgPool,
+            from: NaiveDate,
+            to: NaiveDate,
+            user_id_filter: Option<IdType>,
+        ) -> Result<Vec<Self>, sqlx::Error> {
+            let records = sqlx::query!(
+                r#"
+                SELECT users.*, days.date::DATE as "date!"
+                FROM users
+                CROSS JOIN generate_series($1::DATE, $2::DATE, '1 day') AS days(date)
+                WHERE users.active AND EXISTS (
+                    SELECT 1 FROM permissions
+                    WHERE permissions.user_id = users.id AND permissions.token = $3
+                )
+                AND (($4::UUID IS NULL) OR (users.id = $4::UUID))
+                AND NOT EXISTS (
+                    SELECT 1 FROM entries
+                    WHERE entries.by_user_id = users.id AND entries.date = days.date::DATE
+                )
+                ORDER BY users.family_name, users.id, days.date
+                "#,
+                from,
+                to,
+                EDIT_OWNED,
+                user_id_filter
+            )
+            .fetch_all(pool)
+            .await?;
+
+            let mut missing: Vec<Self> = vec![];
+
+            for r in records {
+                match missing.last_mut() {
+                    Some(last) if last.user.id == r.id => last.dates.push(r.date),
+                    _ => missing.push(Self {
+                        user: crate::models::User {
+                            id: r.id,
+                            name: r.name,
+                            family_name: r.family_name,
+                            patronym: r.patronym,
+                            username: r.username,
+                            password: r.password,
+                            locale: r.locale,
+                            timezone: r.timezone,
+                            created_at: r.created_at,
+                            active: r.active,
+                        }
+                        .into_user_with_password(None)
+                        .0,
+                        dates: vec![r.date],
+                    }),
+                }
+            }
+
+            Ok(missing)
+        }
+    }
+}
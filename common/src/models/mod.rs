@@ -1,5 +1,21 @@
+pub mod attachment;
+pub mod comment;
 pub mod entry;
+pub mod exchange_rate;
+pub mod location;
+pub mod missing;
+pub mod payment;
+pub mod plan;
+pub mod shift;
 pub mod user;
 
-pub use entry::{Entry, EntryWithUser};
+pub use attachment::Attachment;
+pub use comment::Comment;
+pub use entry::{Entry, EntryWithUser, ReportFields};
+pub use exchange_rate::ExchangeRate;
+pub use location::Location;
+pub use missing::MissingReports;
+pub use payment::{Payment, PaymentFields, PaymentMethod};
+pub use plan::{Plan, PlanProgress, PlanScope};
+pub use shift::{Shift, ShiftFields};
 pub use user::{PermissionTokens, User};
@@ -0,0 +1,248 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::AppError,
+    i18n::{Locale, Message},
+    moneys::{Currency, Moneys},
+};
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PaymentMethod {
+    Cash,
+    Card,
+    Online,
+    /// Refunds, subtracted from the revenue.
+    Returns,
+}
+
+impl PaymentMethod {
+    pub const ALL: [PaymentMethod; 4] = [
+        PaymentMethod::Cash,
+        PaymentMethod::Card,
+        PaymentMethod::Online,
+        PaymentMethod::Returns,
+    ];
+
+    /// Stored in the database and used as the form field name.
+    pub fn code(&self) -> &'static str {
+        match self {
+            PaymentMethod::Cash => "cash",
+            PaymentMethod::Card => "card",
+            PaymentMethod::Online => "online",
+            PaymentMethod::Returns => "returns",
+        }
+    }
+
+    pub fn label(&self, locale: Locale) -> String {
+        locale.tr(&format!("payment-{}", self.code()))
+    }
+}
+
+impl Display for PaymentMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl FromStr for PaymentMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PaymentMethod::ALL
+            .into_iter()
+            .find(|m| m.code() == s)
+            .ok_or_else(|| {
+                Message::new("err-unknown-payment-method")
+                    .arg("code", s)
+                    .to_string()
+            })
+    }
+}
+
+/// A line of the revenue breakdown. Amounts are never negative, returns are
+/// subtracted when the total is derived.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Payment {
+    pub method: PaymentMethod,
+    pub amount: Moneys,
+    /// Amount in the reporting currency, `None` while no exchange rate is known.
+    #[serde(default)]
+    pub converted: Option<Moneys>,
+}
+
+impl Payment {
+    /// Parses the breakdown form fields, skipping empty ones, and derives the revenue.
+    pub fn parse_breakdown(
+        fields: &[(PaymentMethod, &str)],
+        currency: Currency,
+    ) -> Result<(Vec<Payment>, Moneys), AppError> {
+        let mut payments = vec![];
+        let mut total = Moneys::new(0, currency);
+
+        for (method, value) in fields {
+            if value.trim().is_empty() {
+                continue;
+            }
+
+            let field_error = |message: Message| AppError::validation(method.code(), message);
+
+            let amount = Moneys::parse(value, currency).map_err(|e| field_error(e.message()))?;
+
+            if amount.amount < 0 {
+                return Err(field_error(Message::new("err-amount-negative")));
+            }
+
+            let signed = match method {
+                PaymentMethod::Returns => Moneys::new(-amount.amount, currency),
+                _ => amount,
+            };
+
+            total = total
+                .checked_add(signed)
+                .ok_or_else(|| field_error(Message::new("err-amount-overflow")))?;
+
+            payments.push(Payment {
+                method: *method,
+                amount,
+                converted: None,
+            });
+        }
+
+        if payments.is_empty() {
+            return Err(AppError::validation("revenue", "err-breakdown-empty"));
+        }
+
+        Ok((payments, total))
+    }
+}
+
+/// Breakdown as typed into the report form, submitted as `payments[cash]` and so on.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct PaymentFields {
+    #[serde(default)]
+    pub cash: String,
+    #[serde(default)]
+    pub card: String,
+    #[serde(default)]
+    pub online: String,
+    #[serde(default)]
+    pub returns: String,
+}
+
+impl PaymentFields {
+    pub fn parse(&self, currency: Currency) -> Result<(Vec<Payment>, Moneys), AppError> {
+        Payment::parse_breakdown(
+            &[
+                (PaymentMethod::Cash, &self.cash),
+                (PaymentMethod::Card, &self.card),
+                (PaymentMethod::Online, &self.online),
+                (PaymentMethod::Returns, &self.returns),
+            ],
+            currency,
+        )
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub mod ssr {
+    use std::collections::HashMap;
+
+    use sqlx::{PgExecutor, PgPool, Postgres, Transaction};
+
+    use super::*;
+    use crate::IdType;
+
+    impl Payment {
+        /// Breakdowns of the entries, entries without one are left out.
+        pub async fn for_entries(
+            pool: &PgPool,
+            entry_ids: &[IdType],
+        ) -> Result<HashMap<IdType, Vec<Payment>>, sqlx::Error> {
+            let records = sqlx::query!(
+                r#"
+                SELECT entry_payments.entry_id, entry_payments.method,
+                    entry_payments.amount, entries.currency
+                FROM entry_payments
+                INNER JOIN entries ON entries.id = entry_payments.entry_id
+                WHERE entry_payments.entry_id = ANY($1)
+                "#,
+                entry_ids
+            )
+            .fetch_all(pool)
+            .await?;
+
+            let mut payments = HashMap::<IdType, Vec<Payment>>::new();
+
+            for r in records {
+                let method = r
+                    .method
+                    .parse()
+                    .map_err(|err: String| sqlx::Error::Decode(err.into()))?;
+
+                payments.entry(r.entry_id).or_default().push(Payment {
+                    method,
+                    amount: Moneys::from_sql(r.amount, &r.currency)?,
+                    converted: None,
+                });
+            }
+
+            for list in payments.values_mut() {
+                list.sort_by_key(|p| p.method);
+            }
+
+            Ok(payments)
+        }
+
+        /// Replaces the breakdown of an entry.
+        pub async fn replace(
+            tx: &mut Transaction<'_, Postgres>,
+            entry_id: IdType,
+            payments: &[Payment],
+        ) -> Result<(), sqlx::Error> {
+            sqlx::query!(
+                r#"
+                DELETE FROM entry_payments
+                WHERE entry_id = $1
+                "#,
+                entry_id
+            )
+            .execute(&mut **tx)
+            .await?;
+
+            let entry_ids = vec![entry_id; payments.len()];
+            Self::insert(&mut **tx, &entry_ids, payments).await
+        }
+
+        /// Inserts payments, `entry_ids` holds the entry of each payment.
+        pub async fn insert(
+            executor: impl PgExecutor<'_>,
+            entry_ids: &[IdType],
+            payments: &[Payment],
+        ) -> Result<(), sqlx::Error> {
+            let methods = payments
+                .iter()
+                .map(|p| p.method.code().to_string())
+                .collect::<Vec<_>>();
+            let amounts = payments
+                .iter()
+                .map(|p| p.amount.to_numeric())
+                .collect::<Vec<_>>();
+
+            sqlx::query!(
+                r#"
+                INSERT INTO entry_payments (entry_id, method, amount)
+                SELECT * FROM UNNEST($1::UUID[], $2::TEXT[], $3::NUMERIC[])
+                "#,
+                entry_ids,
+                &methods,
+                &amounts
+            )
+            .execute(executor)
+            .await?;
+
+            Ok(())
+        }
+    }
+}
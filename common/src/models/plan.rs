@@ -0,0 +1,235 @@
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    models::{entry::month_range, EntryWithUser},
+    moneys::Moneys,
+    tz::{self, Tz},
+    IdType,
+};
+
+/// Whose revenue a plan targets.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum PlanScope {
+    Manager(IdType),
+    Location { id: IdType, address: String },
+}
+
+/// Monthly revenue target of a manager or a location.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Plan {
+    pub id: IdType,
+    pub year: i32,
+    pub month: u32,
+    pub scope: PlanScope,
+    pub target: Moneys,
+    /// Zone of the location, else of the manager, the month's days pass in.
+    pub timezone: Tz,
+}
+
+/// Plan against the entries of its month.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PlanProgress {
+    pub target: Moneys,
+    /// Revenue of the covered entries in the plan's currency.
+    pub actual: Moneys,
+    pub remaining: Moneys,
+    pub percent: f64,
+    /// Revenue at month end if every remaining day earns the average of the days
+    /// passed so far, today included, `None` until there are entries.
+    pub projection: Option<Moneys>,
+    /// Covered entries left out for lack of an exchange rate into the plan's currency.
+    pub unconverted: usize,
+}
+
+impl Plan {
+    pub fn covers(&self, entry: &EntryWithUser) -> bool {
+        match &self.scope {
+            PlanScope::Manager(user_id) => entry.user.id == *user_id,
+            PlanScope::Location { address, .. } => entry.address == *address,
+        }
+    }
+
+    /// Progress at `now` from the month's entries on screen, as loaded by `list_reports`.
+    pub fn progress(&self, entries: &[EntryWithUser], now: DateTime<Utc>) -> PlanProgress {
+        let currency = self.target.currency;
+        let (min_date, max_date) = month_range(self.year, self.month);
+
+        let covered = entries
+            .iter()
+            .filter(|e| self.covers(e) && e.date >= min_date && e.date <= max_date);
+
+        let mut actual: i64 = 0;
+        let mut unconverted = 0;
+        let mut reported = false;
+
+        for entry in covered {
+            let amount = [Some(entry.revenue), entry.converted]
+                .into_iter()
+                .flatten()
+                .find(|m| m.currency == currency);
+
+            match amount {
+                Some(amount) => actual = actual.saturating_add(amount.amount),
+                None => unconverted += 1,
+            }
+
+            reported = true;
+        }
+
+        let today = tz::date_at(self.timezone, now);
+        let elapsed = (today >= min_date).then(|| today.min(max_date).day());
+
+        let projection = elapsed.filter(|_| reported).map(|elapsed| {
+            let days = i128::from(max_date.day());
+            let projected = i128::from(actual) * days / i128::from(elapsed);
+
+            Moneys::new(projected.try_into().unwrap_or(i64::MAX), currency)
+        });
+
+        PlanProgress {
+            target: self.target,
+            actual: Moneys::new(actual, currency),
+            remaining: Moneys::new((self.target.amount - actual).max(0), currency),
+            percent: actual as f64 / self.target.amount as f64 * 100.0,
+            projection,
+            unconverted,
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub mod ssr {
+    use sqlx::{PgExecutor, PgPool};
+
+    use super::*;
+
+    impl Plan {
+        /// Plans of the month, optionally only those relevant to a manager: their own
+        /// and the ones of locations they reported for that month.
+        pub async fn list_month(
+            pool: &PgPool,
+            year: i32,
+            month: u32,
+            user_id_filter: Option<IdType>,
+        ) -> Result<Vec<Self>, sqlx::Error> {
+            let (min_date, max_date) = month_range(year, month);
+
+            let records = sqlx::query!(
+                r#"
+                SELECT plans.id, plans.year, plans.month, plans.user_id, plans.location_id,
+                    plans.target, plans.currency, locations.address as "address?",
+                    COALESCE(locations.timezone, users.timezone) as timezone
+                FROM plans
+                LEFT JOIN locations ON locations.id = plans.location_id
+                LEFT JOIN users ON users.id = plans.user_id
+                WHERE plans.year = $1 AND plans.month = $2
+                AND (($3::UUID IS NULL)
+                    OR plans.user_id = $3::UUID
+                    OR locations.address IN (
+                        SELECT address FROM entries
+                        WHERE by_user_id = $3::UUID AND date >= $4 AND date <= $5
+                    ))
+                ORDER BY locations.address NULLS FIRST, plans.id
+                "#,
+                year,
+                month as i32,
+                user_id_filter,
+                min_date,
+                max_date
+            )
+            .fetch_all(pool)
+            .await?;
+
+            records
+                .into_iter()
+                .map(|r| {
+                    let scope = match (r.user_id, r.location_id, r.address) {
+                        (Some(user_id), _, _) => PlanScope::Manager(user_id),
+                        (None, Some(id), Some(address)) => PlanScope::Location { id, address },
+                        _ => return Err(sqlx::Error::Decode("plan without a scope".into())),
+                    };
+
+                    Ok(Self {
+                        id: r.id,
+                        year: r.year,
+                        month: r.month as u32,
+                        scope,
+                        target: Moneys::from_sql(r.target, &r.currency)?,
+                        timezone: tz::from_stored(r.timezone.as_deref()),
+                    })
+                })
+                .collect()
+        }
+
+        /// Sets the target of the scope for the month, replacing an existing one.
+        pub async fn upsert(
+            executor: impl PgExecutor<'_>,
+            year: i32,
+            month: u32,
+            scope: &PlanScope,
+            target: Moneys,
+        ) -> Result<(), sqlx::Error> {
+            let (user_id, location_id) = match scope {
+                PlanScope::Manager(user_id) => (Some(*user_id), None),
+                PlanScope::Location { id, .. } => (None, Some(*id)),
+            };
+
+            // separate statements, as each scope conflicts on its own partial index
+            match user_id {
+                Some(_) => {
+                    sqlx::query!(
+                        r#"
+                        INSERT INTO plans (year, month, user_id, target, currency)
+                        VALUES ($1, $2, $3, $4, $5)
+                        ON CONFLICT (user_id, year, month) WHERE user_id IS NOT NULL DO UPDATE
+                        SET target = EXCLUDED.target,
+                            currency = EXCLUDED.currency
+                        "#,
+                        year,
+                        month as i32,
+                        user_id,
+                        target.to_numeric(),
+                        target.currency.code()
+                    )
+                    .execute(executor)
+                    .await?;
+                }
+                None => {
+                    sqlx::query!(
+                        r#"
+                        INSERT INTO plans (year, month, location_id, target, currency)
+                        VALUES ($1, $2, $3, $4, $5)
+                        ON CONFLICT (location_id, year, month) WHERE location_id IS NOT NULL DO UPDATE
+                        SET target = EXCLUDED.target,
+                            currency = EXCLUDED.currency
+                        "#,
+                        year,
+                        month as i32,
+                        location_id,
+                        target.to_numeric(),
+                        target.currency.code()
+                    )
+                    .execute(executor)
+                    .await?;
+                }
+            }
+
+            Ok(())
+        }
+
+        pub async fn delete(executor: impl PgExecutor<'_>, id: IdType) -> Result<u64, sqlx::Error> {
+            let result = sqlx::query!(
+                r#"
+                DELETE FROM plans
+                WHERE id = $1
+                "#,
+                id
+            )
+            .execute(executor)
+            .await?;
+
+            Ok(result.rows_affected())
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::fmt::Display;
+
+use chrono::{NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+use crate::{error::AppError, i18n::Message};
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+const MAX_LABEL_CHARS: usize = 50;
+
+/// Hours an entry covers on its date. Entries without them cover the whole day.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Shift {
+    pub start: NaiveTime,
+    /// At or before `start` for a shift that ends the next day.
+    pub end: NaiveTime,
+}
+
+impl Shift {
+    /// Minutes from the start of the date, `[start, end)`.
+    fn span(&self) -> (u32, u32) {
+        let minutes = |time: NaiveTime| time.hour() * 60 + time.minute();
+        let (start, end) = (minutes(self.start), minutes(self.end));
+
+        if end <= start {
+            (start, end + MINUTES_PER_DAY)
+        } else {
+            (start, end)
+        }
+    }
+
+    pub fn overlaps(&self, other: &Shift) -> bool {
+        let (start, end) = self.span();
+        let (other_start, other_end) = other.span();
+
+        start < other_end && other_start < end
+    }
+
+    /// Whether two entries of a location and date can't both be filed. A manager's own
+    /// entries must not overlap, one without hours taking the whole day; entries of
+    /// different managers only clash when both have overlapping hours.
+    pub fn clashes(a: Option<Shift>, b: Option<Shift>, same_manager: bool) -> bool {
+        let whole_day = Shift {
+            start: NaiveTime::MIN,
+            end: NaiveTime::MIN,
+        };
+
+        match (a, b) {
+            (Some(a), Some(b)) => a.overlaps(&b),
+            (a, b) if same_manager => a.unwrap_or(whole_day).overlaps(&b.unwrap_or(whole_day)),
+            _ => false,
+        }
+    }
+}
+
+impl Display for Shift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}–{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+/// Shift as typed into the report form, submitted as `shift[start]`, `shift[end]`
+/// and `shift[label]`. All empty for a whole day report.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct ShiftFields {
+    #[serde(default)]
+    pub start: String,
+    #[serde(default)]
+    pub end: String,
+    #[serde(default)]
+    pub label: String,
+}
+
+impl ShiftFields {
+    pub fn parse(&self) -> Result<(Option<Shift>, Option<String>), AppError> {
+        let time = |value: &str| {
+            NaiveTime::parse_from_str(value, "%H:%M")
+                .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M:%S"))
+                .map_err(|_| {
+                    AppError::validation(
+                        "shift",
+                        Message::new("err-shift-time").arg("value", value),
+                    )
+                })
+        };
+
+        let shift = match (self.start.trim(), self.end.trim()) {
+            ("", "") => None,
+            ("", _) | (_, "") => return Err(AppError::validation("shift", "err-shift-incomplete")),
+            (start, end) => {
+                let shift = Shift {
+                    start: time(start)?,
+                    end: time(end)?,
+                };
+                if shift.start == shift.end {
+                    return Err(AppError::validation("shift", "err-shift-empty"));
+                }
+                Some(shift)
+            }
+        };
+
+        let label = match self.label.trim() {
+            "" => None,
+            label if label.chars().count() > MAX_LABEL_CHARS => {
+                return Err(AppError::validation(
+                    "shift_label",
+                    Message::new("err-shift-label-long").arg("max", MAX_LABEL_CHARS as u32),
+                ))
+            }
+            label => Some(label.to_string()),
+        };
+
+        Ok((shift, label))
+    }
+}
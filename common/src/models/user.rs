@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::IdType;
@@ -11,6 +12,10 @@ pub struct User {
     pub patronym: Option<String>,
     pub username: String,
     pub password: String,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub active: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -25,11 +30,163 @@ pub struct PermissionTokens {
 pub mod ssr {
     use std::collections::HashSet;
 
+    use sqlx::{PgExecutor, PgPool};
+
     use super::*;
 
-    use crate::user::UserPasshash;
+    use crate::{i18n::Locale, tz::Tz, user::UserPasshash};
 
     impl User {
+        /// Inserts a user with an already hashed password and returns its id.
+        pub async fn insert(
+            pool: &PgPool,
+            username: &str,
+            passhash: &str,
+            name: &str,
+            family_name: &str,
+            patronym: Option<&str>,
+        ) -> Result<IdType, sqlx::Error> {
+            let record = sqlx::query!(
+                r#"
+                INSERT INTO users (name, family_name, patronym, username, password)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING id
+                "#,
+                name,
+                family_name,
+                patronym,
+                username,
+                passhash,
+            )
+            .fetch_one(pool)
+            .await?;
+
+            Ok(record.id)
+        }
+
+        pub async fn set_password(
+            pool: &PgPool,
+            id: IdType,
+            passhash: &str,
+        ) -> Result<bool, sqlx::Error> {
+            let result = sqlx::query!(
+                r#"UPDATE users
+                SET password = $1
+                WHERE id = $2"#,
+                passhash,
+                id
+            )
+            .execute(pool)
+            .await?;
+
+            Ok(result.rows_affected() > 0)
+        }
+
+        /// Sets the interface language, `None` to follow the browser.
+        pub async fn set_locale(
+            pool: &PgPool,
+            id: IdType,
+            locale: Option<Locale>,
+        ) -> Result<(), sqlx::Error> {
+            sqlx::query!(
+                r#"UPDATE users
+                SET locale = $1
+                WHERE id = $2"#,
+                locale.map(|l| l.code()),
+                id
+            )
+            .execute(pool)
+            .await?;
+
+            Ok(())
+        }
+
+        /// Sets the zone report dates are checked in, `None` for the default.
+        pub async fn set_timezone(
+            pool: &PgPool,
+            id: IdType,
+            timezone: Option<Tz>,
+        ) -> Result<(), sqlx::Error> {
+            sqlx::query!(
+                r#"UPDATE users
+                SET timezone = $1
+                WHERE id = $2"#,
+                timezone.map(|tz| tz.name()),
+                id
+            )
+            .execute(pool)
+            .await?;
+
+            Ok(())
+        }
+
+        /// Lets the user sign in or locks them out, returns whether the user exists.
+        pub async fn set_active(
+            pool: &PgPool,
+            id: IdType,
+            active: bool,
+        ) -> Result<bool, sqlx::Error> {
+            let result = sqlx::query!(
+                r#"UPDATE users
+                SET active = $1
+                WHERE id = $2"#,
+                active,
+                id
+            )
+            .execute(pool)
+            .await?;
+
+            Ok(result.rows_affected() > 0)
+        }
+
+        /// Whether an insert or update failed on a username another user has.
+        pub fn is_username_taken(err: &sqlx::Error) -> bool {
+            matches!(err, sqlx::Error::Database(db) if db.is_unique_violation())
+        }
+
+        /// Adds the permission tokens the user doesn't have yet.
+        pub async fn grant(
+            executor: impl PgExecutor<'_>,
+            id: IdType,
+            tokens: &[&str],
+        ) -> Result<(), sqlx::Error> {
+            let tokens = tokens.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+            sqlx::query!(
+                r#"
+                INSERT INTO permissions (user_id, token)
+                SELECT DISTINCT $1::UUID, t.token FROM UNNEST($2::TEXT[]) AS t(token)
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM permissions
+                    WHERE permissions.user_id = $1 AND permissions.token = t.token
+                )
+                "#,
+                id,
+                &tokens
+            )
+            .execute(executor)
+            .await?;
+
+            Ok(())
+        }
+
+        pub async fn revoke(pool: &PgPool, id: IdType, tokens: &[&str]) -> Result<(), sqlx::Error> {
+            let tokens = tokens.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+            sqlx::query!(
+                r#"
+                DELETE FROM permissions
+                WHERE user_id = $1 AND token = ANY($2::TEXT[])
+                "#,
+                id,
+                &tokens
+            )
+            .execute(pool)
+            .await?;
+
+            Ok(())
+        }
+
         pub fn into_user_with_password(
             self,
             sql_user_perms: Option<Vec<String>>,
@@ -41,6 +198,10 @@ pub mod ssr {
                     name: self.name,
                     family_name: self.family_name,
                     patronym: self.patronym,
+                    locale: self.locale.and_then(|l| l.parse().ok()),
+                    timezone: self.timezone.and_then(|tz| tz.parse().ok()),
+                    created_at: self.created_at,
+                    active: self.active,
                     permissions: if let Some(user_perms) = sql_user_perms {
                         user_perms.into_iter().collect::<HashSet<String>>()
                     } else {
@@ -1,58 +1,380 @@
-use std::{fmt::Display, num::ParseFloatError, str::FromStr};
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
 
 use serde::{Deserialize, Serialize};
 
-const CENTS: f64 = 100.0;
+use crate::i18n::{Locale, Message};
 
-#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default)]
-pub struct Moneys(pub i64);
+#[derive(
+    Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub enum Currency {
+    #[default]
+    RUB,
+    BYN,
+    KZT,
+    USD,
+    EUR,
+}
 
-impl Display for Moneys {
+impl Currency {
+    pub const ALL: [Currency; 5] = [
+        Currency::RUB,
+        Currency::BYN,
+        Currency::KZT,
+        Currency::USD,
+        Currency::EUR,
+    ];
+
+    pub fn code(&self) -> &'static str {
+        self.iso().iso_alpha_code
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        self.iso().symbol
+    }
+
+    /// Number of minor unit digits, 2 for kopecks.
+    pub fn exponent(&self) -> u32 {
+        self.iso().exponent
+    }
+
+    fn minor_per_major(&self) -> f64 {
+        10f64.powi(self.exponent() as i32)
+    }
+
+    fn iso(&self) -> &'static rusty_money::iso::Currency {
+        use rusty_money::iso;
+
+        match self {
+            Currency::RUB => iso::RUB,
+            Currency::BYN => iso::BYN,
+            Currency::KZT => iso::KZT,
+            Currency::USD => iso::USD,
+            Currency::EUR => iso::EUR,
+        }
+    }
+}
+
+impl Display for Currency {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        use rusty_money::{iso, Money};
+        write!(f, "{}", self.code())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownCurrency(pub String);
+
+impl UnknownCurrency {
+    pub fn message(&self) -> Message {
+        Message::new("err-unknown-currency").arg("code", self.0.as_str())
+    }
+}
 
-        write!(f, "{}", Money::from_minor(self.0, iso::RUB))
+impl Display for UnknownCurrency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Locale::default().message(&self.message()))
     }
 }
 
-impl FromStr for Moneys {
-    type Err = ParseFloatError;
+impl std::error::Error for UnknownCurrency {}
+
+impl FromStr for Currency {
+    type Err = UnknownCurrency;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut it = s
-            .split(|c| char::is_ascii_punctuation(&c))
-            .collect::<Vec<&str>>()
+        Currency::ALL
             .into_iter()
-            .peekable();
+            .find(|c| c.code().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| UnknownCurrency(s.to_string()))
+    }
+}
 
-        let mut whole = String::default();
-        let mut decimal = String::default();
+/// An amount in minor units of its currency.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub struct Moneys {
+    pub amount: i64,
+    pub currency: Currency,
+}
+
+impl Moneys {
+    pub fn new(amount: i64, currency: Currency) -> Self {
+        Self { amount, currency }
+    }
 
-        while let Some(num) = it.next() {
-            if it.peek().is_some() || whole.is_empty() {
-                whole.push_str(num);
-            } else {
-                decimal.push_str(num);
+    /// Parses an amount in major units written the Russian or English way:
+    /// `1 234,56`, `1234.56`, `1,234.56`, `1.234,56`, with an optional leading sign.
+    ///
+    /// A single `,` or `.` followed by exactly three digits groups thousands
+    /// (`1,005` is `1005`), otherwise it separates the fraction. The conversion is
+    /// exact: more fraction digits than the currency has is an error, not rounding.
+    pub fn parse(s: &str, currency: Currency) -> Result<Self, MoneysParseError> {
+        let s = s.trim();
+
+        let (negative, unsigned) = match s.chars().next() {
+            Some('-' | '\u{2212}') => (true, &s[s.chars().next().map_or(0, char::len_utf8)..]),
+            Some('+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+
+        if unsigned.is_empty() {
+            return Err(MoneysParseError::Empty);
+        }
+
+        for c in unsigned.chars() {
+            match c {
+                '0'..='9' | '.' | ',' => {}
+                '-' | '+' | '\u{2212}' => return Err(MoneysParseError::MisplacedSign),
+                c if c.is_whitespace() => {}
+                c => return Err(MoneysParseError::InvalidCharacter(c)),
             }
         }
 
-        let f_val: f64 = format!("{whole}.{decimal}").parse()?;
+        let (whole, fraction) = split_fraction(unsigned, currency.exponent())?;
+
+        if fraction.len() > currency.exponent() as usize {
+            return Err(MoneysParseError::TooManyFractionDigits(currency.exponent()));
+        }
+
+        let digits = whole
+            .iter()
+            .copied()
+            .chain(fraction.chars())
+            .chain(std::iter::repeat_n(
+                '0',
+                currency.exponent() as usize - fraction.len(),
+            ));
 
-        let inner = f_val * CENTS;
+        let mut amount: i64 = 0;
+
+        for digit in digits {
+            let digit = i64::from(digit as u8 - b'0');
+
+            amount = amount
+                .checked_mul(10)
+                .and_then(|a| {
+                    if negative {
+                        a.checked_sub(digit)
+                    } else {
+                        a.checked_add(digit)
+                    }
+                })
+                .ok_or(MoneysParseError::Overflow)?;
+        }
+
+        Ok(Self::new(amount, currency))
+    }
+
+    /// Sum of two amounts, `None` when the currencies differ or on overflow.
+    pub fn checked_add(self, other: Moneys) -> Option<Moneys> {
+        if self.currency != other.currency {
+            return None;
+        }
 
-        Ok(Self(inner as i64))
+        self.amount
+            .checked_add(other.amount)
+            .map(|amount| Moneys::new(amount, self.currency))
     }
 }
 
-#[cfg(feature = "ssr")]
-impl From<sqlx_postgres::types::PgMoney> for Moneys {
-    fn from(value: sqlx_postgres::types::PgMoney) -> Self {
-        Self(value.0)
+impl Display for Moneys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use rusty_money::Money;
+
+        write!(f, "{}", Money::from_minor(self.amount, self.currency.iso()))
     }
 }
 
 impl From<Moneys> for f64 {
     fn from(val: Moneys) -> Self {
-        val.0 as f64 / CENTS
+        val.amount as f64 / val.currency.minor_per_major()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoneysParseError {
+    Empty,
+    InvalidCharacter(char),
+    MisplacedSign,
+    MisplacedSeparator,
+    TooManyFractionDigits(u32),
+    Overflow,
+}
+
+impl MoneysParseError {
+    pub fn message(&self) -> Message {
+        match self {
+            MoneysParseError::Empty => Message::new("err-amount-empty"),
+            MoneysParseError::InvalidCharacter(c) => {
+                Message::new("err-amount-character").arg("character", c.to_string())
+            }
+            MoneysParseError::MisplacedSign => Message::new("err-amount-sign"),
+            MoneysParseError::MisplacedSeparator => Message::new("err-amount-separator"),
+            MoneysParseError::TooManyFractionDigits(max) => {
+                Message::new("err-amount-fraction").arg("max", *max)
+            }
+            MoneysParseError::Overflow => Message::new("err-amount-overflow"),
+        }
+    }
+}
+
+impl Display for MoneysParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Locale::default().message(&self.message()))
+    }
+}
+
+impl std::error::Error for MoneysParseError {}
+
+/// Splits digits of the whole part, dropping group separators, from the fraction digits.
+fn split_fraction(s: &str, exponent: u32) -> Result<(Vec<char>, &str), MoneysParseError> {
+    let is_space = |c: char| c.is_whitespace();
+
+    let dots = s.matches('.').count();
+    let commas = s.matches(',').count();
+    let spaced = s.contains(is_space);
+
+    let decimal = match (dots, commas) {
+        (0, 0) => None,
+        // both present: the last one separates the fraction
+        (_, _) if dots > 0 && commas > 0 => s.rfind(['.', ',']).and_then(|i| s[i..].chars().next()),
+        (1, 0) | (0, 1) => {
+            let separator = if dots == 1 { '.' } else { ',' };
+            let (before, after) = s.split_once(separator).unwrap_or_default();
+            let groups_thousands = !spaced
+                && exponent < 3
+                && after.len() == 3
+                && (1..=3).contains(&before.len())
+                && !before.starts_with('0');
+
+            (!groups_thousands).then_some(separator)
+        }
+        _ => None,
+    };
+
+    let (whole, fraction) = match decimal {
+        Some(separator) => s.rsplit_once(separator).unwrap_or_default(),
+        None => (s, ""),
+    };
+
+    if decimal.is_some() && (fraction.is_empty() || !fraction.chars().all(|c| c.is_ascii_digit())) {
+        return Err(MoneysParseError::MisplacedSeparator);
+    }
+
+    if whole.is_empty() {
+        return Err(MoneysParseError::MisplacedSeparator);
+    }
+
+    let group_separator = whole.chars().find(|c| !c.is_ascii_digit());
+
+    let digits = match group_separator {
+        None => whole.chars().collect(),
+        Some(separator) => {
+            let is_group_separator =
+                |c: char| c == separator || (is_space(separator) && is_space(c));
+
+            if Some(separator) == decimal {
+                return Err(MoneysParseError::MisplacedSeparator);
+            }
+
+            let groups = whole.split(is_group_separator).collect::<Vec<_>>();
+            let (first, rest) = groups
+                .split_first()
+                .ok_or(MoneysParseError::MisplacedSeparator)?;
+
+            let valid = (1..=3).contains(&first.len())
+                && rest.iter().all(|g| g.len() == 3)
+                && groups.iter().all(|g| g.chars().all(|c| c.is_ascii_digit()));
+
+            if !valid {
+                return Err(MoneysParseError::MisplacedSeparator);
+            }
+
+            groups.concat().chars().collect()
+        }
+    };
+
+    Ok((digits, fraction))
+}
+
+/// Totals kept apart per currency, since amounts in different currencies can't be added.
+/// Sums are kept in `i128` so that no number of amounts overflows them.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub struct MoneyTotals(BTreeMap<Currency, i128>);
+
+impl MoneyTotals {
+    pub fn add(&mut self, moneys: Moneys) {
+        *self.0.entry(moneys.currency).or_default() += i128::from(moneys.amount);
+    }
+
+    /// The total in `currency`, `None` without amounts in it or when it's too large
+    /// for an amount.
+    pub fn get(&self, currency: Currency) -> Option<Moneys> {
+        self.0
+            .get(&currency)
+            .and_then(|amount| i64::try_from(*amount).ok())
+            .map(|amount| Moneys::new(amount, currency))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl FromIterator<Moneys> for MoneyTotals {
+    fn from_iter<T: IntoIterator<Item = Moneys>>(iter: T) -> Self {
+        iter.into_iter().fold(Self::default(), |mut acc, m| {
+            acc.add(m);
+            acc
+        })
+    }
+}
+
+impl Display for MoneyTotals {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use rust_decimal::Decimal;
+        use rusty_money::Money;
+
+        if self.is_empty() {
+            return write!(f, "{}", Moneys::default());
+        }
+
+        // a sum of fewer than 2^32 amounts fits the 96 bit mantissa
+        let totals = self
+            .0
+            .iter()
+            .map(|(currency, amount)| {
+                let amount = Decimal::from_i128_with_scale(*amount, currency.exponent());
+                Money::from_decimal(amount, currency.iso()).to_string()
+            })
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", totals.join(" + "))
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub mod ssr {
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    impl Moneys {
+        /// Reads a `NUMERIC` amount and currency code column pair.
+        pub fn from_sql(amount: Decimal, currency: &str) -> Result<Self, sqlx::Error> {
+            let currency =
+                Currency::from_str(currency).map_err(|err| sqlx::Error::Decode(Box::new(err)))?;
+
+            let mut amount = amount;
+            amount.rescale(currency.exponent());
+
+            let amount = i64::try_from(amount.mantissa())
+                .map_err(|err| sqlx::Error::Decode(Box::new(err)))?;
+
+            Ok(Self::new(amount, currency))
+        }
+
+        pub fn to_numeric(&self) -> Decimal {
+            Decimal::new(self.amount, self.currency.exponent())
+        }
     }
 }
@@ -0,0 +1,190 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::AppError,
+    models::{Entry, ReportFields},
+    moneys::{Currency, Moneys},
+    IdType,
+};
+
+/// A report filed or changed without a connection, kept in the browser until synced.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct QueuedReport {
+    /// Generated in the browser, syncing the same change twice applies it once.
+    pub client_id: IdType,
+    /// Manager the report is filed by, others signing in on the device don't sync it.
+    pub user_id: IdType,
+    /// The changed report, `None` for a new one.
+    pub entry_id: Option<IdType>,
+    /// Revision of the report the change was made to.
+    pub base_revision: Option<i32>,
+    pub report: ReportFields,
+    pub queued_at: DateTime<Utc>,
+    pub state: QueueState,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub enum QueueState {
+    /// Waiting for a connection.
+    Pending,
+    /// The server has another version of the report: changed since, or filed from
+    /// another device. The user keeps one of them.
+    Conflict(Entry),
+    /// Refused by the server, e.g. dated before the current month by the time it synced.
+    Failed(AppError),
+}
+
+/// What syncing a queued report did.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum SyncOutcome {
+    Applied {
+        entry_id: IdType,
+        revision: i32,
+    },
+    /// Nothing was changed, this is the server's version.
+    Conflict(Entry),
+}
+
+impl QueuedReport {
+    pub fn is_pending(&self) -> bool {
+        matches!(self.state, QueueState::Pending)
+    }
+
+    /// Total of the breakdown as typed, in rubles until synced when the location's
+    /// currency is meant.
+    pub fn revenue(&self) -> Option<Moneys> {
+        let currency = self.report.currency.parse::<Currency>().unwrap_or_default();
+
+        self.report
+            .payments
+            .parse(currency)
+            .ok()
+            .map(|(_, total)| total)
+    }
+
+    /// Resolves a conflict with the queued version: sent again as a change of the
+    /// server's one.
+    pub fn keep_mine(&mut self) {
+        if let QueueState::Conflict(server) = &self.state {
+            self.entry_id = Some(server.id);
+            self.base_revision = Some(server.revision);
+            self.state = QueueState::Pending;
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub mod ssr {
+    use sqlx::{PgExecutor, PgPool};
+
+    use super::*;
+
+    /// A queued change applied to an entry, see [`QueuedReport::client_id`].
+    pub struct EntrySync;
+
+    impl EntrySync {
+        /// Entry and revision the user's change with `client_id` resulted in, if synced.
+        pub async fn find(
+            pool: &PgPool,
+            client_id: IdType,
+            user_id: IdType,
+        ) -> Result<Option<(IdType, i32)>, sqlx::Error> {
+            let record = sqlx::query!(
+                r#"
+                SELECT entry_id, revision FROM entry_syncs
+                WHERE client_id = $1 AND entry_syncs.user_id = $2
+                "#,
+                client_id,
+                user_id
+            )
+            .fetch_optional(pool)
+            .await?;
+
+            Ok(record.map(|r| (r.entry_id, r.revision)))
+        }
+
+        pub async fn record(
+            executor: impl PgExecutor<'_>,
+            client_id: IdType,
+            user_id: IdType,
+            entry_id: IdType,
+            revision: i32,
+        ) -> Result<(), sqlx::Error> {
+            sqlx::query!(
+                r#"
+                INSERT INTO entry_syncs (client_id, user_id, entry_id, revision)
+                VALUES ($1, $2, $3, $4)
+                "#,
+                client_id,
+                user_id,
+                entry_id,
+                revision
+            )
+            .execute(executor)
+            .await?;
+
+            Ok(())
+        }
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::ssr::EntrySync;
+    use crate::{migrations::ScratchDatabase, models::User, IdType};
+
+    #[tokio::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn synced_changes_are_found_for_their_user_only() {
+        let db = ScratchDatabase::create().await;
+        let pool = &db.pool;
+
+        let mut entries = vec![];
+        for username in ["ivanov", "petrov"] {
+            let user_id = User::insert(pool, username, "-", "Иван", "Иванов", None)
+                .await
+                .unwrap();
+            let entry_id: IdType = sqlx::query_scalar(
+                r#"
+                INSERT INTO entries (address, date, by_user_id)
+                VALUES ('Lenina 1', '2024-03-01', $1)
+                RETURNING id
+                "#,
+            )
+            .bind(user_id)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+            entries.push((user_id, entry_id));
+        }
+        let [(ivanov, ivanovs), (petrov, petrovs)] = entries[..] else {
+            unreachable!()
+        };
+        let client_id = IdType::from_u128(1);
+
+        EntrySync::record(pool, client_id, ivanov, ivanovs, 1)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            EntrySync::find(pool, client_id, ivanov).await.unwrap(),
+            Some((ivanovs, 1))
+        );
+        assert_eq!(
+            EntrySync::find(pool, client_id, petrov).await.unwrap(),
+            None
+        );
+
+        // the same id from another browser doesn't stop the other user's change
+        EntrySync::record(pool, client_id, petrov, petrovs, 2)
+            .await
+            .unwrap();
+        assert_eq!(
+            EntrySync::find(pool, client_id, petrov).await.unwrap(),
+            Some((petrovs, 2))
+        );
+
+        db.drop().await;
+    }
+}
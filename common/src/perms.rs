@@ -2,3 +2,11 @@ pub const MANAGE_USERS: &str = "ManageUsers";
 pub const VIEW_ALL: &str = "ViewAll";
 pub const VIEW_OWNED: &str = "ViewOwned";
 pub const EDIT_OWNED: &str = "EditOwned";
+
+pub const ALL: [&str; 4] = [MANAGE_USERS, VIEW_ALL, VIEW_OWNED, EDIT_OWNED];
+
+/// Tokens granted by the "administrator" checkbox when creating a user.
+pub const ADMIN: [&str; 2] = [MANAGE_USERS, VIEW_ALL];
+
+/// Tokens of a regular manager filing their own reports.
+pub const MANAGER: [&str; 2] = [EDIT_OWNED, VIEW_OWNED];
@@ -0,0 +1,230 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::IdType;
+
+/// Hits shown per group.
+pub const LIMIT: i64 = 5;
+
+/// Shortest query searched for, shorter ones match too much to be useful.
+pub const MIN_QUERY_CHARS: usize = 2;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+pub enum SearchKind {
+    Manager,
+    Address,
+    Date,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct SearchHit {
+    pub kind: SearchKind,
+    /// The manager, or the report the hit opens.
+    pub id: IdType,
+    /// Manager's full name or the report's address.
+    pub title: String,
+    /// Manager's username, or the manager of a report on the date.
+    pub detail: Option<String>,
+    /// Date of the report the hit opens.
+    pub date: Option<NaiveDate>,
+    /// Reports filed at the address.
+    pub reports: Option<i64>,
+}
+
+impl SearchHit {
+    pub fn href(&self) -> String {
+        match self.kind {
+            SearchKind::Manager => format!("/reports?user_id={}", self.id),
+            SearchKind::Address | SearchKind::Date => format!("/reports/{}", self.id),
+        }
+    }
+}
+
+/// Hits grouped by kind, best first within a group.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct SearchResults {
+    pub managers: Vec<SearchHit>,
+    pub addresses: Vec<SearchHit>,
+    pub dates: Vec<SearchHit>,
+}
+
+impl SearchResults {
+    /// Non-empty groups in display order.
+    pub fn groups(&self) -> Vec<(SearchKind, &[SearchHit])> {
+        [
+            (SearchKind::Date, self.dates.as_slice()),
+            (SearchKind::Manager, self.managers.as_slice()),
+            (SearchKind::Address, self.addresses.as_slice()),
+        ]
+        .into_iter()
+        .filter(|(_, hits)| !hits.is_empty())
+        .collect()
+    }
+
+    /// All hits in display order, as walked with the arrow keys.
+    pub fn hits(&self) -> Vec<&SearchHit> {
+        self.groups()
+            .into_iter()
+            .flat_map(|(_, hits)| hits)
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.managers.is_empty() && self.addresses.is_empty() && self.dates.is_empty()
+    }
+}
+
+/// A date as typed into the search: `12.03.2024`, `12.03.24`, `12/03/2024` or `2024-03-12`.
+pub fn parse_date(query: &str) -> Option<NaiveDate> {
+    let query = query.trim();
+    let parse = |format: &str| NaiveDate::parse_from_str(query, format).ok();
+
+    // "%Y" takes a two digit year as is, year 24 instead of 2024
+    ["%d.%m.%Y", "%d/%m/%Y", "%Y-%m-%d"]
+        .into_iter()
+        .filter_map(parse)
+        .find(|date| date.year() >= 1000)
+        .or_else(|| ["%d.%m.%y", "%d/%m/%y"].into_iter().find_map(parse))
+}
+
+/// `ILIKE` pattern matching `text` anywhere, its wildcards taken literally.
+pub fn like_pattern(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+
+    format!("%{escaped}%")
+}
+
+#[cfg(feature = "ssr")]
+pub mod ssr {
+    use sqlx::PgPool;
+
+    use super::*;
+    use crate::perms::EDIT_OWNED;
+
+    impl SearchResults {
+        /// Searches reports of `owner` only when set, managers only when not.
+        pub async fn find(
+            pool: &PgPool,
+            query: &str,
+            owner: Option<IdType>,
+        ) -> Result<Self, sqlx::Error> {
+            let query = query.trim();
+            let pattern = like_pattern(query);
+
+            let managers = match owner {
+                Some(_) => vec![],
+                None => sqlx::query!(
+                    r#"
+                    SELECT users.id, users.username,
+                        users.family_name || ' ' || users.name || ' ' || COALESCE(users.patronym, '') AS "full_name!",
+                        GREATEST(
+                            WORD_SIMILARITY($1, users.family_name || ' ' || users.name || ' ' || COALESCE(users.patronym, '')),
+                            WORD_SIMILARITY($1, users.username)
+                        ) AS "rank!"
+                    FROM users
+                    WHERE EXISTS (
+                        SELECT 1 FROM permissions
+                        WHERE permissions.user_id = users.id AND permissions.token = $3
+                    )
+                    AND ($1 <% (users.family_name || ' ' || users.name || ' ' || COALESCE(users.patronym, ''))
+                        OR $1 <% users.username
+                        OR (users.family_name || ' ' || users.name || ' ' || COALESCE(users.patronym, '')) ILIKE $2
+                        OR users.username ILIKE $2)
+                    ORDER BY "rank!" DESC, users.active DESC, LOWER(users.family_name), users.id
+                    LIMIT $4
+                    "#,
+                    query,
+                    pattern,
+                    EDIT_OWNED,
+                    LIMIT
+                )
+                .fetch_all(pool)
+                .await?
+                .into_iter()
+                .map(|r| SearchHit {
+                    kind: SearchKind::Manager,
+                    id: r.id,
+                    title: r.full_name.trim_end().to_string(),
+                    detail: Some(r.username),
+                    date: None,
+                    reports: None,
+                })
+                .collect(),
+            };
+
+            // the latest report at each matching address
+            let addresses = sqlx::query!(
+                r#"
+                SELECT id AS "id!", address AS "address!", date AS "date!", reports AS "reports!", rank AS "rank!"
+                FROM (
+                    SELECT DISTINCT ON (entries.address) entries.id, entries.address, entries.date,
+                        COUNT(*) OVER (PARTITION BY entries.address) AS reports,
+                        WORD_SIMILARITY($1, entries.address) AS rank
+                    FROM entries
+                    WHERE ($3::UUID IS NULL OR entries.by_user_id = $3)
+                    AND ($1 <% entries.address OR entries.address ILIKE $2)
+                    ORDER BY entries.address, entries.date DESC, entries.id
+                ) AS latest
+                ORDER BY rank DESC, date DESC
+                LIMIT $4
+                "#,
+                query,
+                pattern,
+                owner,
+                LIMIT
+            )
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .map(|r| SearchHit {
+                kind: SearchKind::Address,
+                id: r.id,
+                title: r.address,
+                detail: None,
+                date: Some(r.date),
+                reports: Some(r.reports),
+            })
+            .collect();
+
+            let dates = match parse_date(query) {
+                None => vec![],
+                Some(date) => sqlx::query!(
+                    r#"
+                    SELECT entries.id, entries.address, entries.date,
+                        users.family_name || ' ' || users.name || ' ' || COALESCE(users.patronym, '') AS "manager!"
+                    FROM entries
+                    INNER JOIN users ON users.id = entries.by_user_id
+                    WHERE entries.date = $1
+                    AND ($2::UUID IS NULL OR entries.by_user_id = $2)
+                    ORDER BY LOWER(users.family_name), entries.address, entries.shift_start NULLS FIRST
+                    LIMIT $3
+                    "#,
+                    date,
+                    owner,
+                    LIMIT
+                )
+                .fetch_all(pool)
+                .await?
+                .into_iter()
+                .map(|r| SearchHit {
+                    kind: SearchKind::Date,
+                    id: r.id,
+                    title: r.address,
+                    detail: Some(r.manager.trim_end().to_string()),
+                    date: Some(r.date),
+                    reports: None,
+                })
+                .collect(),
+            };
+
+            Ok(Self {
+                managers,
+                addresses,
+                dates,
+            })
+        }
+    }
+}
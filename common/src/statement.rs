@@ -0,0 +1,266 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{
+    digest::period,
+    error::AppError,
+    i18n::{Locale, Message},
+    models::EntryWithUser,
+    summary::{by_manager, Totals},
+    tz,
+    user::User,
+    IdType,
+};
+
+/// Longest period a statement covers, in days.
+pub const MAX_DAYS: i64 = 366;
+
+/// Period and manager of a statement as requested from `/statements.pdf`.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct StatementQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// The manager whose entries are listed, everyone's when `None`. The reports
+    /// viewer submits an empty value for the whole company.
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub user_id: Option<IdType>,
+}
+
+impl StatementQuery {
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.to < self.from {
+            return Err(AppError::validation("to", "err-statement-period"));
+        }
+        if (self.to - self.from).num_days() >= MAX_DAYS {
+            return Err(AppError::validation(
+                "to",
+                Message::new("err-statement-too-long").arg("days", MAX_DAYS),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Whose entries are listed given the viewer's `Entry::owner_filter`: managers
+    /// only get their own, the whole company needs `ViewAll`.
+    pub fn owner(&self, filter: Option<Option<IdType>>) -> Result<Option<IdType>, AppError> {
+        match (filter, self.user_id) {
+            (None, _) => Err(AppError::Forbidden(
+                "err-forbidden-view-reports".to_string(),
+            )),
+            (Some(Some(own)), Some(requested)) if requested != own => Err(AppError::Forbidden(
+                "err-forbidden-view-others-reports".to_string(),
+            )),
+            (Some(Some(own)), _) => Ok(Some(own)),
+            (Some(None), requested) => Ok(requested),
+        }
+    }
+}
+
+fn empty_as_none<'de, D>(deserializer: D) -> Result<Option<IdType>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)?.as_deref() {
+        None | Some("") => Ok(None),
+        Some(id) => id.parse().map(Some).map_err(serde::de::Error::custom),
+    }
+}
+
+/// A line of the entries table, as in `ReportsTable`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatementRow {
+    pub date: String,
+    /// Label and hours of the entry's shift, empty for a whole day.
+    pub shift: String,
+    pub manager: String,
+    pub address: String,
+    pub revenue: String,
+    /// Revenue in the reporting currency, when filed in another one.
+    pub converted: Option<String>,
+}
+
+/// Entries of a period archived by accounting: a manager's or the whole company's,
+/// with the summary's subtotals per manager and who generated it when.
+#[derive(Clone)]
+pub struct Statement {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// The manager whose entries are listed, `None` for the whole company.
+    pub manager: Option<User>,
+    /// Entries of the period with their converted revenues, by date.
+    pub entries: Vec<EntryWithUser>,
+    pub generated_at: DateTime<Utc>,
+    /// The signed in user, `None` when generated with the CLI.
+    pub generated_by: Option<User>,
+}
+
+impl Statement {
+    pub fn new(
+        from: NaiveDate,
+        to: NaiveDate,
+        manager: Option<User>,
+        mut entries: Vec<EntryWithUser>,
+        generated_at: DateTime<Utc>,
+        generated_by: Option<User>,
+    ) -> Self {
+        entries.sort_by(|a, b| {
+            (
+                a.date,
+                a.shift.map(|s| s.start),
+                &a.user.family_name,
+                &a.address,
+            )
+                .cmp(&(
+                    b.date,
+                    b.shift.map(|s| s.start),
+                    &b.user.family_name,
+                    &b.address,
+                ))
+        });
+
+        Self {
+            from,
+            to,
+            manager,
+            entries,
+            generated_at,
+            generated_by,
+        }
+    }
+
+    pub fn period(&self) -> String {
+        period(self.from, self.to)
+    }
+
+    pub fn title(&self, locale: Locale) -> String {
+        locale.message(&Message::new("statement-title").arg("period", self.period()))
+    }
+
+    /// The manager the statement is for, or that it covers everyone.
+    pub fn scope(&self, locale: Locale) -> String {
+        match self.manager.as_ref() {
+            Some(manager) => locale
+                .message(&Message::new("statement-manager").arg("manager", manager.short_name())),
+            None => locale.tr("statement-company"),
+        }
+    }
+
+    /// `statement-2024-03-01-2024-03-31.pdf`, followed by the manager's username.
+    pub fn file_name(&self) -> String {
+        let dates = format!(
+            "{}-{}",
+            self.from.format("%Y-%m-%d"),
+            self.to.format("%Y-%m-%d")
+        );
+
+        match self.manager.as_ref() {
+            Some(manager) => format!("statement-{dates}-{}.pdf", manager.username),
+            None => format!("statement-{dates}.pdf"),
+        }
+    }
+
+    pub fn header(locale: Locale) -> [String; 4] {
+        [
+            "field-date",
+            "field-manager",
+            "field-address",
+            "field-revenue",
+        ]
+        .map(|key| locale.tr(key))
+    }
+
+    pub fn rows(&self) -> Vec<StatementRow> {
+        self.entries
+            .iter()
+            .map(|entry| StatementRow {
+                date: entry.date.format("%d.%m.%Y").to_string(),
+                shift: [
+                    entry.shift_label.clone(),
+                    entry.shift.map(|s| s.to_string()),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(", "),
+                manager: entry.user.short_name(),
+                address: entry.address.clone(),
+                revenue: entry.revenue.to_string(),
+                converted: entry
+                    .converted
+                    .filter(|c| c.currency != entry.revenue.currency)
+                    .map(|converted| format!("≈ {converted}")),
+            })
+            .collect()
+    }
+
+    /// Totals of each manager's entries, by family name.
+    pub fn subtotals(&self) -> Vec<(User, Totals)> {
+        by_manager(&self.entries)
+    }
+
+    pub fn totals(&self) -> Totals {
+        Totals::of(&self.entries)
+    }
+
+    /// When and by whom it was generated, in the generating user's zone.
+    pub fn generated(&self, locale: Locale) -> String {
+        let zone = tz::resolve(
+            None,
+            self.generated_by.as_ref().and_then(|user| user.timezone),
+        );
+        let at = self
+            .generated_at
+            .with_timezone(&zone)
+            .format("%d.%m.%Y %H:%M %Z")
+            .to_string();
+
+        match self.generated_by.as_ref() {
+            Some(user) => locale.message(
+                &Message::new("statement-generated")
+                    .arg("at", at)
+                    .arg("user", user.short_name()),
+            ),
+            None => locale.message(&Message::new("statement-generated-cli").arg("at", at)),
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub mod ssr {
+    use sqlx::PgPool;
+
+    use super::*;
+    use crate::{exchange::ssr::ExchangeRates, moneys::Currency};
+
+    impl Statement {
+        /// Entries of the period filed by `owner`, or by everyone when `None`, with
+        /// revenues converted to `currency`.
+        pub async fn load(
+            pool: &PgPool,
+            currency: Currency,
+            (from, to): (NaiveDate, NaiveDate),
+            owner: Option<IdType>,
+            generated_by: Option<User>,
+        ) -> Result<Self, sqlx::Error> {
+            let manager = match owner {
+                Some(id) => Some(User::get(id, pool).await.ok_or(sqlx::Error::RowNotFound)?),
+                None => None,
+            };
+
+            let mut entries = EntryWithUser::list_range(pool, from, to, owner).await?;
+            ExchangeRates::load(pool, currency, to)
+                .await?
+                .convert_entries(&mut entries);
+
+            Ok(Self::new(
+                from,
+                to,
+                manager,
+                entries,
+                Utc::now(),
+                generated_by,
+            ))
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    i18n::Locale,
+    models::{EntryWithUser, PaymentMethod},
+    moneys::MoneyTotals,
+    user::User,
+    IdType,
+};
+
+/// Revenue of a set of entries, in total and per payment method.
+#[derive(Clone, Default)]
+pub struct Totals {
+    pub revenue: MoneyTotals,
+    pub by_method: BTreeMap<PaymentMethod, MoneyTotals>,
+    /// Revenue of entries filed without a breakdown.
+    pub unsplit: MoneyTotals,
+    /// Revenue per shift name, `None` for whole day entries.
+    pub by_shift: BTreeMap<Option<String>, MoneyTotals>,
+    /// Comments on the entries the viewing user hasn't read.
+    pub unread_comments: i64,
+}
+
+impl Totals {
+    pub fn of<'a>(entries: impl IntoIterator<Item = &'a EntryWithUser>) -> Self {
+        entries.into_iter().fold(Self::default(), |mut acc, entry| {
+            acc.add(entry);
+            acc
+        })
+    }
+
+    pub fn add(&mut self, entry: &EntryWithUser) {
+        self.revenue.add(entry.converted.unwrap_or(entry.revenue));
+        self.unread_comments += entry.unread_comments;
+
+        if entry.payments.is_empty() {
+            self.unsplit.add(entry.converted.unwrap_or(entry.revenue));
+        }
+
+        self.by_shift
+            .entry(entry.shift_name())
+            .or_default()
+            .add(entry.converted.unwrap_or(entry.revenue));
+
+        for payment in entry.payments.iter() {
+            self.by_method
+                .entry(payment.method)
+                .or_default()
+                .add(payment.converted.unwrap_or(payment.amount));
+        }
+    }
+
+    pub fn method(&self, method: PaymentMethod) -> String {
+        self.by_method
+            .get(&method)
+            .cloned()
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    pub fn shift(&self, name: &Option<String>) -> String {
+        self.by_shift
+            .get(name)
+            .cloned()
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    /// A column per shift name and one for whole day entries, once a shift was reported.
+    pub fn shift_columns(&self) -> Vec<Option<String>> {
+        match self.by_shift.keys().any(Option::is_some) {
+            true => {
+                let mut names = self.by_shift.keys().cloned().collect::<Vec<_>>();
+                let whole_day = usize::from(names.first() == Some(&None));
+                names.rotate_left(whole_day);
+                names
+            }
+            false => vec![],
+        }
+    }
+
+    /// Labels and values of a row of the summary table, see `ReportsSummary`, with
+    /// the columns `period` has.
+    pub fn columns(&self, period: &Totals, locale: Locale) -> Vec<(String, String)> {
+        let mut columns = PaymentMethod::ALL
+            .map(|method| (method.label(locale), self.method(method)))
+            .to_vec();
+
+        if !period.unsplit.is_empty() {
+            columns.push((locale.tr("summary-unsplit"), self.unsplit.to_string()));
+        }
+        for name in period.shift_columns() {
+            let value = self.shift(&name);
+            columns.push((
+                name.unwrap_or_else(|| locale.tr("summary-whole-day")),
+                value,
+            ));
+        }
+
+        columns
+    }
+}
+
+/// Totals of each manager's entries, by family name.
+pub fn by_manager<'a>(entries: impl IntoIterator<Item = &'a EntryWithUser>) -> Vec<(User, Totals)> {
+    let grouped = entries.into_iter().fold(
+        HashMap::<IdType, (User, Totals)>::new(),
+        |mut acc, entry| {
+            acc.entry(entry.user.id)
+                .or_insert_with(|| (entry.user.clone(), Totals::default()))
+                .1
+                .add(entry);
+
+            acc
+        },
+    );
+
+    let mut managers = grouped.into_values().collect::<Vec<_>>();
+    managers.sort_by(|(a, _), (b, _)| a.family_name.cmp(&b.family_name));
+
+    managers
+}
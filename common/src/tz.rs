@@ -0,0 +1,63 @@
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+pub use chrono_tz::Tz;
+
+use crate::i18n::Message;
+
+/// Zone of users and locations without their own.
+pub const DEFAULT: Tz = Tz::Europe__Moscow;
+
+/// Zones offered in the user form, any other IANA name is accepted too.
+pub const SUGGESTED: [Tz; 11] = [
+    Tz::Europe__Kaliningrad,
+    Tz::Europe__Moscow,
+    Tz::Europe__Samara,
+    Tz::Asia__Yekaterinburg,
+    Tz::Asia__Omsk,
+    Tz::Asia__Novosibirsk,
+    Tz::Asia__Krasnoyarsk,
+    Tz::Asia__Irkutsk,
+    Tz::Asia__Yakutsk,
+    Tz::Asia__Vladivostok,
+    Tz::Asia__Magadan,
+];
+
+/// Zone a report is dated in: its location's, else its manager's, else [`DEFAULT`].
+pub fn resolve(location: Option<Tz>, user: Option<Tz>) -> Tz {
+    location.or(user).unwrap_or(DEFAULT)
+}
+
+/// Zone stored as its IANA name, [`DEFAULT`] when unset or no longer known.
+pub fn from_stored(name: Option<&str>) -> Tz {
+    name.and_then(|name| name.parse().ok()).unwrap_or(DEFAULT)
+}
+
+/// The user's choice from a form, `None` for an empty one.
+pub fn parse_choice(choice: Option<&str>) -> Result<Option<Tz>, Message> {
+    match choice.map(str::trim).unwrap_or_default() {
+        "" => Ok(None),
+        name => name
+            .parse()
+            .map(Some)
+            .map_err(|_| Message::new("err-unknown-timezone").arg("name", name)),
+    }
+}
+
+/// Local date at `now` in the zone.
+pub fn date_at(tz: Tz, now: DateTime<Utc>) -> NaiveDate {
+    now.with_timezone(&tz).date_naive()
+}
+
+pub fn today(tz: Tz) -> NaiveDate {
+    date_at(tz, Utc::now())
+}
+
+/// Dates reports can be filed or changed for on `today`: the current month up to today.
+pub fn report_window(today: NaiveDate) -> (NaiveDate, NaiveDate) {
+    (today.with_day(1).unwrap_or(today), today)
+}
+
+pub fn in_report_window(date: NaiveDate, today: NaiveDate) -> bool {
+    let (from, to) = report_window(today);
+
+    from <= date && date <= to
+}
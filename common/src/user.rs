@@ -1,6 +1,13 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, str::FromStr};
 
-use crate::IdType;
+use chrono::{DateTime, Utc};
+
+use crate::{
+    error::AppError,
+    i18n::Locale,
+    tz::{self, Tz},
+    IdType,
+};
 use leptos::*;
 use serde::{Deserialize, Serialize};
 
@@ -8,7 +15,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct UserPasshash(pub String);
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct User {
     pub id: IdType,
     pub name: String,
@@ -16,10 +23,123 @@ pub struct User {
     pub patronym: Option<String>,
     pub username: String,
     pub permissions: HashSet<String>,
+    /// Interface language the user chose, `None` follows the browser.
+    #[serde(default)]
+    pub locale: Option<Locale>,
+    /// Zone report dates are checked in where the location has none, `None` for the default.
+    #[serde(default)]
+    pub timezone: Option<Tz>,
+    #[serde(default)]
+    pub created_at: DateTime<Utc>,
+    /// Inactive users can't sign in, their reports stay.
+    #[serde(default = "active_by_default")]
+    pub active: bool,
+}
+
+fn active_by_default() -> bool {
+    true
+}
+
+impl Default for User {
+    fn default() -> Self {
+        Self {
+            id: IdType::default(),
+            name: String::new(),
+            family_name: String::new(),
+            patronym: None,
+            username: String::new(),
+            permissions: HashSet::new(),
+            locale: None,
+            timezone: None,
+            created_at: DateTime::default(),
+            active: active_by_default(),
+        }
+    }
+}
+
+impl User {
+    /// Family name and initials, as managers are listed in summaries.
+    pub fn short_name(&self) -> String {
+        let initial = |s: &str| s.chars().next().map(|c| format!("{c}."));
+
+        [
+            Some(self.family_name.clone()),
+            initial(&self.name),
+            self.patronym.as_deref().and_then(initial),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ")
+    }
+}
+
+/// Preferences as typed into the user form, submitted as `settings[locale]` and
+/// `settings[timezone]`. Empty ones fall back to the defaults.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct SettingsFields {
+    #[serde(default)]
+    pub locale: String,
+    #[serde(default)]
+    pub timezone: String,
+}
+
+impl SettingsFields {
+    pub fn parse(&self) -> Result<(Option<Locale>, Option<Tz>), AppError> {
+        let locale = Locale::parse_choice(Some(&self.locale))
+            .map_err(|message| AppError::validation("locale", message))?;
+        let timezone = tz::parse_choice(Some(&self.timezone))
+            .map_err(|message| AppError::validation("timezone", message))?;
+
+        Ok((locale, timezone))
+    }
+}
+
+/// Users shown on a page of the users list.
+pub const USERS_PER_PAGE: i64 = 25;
+
+/// Column the users list is ordered by, family name then name by default.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub enum UserSort {
+    #[default]
+    FamilyName,
+    Username,
+    Created,
+}
+
+impl UserSort {
+    pub const ALL: [UserSort; 3] = [UserSort::FamilyName, UserSort::Username, UserSort::Created];
+
+    /// Name in the page URL and the listing query.
+    pub fn code(&self) -> &'static str {
+        match self {
+            UserSort::FamilyName => "family_name",
+            UserSort::Username => "username",
+            UserSort::Created => "created",
+        }
+    }
+}
+
+impl FromStr for UserSort {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        UserSort::ALL
+            .into_iter()
+            .find(|sort| sort.code() == s)
+            .ok_or(())
+    }
+}
+
+/// A page of the users list and the number of users matching its filters.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct UserPage {
+    pub users: Vec<User>,
+    pub total: i64,
 }
 
 #[server]
-pub async fn get_user() -> Result<Option<User>, ServerFnError> {
+pub async fn get_user() -> Result<Option<User>, ServerFnError<crate::error::AppError>> {
     use crate::ctx::auth;
 
     let auth = auth()?;
@@ -100,8 +220,10 @@ pub mod ssr {
         async fn load_user(userid: IdType, pool: Option<&PgPool>) -> Result<User, anyhow::Error> {
             let pool = pool.ok_or_else(|| anyhow::anyhow!("No pool"))?;
 
+            // a deactivated user's session ends with their next request
             User::get(userid, pool)
                 .await
+                .filter(|user| user.active)
                 .ok_or_else(|| anyhow::anyhow!("Cannot get user"))
         }
 
@@ -110,7 +232,7 @@ pub mod ssr {
         }
 
         fn is_active(&self) -> bool {
-            true
+            self.active
         }
 
         fn is_anonymous(&self) -> bool {
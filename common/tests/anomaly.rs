@@ -0,0 +1,90 @@
+use common::anomaly::{outlier, Thresholds};
+
+fn thresholds(score: f64, min_samples: usize) -> Thresholds {
+    Thresholds {
+        score,
+        min_samples,
+        ..Default::default()
+    }
+}
+
+/// Median 0 and median absolute deviation 1, so the score is `0.6745 * value`.
+const SPREAD: [f64; 9] = [-3.0, -1.0, -1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 3.0];
+
+#[test]
+fn short_histories_flag_nothing() {
+    let history = [100.0; 9];
+
+    assert_eq!(outlier(&history, 1e9, &thresholds(3.5, 10)), None);
+    assert!(outlier(
+        &[history.as_slice(), &[100.0]].concat(),
+        1e9,
+        &thresholds(3.5, 10)
+    )
+    .is_some());
+
+    // an empty history is never enough
+    assert_eq!(outlier(&[], 1e9, &thresholds(3.5, 0)), None);
+}
+
+#[test]
+fn scores_are_modified_z_scores() {
+    let found = outlier(&SPREAD, 10.0, &thresholds(3.5, 5)).unwrap();
+
+    assert_eq!(found.median, 0.0);
+    assert_eq!(found.score, 0.6745 * 10.0);
+
+    let found = outlier(&SPREAD, -10.0, &thresholds(3.5, 5)).unwrap();
+    assert_eq!(found.score, -0.6745 * 10.0);
+
+    assert_eq!(outlier(&SPREAD, 2.0, &thresholds(3.5, 5)), None);
+}
+
+#[test]
+fn scores_on_the_threshold_are_usual() {
+    let thresholds = thresholds(0.6745 * 4.0, 5);
+
+    assert_eq!(outlier(&SPREAD, 4.0, &thresholds), None);
+    assert_eq!(outlier(&SPREAD, -4.0, &thresholds), None);
+    assert!(outlier(&SPREAD, 4.01, &thresholds).is_some());
+    assert!(outlier(&SPREAD, -4.01, &thresholds).is_some());
+}
+
+#[test]
+fn earlier_outliers_do_not_skew_the_score() {
+    let mut history = SPREAD.to_vec();
+    history.push(1e9);
+
+    assert!(outlier(&history, 10.0, &thresholds(3.5, 5)).is_some());
+}
+
+#[test]
+fn a_zero_mad_falls_back_to_the_mean_deviation() {
+    // the median deviation is 0, the mean one 30
+    let history = [
+        100.0, 100.0, 100.0, 100.0, 100.0, 100.0, 100.0, 100.0, 200.0, 300.0,
+    ];
+    let thresholds = thresholds(3.5, 10);
+
+    assert_eq!(outlier(&history, 200.0, &thresholds), None);
+
+    let found = outlier(&history, 300.0, &thresholds).unwrap();
+    assert_eq!(found.median, 100.0);
+    assert_eq!(found.score, 200.0 / (1.2533 * 30.0));
+}
+
+#[test]
+fn a_constant_history_flags_any_other_value() {
+    let history = [100.0; 10];
+    let thresholds = thresholds(3.5, 10);
+
+    assert_eq!(outlier(&history, 100.0, &thresholds), None);
+    assert_eq!(
+        outlier(&history, 100.5, &thresholds).map(|o| o.score),
+        Some(f64::INFINITY)
+    );
+    assert_eq!(
+        outlier(&history, 99.5, &thresholds).map(|o| o.score),
+        Some(f64::NEG_INFINITY)
+    );
+}
@@ -0,0 +1,86 @@
+use chrono::{DateTime, TimeZone, Utc};
+use common::{
+    models::{comment::MAX_COMMENT_LENGTH, Comment},
+    user::User,
+};
+use uuid::Uuid;
+
+fn at(minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 1, 12, minute, 0).unwrap()
+}
+
+fn comment(id: u128, parent_id: Option<u128>, minute: u32) -> Comment {
+    Comment {
+        id: Uuid::from_u128(id),
+        entry_id: Uuid::from_u128(100),
+        parent_id: parent_id.map(Uuid::from_u128),
+        author: User::default(),
+        body: format!("comment {id}"),
+        created_at: at(minute),
+        edited_at: None,
+    }
+}
+
+fn thread(comments: &[Comment]) -> Vec<(usize, u128)> {
+    Comment::thread(comments)
+        .into_iter()
+        .map(|(depth, c)| (depth, c.id.as_u128()))
+        .collect()
+}
+
+#[test]
+fn replies_follow_their_parent_oldest_first() {
+    let comments = [
+        comment(4, Some(1), 4),
+        comment(2, None, 2),
+        comment(3, Some(1), 3),
+        comment(1, None, 1),
+        comment(5, Some(3), 5),
+    ];
+
+    assert_eq!(
+        thread(&comments),
+        vec![(0, 1), (1, 3), (2, 5), (1, 4), (0, 2)]
+    );
+}
+
+#[test]
+fn ties_are_ordered_by_id() {
+    let comments = [comment(2, None, 1), comment(1, None, 1)];
+
+    assert_eq!(thread(&comments), vec![(0, 1), (0, 2)]);
+}
+
+#[test]
+fn replies_to_missing_comments_start_threads() {
+    let comments = [
+        comment(1, None, 1),
+        comment(2, Some(99), 2),
+        comment(3, Some(2), 3),
+    ];
+
+    assert_eq!(thread(&comments), vec![(0, 1), (0, 2), (1, 3)]);
+}
+
+#[test]
+fn bodies_are_trimmed_and_not_empty() {
+    assert_eq!(Comment::validate_body("  Hello\n"), Ok("Hello"));
+    assert_eq!(
+        Comment::validate_body(" \n\t"),
+        Err("err-comment-empty".to_string())
+    );
+}
+
+#[test]
+fn the_length_limit_counts_characters() {
+    // two bytes each in UTF-8
+    let longest = "ж".repeat(MAX_COMMENT_LENGTH);
+    assert_eq!(Comment::validate_body(&longest), Ok(longest.as_str()));
+
+    let too_long = format!("{longest}ж");
+    let err = Comment::validate_body(&too_long).unwrap_err();
+    assert!(err.starts_with("err-comment-too-long"), "{err}");
+
+    // surrounding whitespace doesn't count
+    assert!(Comment::validate_body(&format!("  {longest}  ")).is_ok());
+}
@@ -0,0 +1,218 @@
+use chrono::NaiveDate;
+use common::{
+    digest::{is_email, Digest, DigestFields, DigestFrequency},
+    i18n::Locale,
+    models::{EntryWithUser, Payment, PaymentMethod},
+    moneys::{Currency, Moneys},
+    tz,
+    user::User,
+};
+use uuid::Uuid;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn manager(id: u128, family_name: &str) -> User {
+    User {
+        id: Uuid::from_u128(id),
+        name: "Ivan".to_string(),
+        family_name: family_name.to_string(),
+        patronym: Some("Petrovich".to_string()),
+        username: family_name.to_lowercase(),
+        ..Default::default()
+    }
+}
+
+fn entry(user: &User, date: NaiveDate, cash: i64, card: i64) -> EntryWithUser {
+    let payment = |method, amount| Payment {
+        method,
+        amount: Moneys::new(amount, Currency::RUB),
+        converted: None,
+    };
+
+    EntryWithUser {
+        id: Uuid::new_v4(),
+        address: "Lenina 1".to_string(),
+        revenue: Moneys::new(cash + card, Currency::RUB),
+        converted: None,
+        payments: vec![
+            payment(PaymentMethod::Cash, cash),
+            payment(PaymentMethod::Card, card),
+        ],
+        date,
+        shift: None,
+        shift_label: None,
+        user: user.clone(),
+        anomaly: None,
+        timezone: tz::DEFAULT,
+        comments: 0,
+        unread_comments: 0,
+    }
+}
+
+#[test]
+fn daily_digests_cover_the_day_before() {
+    assert_eq!(
+        DigestFrequency::Daily.period(date(2024, 3, 1)),
+        Some((date(2024, 2, 29), date(2024, 2, 29)))
+    );
+}
+
+#[test]
+fn weekly_digests_cover_the_previous_week_on_mondays() {
+    // 2024-03-11 is a Monday
+    assert_eq!(
+        DigestFrequency::Weekly.period(date(2024, 3, 11)),
+        Some((date(2024, 3, 4), date(2024, 3, 10)))
+    );
+    assert_eq!(DigestFrequency::Weekly.period(date(2024, 3, 12)), None);
+}
+
+#[test]
+fn monthly_digests_cover_the_previous_month_on_the_first() {
+    assert_eq!(
+        DigestFrequency::Monthly.period(date(2024, 1, 1)),
+        Some((date(2023, 12, 1), date(2023, 12, 31)))
+    );
+    assert_eq!(DigestFrequency::Monthly.period(date(2024, 1, 2)), None);
+}
+
+#[test]
+fn frequencies_round_trip_their_codes() {
+    for frequency in DigestFrequency::ALL {
+        assert_eq!(frequency.code().parse(), Ok(frequency));
+    }
+    assert!("hourly".parse::<DigestFrequency>().is_err());
+}
+
+#[test]
+fn checked_frequencies_need_an_address() {
+    let fields = DigestFields {
+        email: " ".to_string(),
+        daily: Some("on".to_string()),
+        ..Default::default()
+    };
+    let err = fields.parse().unwrap_err();
+    assert_eq!(err.field(), Some("email"));
+
+    let fields = DigestFields {
+        email: " boss@example.com ".to_string(),
+        daily: Some("on".to_string()),
+        monthly: Some("on".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        fields.parse().unwrap(),
+        (
+            "boss@example.com".to_string(),
+            vec![DigestFrequency::Daily, DigestFrequency::Monthly]
+        )
+    );
+
+    assert_eq!(
+        DigestFields::default().parse().unwrap(),
+        (String::new(), vec![])
+    );
+}
+
+#[test]
+fn email_addresses_are_checked_roughly() {
+    assert!(is_email("boss@example.com"));
+    assert!(is_email("first.last+digest@mail.example.ru"));
+    assert!(!is_email("boss"));
+    assert!(!is_email("boss@localhost"));
+    assert!(!is_email("@example.com"));
+    assert!(!is_email("boss@example..com"));
+    assert!(!is_email("boss@ex@ample.com"));
+    assert!(!is_email("boss @example.com"));
+    assert!(!is_email("boss@example.com\r\nBcc: x@example.com"));
+}
+
+#[test]
+fn daily_digests_add_the_month_to_date() {
+    let ivanov = manager(1, "Ivanov");
+    let petrov = manager(2, "Petrov");
+    let entries = vec![
+        entry(&petrov, date(2024, 3, 12), 10_000, 5_000),
+        entry(&ivanov, date(2024, 3, 12), 20_000, 0),
+        entry(&ivanov, date(2024, 3, 1), 10_0000, 0),
+    ];
+
+    let (from, to) = DigestFrequency::Daily.period(date(2024, 3, 13)).unwrap();
+    assert_eq!(
+        Digest::range(DigestFrequency::Daily, from, to),
+        (date(2024, 3, 1), date(2024, 3, 12))
+    );
+
+    let digest = Digest::new(DigestFrequency::Daily, from, to, entries);
+
+    assert_eq!(digest.entries.len(), 2);
+    assert_eq!(
+        digest
+            .month_to_date
+            .as_ref()
+            .and_then(|totals| totals.get(Currency::RUB)),
+        Some(Moneys::new(135_000, Currency::RUB))
+    );
+
+    let text = digest.text(Locale::En);
+    assert!(text.starts_with("Revenue for 12.03.2024\n"), "{text}");
+    assert!(text.contains("2 reports by 2 managers"), "{text}");
+    assert!(text.contains("Month to date:"), "{text}");
+    // managers by family name
+    let ivanov_at = text.find("Ivanov I. P.:").unwrap();
+    let petrov_at = text.find("Petrov I. P.:").unwrap();
+    assert!(ivanov_at < petrov_at, "{text}");
+
+    assert_eq!(
+        digest.subject(Locale::En),
+        "Daily summary for 12.03.2024".to_string()
+    );
+}
+
+#[test]
+fn monthly_digests_have_no_month_to_date() {
+    let ivanov = manager(1, "Ivanov");
+    let (from, to) = DigestFrequency::Monthly.period(date(2024, 4, 1)).unwrap();
+
+    let digest = Digest::new(
+        DigestFrequency::Monthly,
+        from,
+        to,
+        vec![entry(&ivanov, date(2024, 3, 5), 10_000, 0)],
+    );
+
+    assert!(digest.month_to_date.is_none());
+    assert_eq!(digest.period(), "01.03.2024 – 31.03.2024");
+    assert!(!digest.text(Locale::En).contains("Month to date"));
+}
+
+#[test]
+fn html_digests_escape_names() {
+    let manager = manager(1, "<b>Ivanov</b> & Co");
+    let (from, to) = DigestFrequency::Daily.period(date(2024, 3, 13)).unwrap();
+
+    let html = Digest::new(
+        DigestFrequency::Daily,
+        from,
+        to,
+        vec![entry(&manager, to, 10_000, 0)],
+    )
+    .html(Locale::En);
+
+    assert!(
+        html.contains("&lt;b&gt;Ivanov&lt;/b&gt; &amp; Co"),
+        "{html}"
+    );
+    assert!(!html.contains("<b>Ivanov"), "{html}");
+}
+
+#[test]
+fn empty_periods_say_so() {
+    let (from, to) = DigestFrequency::Daily.period(date(2024, 3, 13)).unwrap();
+    let digest = Digest::new(DigestFrequency::Daily, from, to, vec![]);
+
+    assert!(digest.text(Locale::En).contains("No reports were filed."));
+    assert!(!digest.html(Locale::En).contains("<table"));
+}
@@ -0,0 +1,74 @@
+use common::{
+    error::AppError,
+    i18n::{Locale, Message},
+};
+use http::StatusCode;
+use leptos::{server_fn::error::ServerFnErrorSerde, ServerFnError};
+use uuid::Uuid;
+
+fn roundtrip(err: AppError) -> ServerFnError<AppError> {
+    let sent = ServerFnError::WrappedServerError(err).ser().unwrap();
+
+    <ServerFnError<AppError> as ServerFnErrorSerde>::de(&sent)
+}
+
+#[test]
+fn travels_to_the_client() {
+    let field = AppError::validation("cash", Message::new("err-amount-fraction").arg("max", 2));
+
+    for err in [
+        AppError::Unauthorized,
+        AppError::Forbidden("err-forbidden-edit-plans".to_string()),
+        AppError::NotFound("err-report-not-found".to_string()),
+        field,
+        AppError::Conflict("err-duplicate-entry".to_string()),
+        AppError::Internal,
+    ] {
+        assert_eq!(
+            roundtrip(err.clone()),
+            ServerFnError::WrappedServerError(err)
+        );
+    }
+}
+
+#[test]
+fn statuses() {
+    assert_eq!(
+        AppError::Unauthorized.status_code(),
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        AppError::Forbidden(String::new()).status_code(),
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        AppError::validation("date", "err-report-date-range").status_code(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+    assert_eq!(
+        AppError::duplicate_entry(Uuid::nil()).status_code(),
+        StatusCode::CONFLICT
+    );
+}
+
+#[test]
+fn duplicate_links_the_existing_entry() {
+    let existing = Uuid::from_u128(42);
+    let err = AppError::duplicate_entry(existing);
+
+    assert_eq!(err.duplicate_of(), Some(existing));
+    assert_eq!(AppError::Internal.duplicate_of(), None);
+    assert_eq!(
+        Locale::En.text(err.message()),
+        "There is already a report for this location and date"
+    );
+}
+
+#[test]
+fn field_errors() {
+    let err = AppError::validation("username", "err-username-taken");
+
+    assert_eq!(err.field(), Some("username"));
+    assert_eq!(AppError::Unauthorized.field(), None);
+    assert_eq!(Locale::Ru.text(err.message()), "Этот логин уже занят");
+}
@@ -0,0 +1,124 @@
+use std::collections::BTreeSet;
+
+use common::i18n::{Arg, Locale, Message};
+use proptest::prelude::*;
+
+/// Message keys defined in a catalog, attributes and terms left out.
+fn keys(catalog: &str) -> BTreeSet<&str> {
+    catalog
+        .lines()
+        .filter(|line| line.starts_with(|c: char| c.is_ascii_lowercase()))
+        .filter_map(|line| line.split_once(" =").map(|(key, _)| key))
+        .collect()
+}
+
+#[test]
+fn catalogs_have_the_same_keys() {
+    let ru = keys(include_str!("../locales/ru.ftl"));
+    let en = keys(include_str!("../locales/en.ftl"));
+
+    assert!(ru.len() > 100, "{} keys in ru", ru.len());
+    assert_eq!(
+        ru.difference(&en).collect::<Vec<_>>(),
+        Vec::<&&str>::new(),
+        "missing in en"
+    );
+    assert_eq!(
+        en.difference(&ru).collect::<Vec<_>>(),
+        Vec::<&&str>::new(),
+        "missing in ru"
+    );
+}
+
+#[test]
+fn every_key_formats() {
+    for locale in Locale::ALL {
+        for key in keys(include_str!("../locales/ru.ftl")) {
+            assert_ne!(locale.tr(key), key, "{key} in {locale}");
+        }
+    }
+}
+
+#[test]
+fn russian_plurals() {
+    let days = |count: u32| {
+        Locale::Ru.message(
+            &Message::new("missing-days")
+                .arg("count", count)
+                .arg("dates", "1"),
+        )
+    };
+
+    assert_eq!(days(1), "1 день: 1");
+    assert_eq!(days(2), "2 дня: 1");
+    assert_eq!(days(5), "5 дней: 1");
+    assert_eq!(days(11), "11 дней: 1");
+    assert_eq!(days(21), "21 день: 1");
+    assert_eq!(days(24), "24 дня: 1");
+}
+
+#[test]
+fn english_plurals() {
+    let files =
+        |count: u32| Locale::En.message(&Message::new("attachments-uploaded").arg("count", count));
+
+    assert_eq!(files(1), "1 file uploaded");
+    assert_eq!(files(2), "2 files uploaded");
+}
+
+#[test]
+fn unknown_keys_and_plain_text_pass_through() {
+    assert_eq!(Locale::En.tr("no-such-key"), "no-such-key");
+    assert_eq!(Locale::En.text("Connection refused"), "Connection refused");
+    assert_eq!(
+        Locale::En.text("err-unknown-currency?code=XYZ"),
+        "Unknown currency: XYZ"
+    );
+    assert_eq!(
+        Locale::Ru.error_text("err-file-missing"),
+        "Ошибка: файл не выбран"
+    );
+}
+
+#[test]
+fn negotiation() {
+    assert_eq!(
+        Locale::negotiate("en-US,en;q=0.9,ru;q=0.8"),
+        Some(Locale::En)
+    );
+    assert_eq!(
+        Locale::negotiate("de-DE, ru;q=0.5, en;q=0.4"),
+        Some(Locale::Ru)
+    );
+    assert_eq!(Locale::negotiate("ru;q=0.5, en"), Some(Locale::En));
+    assert_eq!(Locale::negotiate("en;q=0, ru;q=0.1"), Some(Locale::Ru));
+    assert_eq!(Locale::negotiate("de, fr"), None);
+    assert_eq!(Locale::negotiate(""), None);
+}
+
+#[test]
+fn locale_choice() {
+    assert_eq!(Locale::parse_choice(None), Ok(None));
+    assert_eq!(Locale::parse_choice(Some(" ")), Ok(None));
+    assert_eq!(Locale::parse_choice(Some("en")), Ok(Some(Locale::En)));
+    assert!(Locale::parse_choice(Some("de")).is_err());
+}
+
+fn arg() -> impl Strategy<Value = Arg> {
+    prop_oneof![
+        any::<String>().prop_map(Arg::Text),
+        (-1e9..1e9f64).prop_map(Arg::Number),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn message_roundtrip(
+        key in "[a-z][a-z0-9-]{0,30}",
+        args in prop::collection::vec(("[a-z][a-z-]{0,10}", arg()), 0..4),
+    ) {
+        let message = Message { key, args };
+
+        prop_assert_eq!(message.to_string().parse::<Message>(), Ok(message));
+    }
+}
@@ -0,0 +1,48 @@
+use chrono::NaiveDate;
+use common::live::{ChangeKind, EntryChange};
+use uuid::Uuid;
+
+#[test]
+fn reads_the_database_notification() {
+    // as built by `notify_entry_changes()`
+    let payload = r#"{"kind" : "deleted", "entry_id" : "00000000-0000-0000-0000-00000000002a", "user_id" : "00000000-0000-0000-0000-000000000007", "date" : "2024-03-12"}"#;
+
+    assert_eq!(
+        serde_json::from_str::<EntryChange>(payload).unwrap(),
+        EntryChange {
+            kind: ChangeKind::Deleted,
+            entry_id: Some(Uuid::from_u128(42)),
+            user_id: Uuid::from_u128(7),
+            date: NaiveDate::from_ymd_opt(2024, 3, 12),
+        }
+    );
+}
+
+#[test]
+fn reads_bulk_changes() {
+    let payload = r#"{"kind" : "created", "entry_id" : null, "user_id" : "00000000-0000-0000-0000-000000000007", "date" : null}"#;
+
+    assert_eq!(
+        serde_json::from_str::<EntryChange>(payload).unwrap(),
+        EntryChange {
+            kind: ChangeKind::Created,
+            entry_id: None,
+            user_id: Uuid::from_u128(7),
+            date: None,
+        }
+    );
+}
+
+#[test]
+fn scoped_to_the_owner() {
+    let change = EntryChange {
+        kind: ChangeKind::Created,
+        entry_id: Some(Uuid::from_u128(42)),
+        user_id: Uuid::from_u128(7),
+        date: NaiveDate::from_ymd_opt(2024, 3, 12),
+    };
+
+    assert!(change.visible_to(None));
+    assert!(change.visible_to(Some(Uuid::from_u128(7))));
+    assert!(!change.visible_to(Some(Uuid::from_u128(8))));
+}
@@ -0,0 +1,192 @@
+use common::moneys::{Currency, MoneyTotals, Moneys, MoneysParseError};
+use proptest::prelude::*;
+
+const NBSP: char = '\u{a0}';
+
+fn parse(s: &str) -> Result<i64, MoneysParseError> {
+    Moneys::parse(s, Currency::RUB).map(|m| m.amount)
+}
+
+/// Whole part digits grouped by three with `separator`.
+fn grouped(whole: u64, separator: char) -> String {
+    let digits = whole.to_string();
+    let mut out = String::new();
+
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(separator);
+        }
+        out.push(c);
+    }
+
+    out
+}
+
+fn format(amount: i64, group: Option<char>, decimal: char) -> String {
+    let sign = if amount < 0 { "-" } else { "" };
+    let abs = amount.unsigned_abs();
+    let whole = match group {
+        Some(separator) => grouped(abs / 100, separator),
+        None => (abs / 100).to_string(),
+    };
+
+    format!("{sign}{whole}{decimal}{:02}", abs % 100)
+}
+
+#[test]
+fn parses_examples() {
+    assert_eq!(parse("19.99"), Ok(1999));
+    assert_eq!(parse("19,99"), Ok(1999));
+    assert_eq!(parse("1,005.1"), Ok(100510));
+    assert_eq!(parse("1.005,1"), Ok(100510));
+    assert_eq!(parse("1,005"), Ok(100500));
+    assert_eq!(parse("1 234,56"), Ok(123456));
+    assert_eq!(parse(&format!("1{NBSP}234{NBSP}567,8")), Ok(123456780));
+    assert_eq!(parse("1\u{202f}234.5"), Ok(123450));
+    assert_eq!(parse("1.234.567"), Ok(123456700));
+    assert_eq!(parse(" 42 "), Ok(4200));
+    assert_eq!(parse("+0,5"), Ok(50));
+    assert_eq!(parse("-0,01"), Ok(-1));
+    assert_eq!(parse("\u{2212}7"), Ok(-700));
+    assert_eq!(parse("0012"), Ok(1200));
+}
+
+#[test]
+fn rejects_malformed_input() {
+    assert_eq!(parse(""), Err(MoneysParseError::Empty));
+    assert_eq!(parse("  -"), Err(MoneysParseError::Empty));
+    assert_eq!(parse("12-"), Err(MoneysParseError::MisplacedSign));
+    assert_eq!(parse("--1"), Err(MoneysParseError::MisplacedSign));
+    assert_eq!(parse("1e5"), Err(MoneysParseError::InvalidCharacter('e')));
+    assert_eq!(parse("12 ₽"), Err(MoneysParseError::InvalidCharacter('₽')));
+    assert_eq!(
+        parse("0,125"),
+        Err(MoneysParseError::TooManyFractionDigits(2))
+    );
+    assert_eq!(
+        parse("1.2345"),
+        Err(MoneysParseError::TooManyFractionDigits(2))
+    );
+    assert_eq!(parse("1,2.34"), Err(MoneysParseError::MisplacedSeparator));
+    assert_eq!(
+        parse("1.234,5.6"),
+        Err(MoneysParseError::MisplacedSeparator)
+    );
+    assert_eq!(parse("12 34"), Err(MoneysParseError::MisplacedSeparator));
+    assert_eq!(parse("1  234"), Err(MoneysParseError::MisplacedSeparator));
+    assert_eq!(parse(".5"), Err(MoneysParseError::MisplacedSeparator));
+    assert_eq!(parse("5."), Err(MoneysParseError::MisplacedSeparator));
+}
+
+#[test]
+fn detects_overflow() {
+    assert_eq!(parse("92233720368547758.07"), Ok(i64::MAX));
+    assert_eq!(parse("-92233720368547758.08"), Ok(i64::MIN));
+    assert_eq!(
+        parse("92233720368547758.08"),
+        Err(MoneysParseError::Overflow)
+    );
+    assert_eq!(
+        parse("-92233720368547758.09"),
+        Err(MoneysParseError::Overflow)
+    );
+    assert_eq!(
+        parse("100000000000000000000"),
+        Err(MoneysParseError::Overflow)
+    );
+}
+
+#[test]
+fn totals_do_not_overflow() {
+    let max = Moneys::new(i64::MAX, Currency::RUB);
+    let totals = [max, max, Moneys::new(2, Currency::RUB)]
+        .into_iter()
+        .collect::<MoneyTotals>();
+
+    assert_eq!(totals.get(Currency::RUB), None);
+    assert_eq!(
+        totals
+            .to_string()
+            .chars()
+            .filter(char::is_ascii_digit)
+            .collect::<String>(),
+        "18446744073709551616"
+    );
+
+    let totals = [
+        max,
+        Moneys::new(-1, Currency::RUB),
+        Moneys::new(5, Currency::USD),
+    ]
+    .into_iter()
+    .collect::<MoneyTotals>();
+
+    assert_eq!(
+        totals.get(Currency::RUB),
+        Some(Moneys::new(i64::MAX - 1, Currency::RUB))
+    );
+    assert_eq!(
+        totals.get(Currency::USD),
+        Some(Moneys::new(5, Currency::USD))
+    );
+    assert_eq!(totals.get(Currency::EUR), None);
+}
+
+proptest! {
+    #[test]
+    fn plain_round_trip(amount in any::<i64>()) {
+        prop_assert_eq!(parse(&format(amount, None, '.')), Ok(amount));
+        prop_assert_eq!(parse(&format(amount, None, ',')), Ok(amount));
+    }
+
+    #[test]
+    fn english_round_trip(amount in any::<i64>()) {
+        prop_assert_eq!(parse(&format(amount, Some(','), '.')), Ok(amount));
+    }
+
+    #[test]
+    fn russian_round_trip(amount in any::<i64>()) {
+        prop_assert_eq!(parse(&format(amount, Some(NBSP), ',')), Ok(amount));
+        prop_assert_eq!(parse(&format(amount, Some(' '), ',')), Ok(amount));
+        prop_assert_eq!(parse(&format(amount, Some('.'), ',')), Ok(amount));
+    }
+
+    #[test]
+    fn whole_amounts(whole in 0u64..1_000_000_000_000, negative in any::<bool>()) {
+        let sign = if negative { "-" } else { "" };
+        let expected = whole as i64 * 100 * if negative { -1 } else { 1 };
+
+        prop_assert_eq!(parse(&format!("{sign}{whole}")), Ok(expected));
+        prop_assert_eq!(parse(&format!("{sign}{}", grouped(whole, NBSP))), Ok(expected));
+    }
+
+    #[test]
+    fn extra_fraction_digits_are_rejected(whole in 0u64..1_000_000, fraction in 0u32..10_000) {
+        let input = format!("{whole},{fraction:04}");
+
+        prop_assert_eq!(parse(&input), Err(MoneysParseError::TooManyFractionDigits(2)));
+    }
+
+    // "1,005" groups thousands, three fraction digits are only unambiguous after 0 or 4+ digits
+    #[test]
+    fn three_fraction_digits_are_rejected(
+        whole in prop_oneof![Just(0u64), 1000u64..1_000_000],
+        fraction in 0u32..1000,
+    ) {
+        let input = format!("{whole}.{fraction:03}");
+
+        prop_assert_eq!(parse(&input), Err(MoneysParseError::TooManyFractionDigits(2)));
+    }
+
+    #[test]
+    fn arbitrary_input_never_panics(input in "\\PC{0,24}") {
+        let _ = parse(&input);
+    }
+
+    #[test]
+    fn numeric_looking_input_never_panics(input in "[-+ 0-9.,\u{a0}]{0,32}") {
+        if let Ok(amount) = parse(&input) {
+            prop_assert!(input.chars().any(|c| c.is_ascii_digit()), "{} parsed as {}", input, amount);
+        }
+    }
+}
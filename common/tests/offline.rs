@@ -0,0 +1,100 @@
+use chrono::{NaiveDate, Utc};
+use common::{
+    error::AppError,
+    i18n::Locale,
+    models::{Entry, PaymentFields, ReportFields},
+    moneys::{Currency, Moneys},
+    offline::{QueueState, QueuedReport},
+};
+use uuid::Uuid;
+
+fn queued(report: ReportFields) -> QueuedReport {
+    QueuedReport {
+        client_id: Uuid::from_u128(1),
+        user_id: Uuid::from_u128(2),
+        entry_id: None,
+        base_revision: None,
+        report,
+        queued_at: Utc::now(),
+        state: QueueState::Pending,
+    }
+}
+
+fn report(cash: &str, currency: &str) -> ReportFields {
+    ReportFields {
+        payments: PaymentFields {
+            cash: cash.to_string(),
+            ..Default::default()
+        },
+        currency: currency.to_string(),
+        address: "Lenina 1".to_string(),
+        date: NaiveDate::from_ymd_opt(2024, 3, 12).unwrap(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn keeping_mine_changes_the_server_version() {
+    let server = Entry {
+        id: Uuid::from_u128(42),
+        revision: 3,
+        ..Default::default()
+    };
+    let mut item = queued(report("100", ""));
+    item.state = QueueState::Conflict(server);
+
+    item.keep_mine();
+
+    assert!(item.is_pending());
+    assert_eq!(item.entry_id, Some(Uuid::from_u128(42)));
+    assert_eq!(item.base_revision, Some(3));
+}
+
+#[test]
+fn keeping_mine_leaves_other_states() {
+    let mut item = queued(report("100", ""));
+    item.state = QueueState::Failed(AppError::validation("date", "err-report-date-range"));
+
+    item.keep_mine();
+
+    assert!(!item.is_pending());
+    assert_eq!(item.entry_id, None);
+}
+
+#[test]
+fn revenue_as_typed() {
+    assert_eq!(
+        queued(report("1 234,50", "USD")).revenue(),
+        Some(Moneys::new(123450, Currency::USD))
+    );
+    assert_eq!(
+        queued(report("10", "")).revenue(),
+        Some(Moneys::new(1000, Currency::default()))
+    );
+    assert_eq!(queued(report("1,2,3", "")).revenue(), None);
+}
+
+#[test]
+fn change_conflict_links_the_report() {
+    let existing = Uuid::from_u128(42);
+    let err = AppError::changed_meanwhile(existing);
+
+    assert_eq!(err.duplicate_of(), Some(existing));
+    assert_eq!(
+        Locale::En.text(err.message()),
+        "The report was changed since it was opened"
+    );
+}
+
+#[test]
+fn survives_the_browser_store() {
+    let mut item = queued(report("100", "EUR"));
+    item.state = QueueState::Conflict(Entry::default());
+
+    let stored = serde_json::to_string(&item).unwrap();
+    let loaded: QueuedReport = serde_json::from_str(&stored).unwrap();
+
+    assert_eq!(loaded.client_id, item.client_id);
+    assert_eq!(loaded.report, item.report);
+    assert!(matches!(loaded.state, QueueState::Conflict(_)));
+}
@@ -0,0 +1,124 @@
+use common::{
+    error::AppError,
+    models::{Payment, PaymentFields, PaymentMethod},
+    moneys::{Currency, Moneys},
+};
+
+fn rub(amount: i64) -> Moneys {
+    Moneys::new(amount, Currency::RUB)
+}
+
+fn fields(cash: &str, card: &str, online: &str, returns: &str) -> PaymentFields {
+    PaymentFields {
+        cash: cash.to_string(),
+        card: card.to_string(),
+        online: online.to_string(),
+        returns: returns.to_string(),
+    }
+}
+
+#[test]
+fn revenue_is_the_sum_less_returns() {
+    let (payments, revenue) = fields("1 000", "2500,50", "", "100")
+        .parse(Currency::RUB)
+        .unwrap();
+
+    assert_eq!(revenue, rub(340_050));
+    assert_eq!(
+        payments,
+        vec![
+            Payment {
+                method: PaymentMethod::Cash,
+                amount: rub(100_000),
+                converted: None,
+            },
+            Payment {
+                method: PaymentMethod::Card,
+                amount: rub(250_050),
+                converted: None,
+            },
+            Payment {
+                method: PaymentMethod::Returns,
+                amount: rub(10_000),
+                converted: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn amounts_are_in_the_entry_currency() {
+    let (payments, revenue) = fields("", "12.5", "", "").parse(Currency::USD).unwrap();
+
+    assert_eq!(revenue, Moneys::new(1250, Currency::USD));
+    assert_eq!(payments[0].amount.currency, Currency::USD);
+}
+
+#[test]
+fn empty_fields_are_skipped_but_one_is_needed() {
+    let (payments, revenue) = fields("  ", "0", "", "").parse(Currency::RUB).unwrap();
+    assert_eq!(revenue, rub(0));
+    assert_eq!(payments.len(), 1);
+
+    let err = fields("", " ", "", "").parse(Currency::RUB).unwrap_err();
+    assert_eq!(err, AppError::validation("revenue", "err-breakdown-empty"));
+}
+
+#[test]
+fn invalid_amounts_point_at_their_field() {
+    let err = fields("100", "1,234.567", "", "")
+        .parse(Currency::RUB)
+        .unwrap_err();
+    assert_eq!(err.field(), Some("card"));
+    assert!(err.message().starts_with("err-amount-fraction"));
+
+    let err = fields("100", "", "ten", "")
+        .parse(Currency::RUB)
+        .unwrap_err();
+    assert_eq!(err.field(), Some("online"));
+    assert!(err.message().starts_with("err-amount-character"));
+
+    let err = fields("100", "", "", "-5")
+        .parse(Currency::RUB)
+        .unwrap_err();
+    assert_eq!(err, AppError::validation("returns", "err-amount-negative"));
+}
+
+#[test]
+fn returns_may_exceed_sales() {
+    let (_, revenue) = fields("100", "", "", "150").parse(Currency::RUB).unwrap();
+
+    assert_eq!(revenue, rub(-5_000));
+}
+
+#[test]
+fn overflowing_totals_are_rejected() {
+    let max = "92233720368547758.07";
+
+    let err = fields(max, "0,01", "", "")
+        .parse(Currency::RUB)
+        .unwrap_err();
+    assert_eq!(err, AppError::validation("card", "err-amount-overflow"));
+
+    let (_, revenue) = fields(max, "", "", "0,01").parse(Currency::RUB).unwrap();
+    assert_eq!(revenue, rub(i64::MAX - 1));
+}
+
+#[test]
+fn breakdowns_mix_fields_in_any_order() {
+    let (payments, revenue) = Payment::parse_breakdown(
+        &[
+            (PaymentMethod::Returns, "5"),
+            (PaymentMethod::Online, "20"),
+            (PaymentMethod::Cash, ""),
+        ],
+        Currency::RUB,
+    )
+    .unwrap();
+
+    assert_eq!(revenue, rub(1_500));
+    assert_eq!(
+        payments.iter().map(|p| p.method).collect::<Vec<_>>(),
+        vec![PaymentMethod::Returns, PaymentMethod::Online]
+    );
+}
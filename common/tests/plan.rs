@@ -0,0 +1,179 @@
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use common::{
+    models::{EntryWithUser, Plan, PlanScope},
+    moneys::{Currency, Moneys},
+    tz,
+    user::User,
+};
+use uuid::Uuid;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn manager(id: u128) -> User {
+    User {
+        id: Uuid::from_u128(id),
+        ..Default::default()
+    }
+}
+
+fn entry(user: &User, address: &str, date: NaiveDate, revenue: Moneys) -> EntryWithUser {
+    EntryWithUser {
+        id: Uuid::new_v4(),
+        address: address.to_string(),
+        revenue,
+        converted: None,
+        payments: vec![],
+        date,
+        shift: None,
+        shift_label: None,
+        user: user.clone(),
+        anomaly: None,
+        timezone: tz::DEFAULT,
+        comments: 0,
+        unread_comments: 0,
+    }
+}
+
+fn rub(amount: i64) -> Moneys {
+    Moneys::new(amount, Currency::RUB)
+}
+
+/// April 2024, 30 days, targeting 300 000 ₽.
+fn plan(scope: PlanScope) -> Plan {
+    Plan {
+        id: Uuid::from_u128(100),
+        year: 2024,
+        month: 4,
+        scope,
+        target: rub(30_000_000),
+        timezone: tz::DEFAULT,
+    }
+}
+
+fn location() -> PlanScope {
+    PlanScope::Location {
+        id: Uuid::from_u128(200),
+        address: "Lenina 1".to_string(),
+    }
+}
+
+/// Noon in Moscow.
+fn noon(date: NaiveDate) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&date.and_hms_opt(9, 0, 0).unwrap())
+}
+
+#[test]
+fn actual_remaining_and_percent() {
+    let ivanov = manager(1);
+    let entries = [
+        entry(&ivanov, "Lenina 1", date(2024, 4, 1), rub(6_000_000)),
+        entry(&ivanov, "Lenina 1", date(2024, 4, 2), rub(3_000_000)),
+        // other months and managers are left out
+        entry(&ivanov, "Lenina 1", date(2024, 3, 31), rub(1_000_000)),
+        entry(&manager(2), "Mira 2", date(2024, 4, 1), rub(1_000_000)),
+    ];
+
+    let progress = plan(PlanScope::Manager(ivanov.id)).progress(&entries, noon(date(2024, 4, 3)));
+
+    assert_eq!(progress.actual, rub(9_000_000));
+    assert_eq!(progress.remaining, rub(21_000_000));
+    assert_eq!(progress.percent, 30.0);
+    assert_eq!(progress.unconverted, 0);
+
+    let exceeded = [entry(
+        &ivanov,
+        "Lenina 1",
+        date(2024, 4, 1),
+        rub(40_000_000),
+    )];
+    let progress = plan(PlanScope::Manager(ivanov.id)).progress(&exceeded, noon(date(2024, 4, 3)));
+    assert_eq!(progress.remaining, rub(0));
+}
+
+#[test]
+fn locations_count_every_managers_entries() {
+    let entries = [
+        entry(&manager(1), "Lenina 1", date(2024, 4, 1), rub(1_000_000)),
+        entry(&manager(2), "Lenina 1", date(2024, 4, 1), rub(2_000_000)),
+        entry(&manager(2), "Mira 2", date(2024, 4, 1), rub(4_000_000)),
+    ];
+
+    let progress = plan(location()).progress(&entries, noon(date(2024, 4, 1)));
+
+    assert_eq!(progress.actual, rub(3_000_000));
+}
+
+#[test]
+fn projections_run_to_today_not_the_latest_entry() {
+    let ivanov = manager(1);
+    let entries = [entry(&ivanov, "Lenina 1", date(2024, 4, 1), rub(1_000_000))];
+    let plan = plan(PlanScope::Manager(ivanov.id));
+
+    // a single late report doesn't stand for the whole month
+    let progress = plan.progress(&entries, noon(date(2024, 4, 10)));
+    assert_eq!(progress.projection, Some(rub(3_000_000)));
+
+    let progress = plan.progress(&entries, noon(date(2024, 4, 1)));
+    assert_eq!(progress.projection, Some(rub(30_000_000)));
+
+    // past months are projected as they ended
+    let progress = plan.progress(&entries, noon(date(2024, 6, 1)));
+    assert_eq!(progress.projection, Some(rub(1_000_000)));
+
+    assert_eq!(plan.progress(&[], noon(date(2024, 4, 10))).projection, None);
+}
+
+#[test]
+fn days_pass_in_the_plans_zone() {
+    let ivanov = manager(1);
+    let entries = [entry(&ivanov, "Lenina 1", date(2024, 4, 1), rub(1_000_000))];
+    // 20:00 on the 1st in Moscow is already the 2nd in Vladivostok
+    let now = Utc.with_ymd_and_hms(2024, 4, 1, 17, 0, 0).unwrap();
+
+    let moscow = plan(PlanScope::Manager(ivanov.id));
+    assert_eq!(
+        moscow.progress(&entries, now).projection,
+        Some(rub(30_000_000))
+    );
+
+    let vladivostok = Plan {
+        timezone: tz::Tz::Asia__Vladivostok,
+        ..moscow
+    };
+    assert_eq!(
+        vladivostok.progress(&entries, now).projection,
+        Some(rub(15_000_000))
+    );
+
+    // nothing to project before the month starts there
+    let before = Utc.with_ymd_and_hms(2024, 3, 31, 12, 0, 0).unwrap();
+    assert_eq!(vladivostok.progress(&entries, before).projection, None);
+}
+
+#[test]
+fn unconverted_entries_are_counted_apart() {
+    let ivanov = manager(1);
+    let mut converted = entry(
+        &ivanov,
+        "Lenina 1",
+        date(2024, 4, 1),
+        Moneys::new(10_000, Currency::USD),
+    );
+    converted.converted = Some(rub(900_000));
+    let entries = [
+        converted,
+        entry(
+            &ivanov,
+            "Lenina 1",
+            date(2024, 4, 2),
+            Moneys::new(10_000, Currency::EUR),
+        ),
+    ];
+
+    let progress = plan(PlanScope::Manager(ivanov.id)).progress(&entries, noon(date(2024, 4, 3)));
+
+    assert_eq!(progress.actual, rub(900_000));
+    assert_eq!(progress.unconverted, 1);
+}
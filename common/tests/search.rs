@@ -0,0 +1,73 @@
+use chrono::NaiveDate;
+use common::{
+    search::{self, SearchHit, SearchKind, SearchResults},
+    IdType,
+};
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn hit(kind: SearchKind, id: u128) -> SearchHit {
+    SearchHit {
+        kind,
+        id: IdType::from_u128(id),
+        title: String::new(),
+        detail: None,
+        date: None,
+        reports: None,
+    }
+}
+
+#[test]
+fn dates() {
+    assert_eq!(search::parse_date("12.03.2024"), Some(date(2024, 3, 12)));
+    assert_eq!(search::parse_date(" 12/03/2024 "), Some(date(2024, 3, 12)));
+    assert_eq!(search::parse_date("2024-03-12"), Some(date(2024, 3, 12)));
+    assert_eq!(search::parse_date("12.03.24"), Some(date(2024, 3, 12)));
+    assert_eq!(search::parse_date("1.3.2024"), Some(date(2024, 3, 1)));
+
+    assert_eq!(search::parse_date("31.02.2024"), None);
+    assert_eq!(search::parse_date("12.03"), None);
+    assert_eq!(search::parse_date("ул. Мира, 12"), None);
+}
+
+#[test]
+fn like_patterns() {
+    assert_eq!(search::like_pattern("Мира"), "%Мира%");
+    assert_eq!(search::like_pattern("user_1"), "%user\\_1%");
+    assert_eq!(search::like_pattern("100%"), "%100\\%%");
+    assert_eq!(search::like_pattern("a\\b"), "%a\\\\b%");
+}
+
+#[test]
+fn groups_in_display_order() {
+    let results = SearchResults {
+        managers: vec![hit(SearchKind::Manager, 1)],
+        addresses: vec![hit(SearchKind::Address, 2), hit(SearchKind::Address, 3)],
+        dates: vec![],
+    };
+
+    let kinds = results
+        .groups()
+        .into_iter()
+        .map(|(kind, hits)| (kind, hits.len()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![(SearchKind::Manager, 1), (SearchKind::Address, 2)]
+    );
+
+    let ids = results.hits().into_iter().map(|h| h.id).collect::<Vec<_>>();
+    assert_eq!(ids, (1..=3).map(IdType::from_u128).collect::<Vec<_>>());
+
+    assert_eq!(
+        results.managers[0].href(),
+        format!("/reports?user_id={}", IdType::from_u128(1))
+    );
+    assert_eq!(
+        results.addresses[0].href(),
+        format!("/reports/{}", IdType::from_u128(2))
+    );
+    assert!(SearchResults::default().is_empty());
+}
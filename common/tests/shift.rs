@@ -0,0 +1,105 @@
+use chrono::NaiveTime;
+use common::{
+    error::AppError,
+    models::{Shift, ShiftFields},
+};
+
+fn shift(start: &str, end: &str) -> Shift {
+    Shift {
+        start: NaiveTime::parse_from_str(start, "%H:%M").unwrap(),
+        end: NaiveTime::parse_from_str(end, "%H:%M").unwrap(),
+    }
+}
+
+fn fields(start: &str, end: &str, label: &str) -> ShiftFields {
+    ShiftFields {
+        start: start.into(),
+        end: end.into(),
+        label: label.into(),
+    }
+}
+
+fn field_of(err: AppError) -> Option<String> {
+    match err {
+        AppError::Validation { field, .. } => Some(field),
+        _ => None,
+    }
+}
+
+#[test]
+fn adjacent_shifts_do_not_overlap() {
+    let morning = shift("08:00", "14:00");
+    let evening = shift("14:00", "22:00");
+
+    assert!(!morning.overlaps(&evening));
+    assert!(!evening.overlaps(&morning));
+    assert!(morning.overlaps(&shift("13:30", "15:00")));
+    assert!(morning.overlaps(&shift("09:00", "10:00")));
+}
+
+#[test]
+fn overnight_shifts() {
+    let night = shift("22:00", "06:00");
+
+    assert!(night.overlaps(&shift("23:00", "23:30")));
+    assert!(night.overlaps(&shift("20:00", "22:30")));
+    assert!(!night.overlaps(&shift("06:00", "22:00")));
+    // the early hours of the next day belong to the next date's entries
+    assert!(!night.overlaps(&shift("02:00", "05:00")));
+
+    assert_eq!(night.to_string(), "22:00–06:00");
+}
+
+#[test]
+fn whole_day_entries() {
+    let morning = Some(shift("08:00", "14:00"));
+
+    // a manager's whole day entry leaves no room for their shifts
+    assert!(Shift::clashes(None, morning, true));
+    assert!(Shift::clashes(None, None, true));
+    assert!(Shift::clashes(morning, morning, true));
+    assert!(!Shift::clashes(
+        morning,
+        Some(shift("14:00", "20:00")),
+        true
+    ));
+
+    // other managers only clash on overlapping hours
+    assert!(!Shift::clashes(None, morning, false));
+    assert!(!Shift::clashes(None, None, false));
+    assert!(Shift::clashes(
+        morning,
+        Some(shift("12:00", "16:00")),
+        false
+    ));
+}
+
+#[test]
+fn form_fields() {
+    assert_eq!(fields("", "", "").parse(), Ok((None, None)));
+    assert_eq!(
+        fields("08:00", "14:00", " Morning ").parse(),
+        Ok((Some(shift("08:00", "14:00")), Some("Morning".into())))
+    );
+    assert_eq!(
+        fields("22:00:00", "06:00", "").parse(),
+        Ok((Some(shift("22:00", "06:00")), None))
+    );
+    assert_eq!(
+        fields("", "", "Evening").parse(),
+        Ok((None, Some("Evening".into())))
+    );
+
+    for invalid in [
+        fields("08:00", "", ""),
+        fields("8 am", "14:00", ""),
+        fields("08:00", "08:00", ""),
+    ] {
+        assert_eq!(invalid.parse().map_err(field_of), Err(Some("shift".into())));
+    }
+
+    assert_eq!(
+        fields("", "", &"x".repeat(51)).parse().map_err(field_of),
+        Err(Some("shift_label".into()))
+    );
+}
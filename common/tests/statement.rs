@@ -0,0 +1,262 @@
+use chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
+use common::{
+    error::AppError,
+    i18n::Locale,
+    models::{EntryWithUser, Shift},
+    moneys::{Currency, Moneys},
+    statement::{Statement, StatementQuery},
+    tz,
+    user::User,
+};
+use uuid::Uuid;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn manager(id: u128, family_name: &str) -> User {
+    User {
+        id: Uuid::from_u128(id),
+        name: "Ivan".to_string(),
+        family_name: family_name.to_string(),
+        patronym: Some("Petrovich".to_string()),
+        username: family_name.to_lowercase(),
+        ..Default::default()
+    }
+}
+
+fn entry(user: &User, date: NaiveDate, revenue: i64) -> EntryWithUser {
+    EntryWithUser {
+        id: Uuid::new_v4(),
+        address: "Lenina 1".to_string(),
+        revenue: Moneys::new(revenue, Currency::RUB),
+        converted: None,
+        payments: vec![],
+        date,
+        shift: None,
+        shift_label: None,
+        user: user.clone(),
+        anomaly: None,
+        timezone: tz::DEFAULT,
+        comments: 0,
+        unread_comments: 0,
+    }
+}
+
+fn query(from: NaiveDate, to: NaiveDate) -> StatementQuery {
+    StatementQuery {
+        from,
+        to,
+        user_id: None,
+    }
+}
+
+#[test]
+fn an_empty_manager_means_the_whole_company() {
+    let query: StatementQuery =
+        serde_json::from_str(r#"{"from":"2024-03-01","to":"2024-03-31","user_id":""}"#).unwrap();
+    assert_eq!(query.user_id, None);
+
+    let query: StatementQuery =
+        serde_json::from_str(r#"{"from":"2024-03-01","to":"2024-03-31"}"#).unwrap();
+    assert_eq!(query.user_id, None);
+
+    let id = Uuid::from_u128(7);
+    let query: StatementQuery = serde_json::from_str(&format!(
+        r#"{{"from":"2024-03-01","to":"2024-03-31","user_id":"{id}"}}"#
+    ))
+    .unwrap();
+    assert_eq!(query.user_id, Some(id));
+}
+
+#[test]
+fn periods_run_forward_for_at_most_a_year() {
+    assert!(query(date(2024, 3, 1), date(2024, 3, 1)).validate().is_ok());
+    assert!(query(date(2024, 1, 1), date(2024, 12, 31))
+        .validate()
+        .is_ok());
+
+    let err = query(date(2024, 3, 2), date(2024, 3, 1))
+        .validate()
+        .unwrap_err();
+    assert_eq!(err.field(), Some("to"));
+
+    assert!(query(date(2024, 1, 1), date(2025, 1, 1))
+        .validate()
+        .is_err());
+}
+
+#[test]
+fn managers_only_get_their_own_statements() {
+    let own = Uuid::from_u128(1);
+    let other = Uuid::from_u128(2);
+    let company = query(date(2024, 3, 1), date(2024, 3, 31));
+    let others = StatementQuery {
+        user_id: Some(other),
+        ..company.clone()
+    };
+
+    // `ViewAll`
+    assert_eq!(company.owner(Some(None)), Ok(None));
+    assert_eq!(others.owner(Some(None)), Ok(Some(other)));
+
+    // `ViewOwned`
+    assert_eq!(company.owner(Some(Some(own))), Ok(Some(own)));
+    assert_eq!(
+        others.owner(Some(Some(own))),
+        Err(AppError::Forbidden(
+            "err-forbidden-view-others-reports".to_string()
+        ))
+    );
+
+    assert!(matches!(company.owner(None), Err(AppError::Forbidden(_))));
+}
+
+#[test]
+fn entries_are_listed_by_date_and_shift() {
+    let ivanov = manager(1, "Ivanov");
+    let petrov = manager(2, "Petrov");
+    let mut evening = entry(&ivanov, date(2024, 3, 1), 20_000);
+    evening.shift = Some(Shift {
+        start: NaiveTime::from_hms_opt(16, 0, 0).unwrap(),
+        end: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+    });
+    evening.shift_label = Some("Evening".to_string());
+    let mut morning = evening.clone();
+    morning.shift = Some(Shift {
+        start: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+        end: NaiveTime::from_hms_opt(16, 0, 0).unwrap(),
+    });
+    morning.shift_label = None;
+
+    let statement = Statement::new(
+        date(2024, 3, 1),
+        date(2024, 3, 31),
+        None,
+        vec![entry(&petrov, date(2024, 3, 2), 10_000), evening, morning],
+        Utc::now(),
+        None,
+    );
+
+    let rows = statement.rows();
+    assert_eq!(
+        rows.iter().map(|r| r.shift.as_str()).collect::<Vec<_>>(),
+        vec!["08:00–16:00", "Evening, 16:00–23:00", ""]
+    );
+    assert_eq!(rows[0].date, "01.03.2024");
+    assert_eq!(rows[0].manager, "Ivanov I. P.");
+    assert_eq!(rows[2].manager, "Petrov I. P.");
+}
+
+#[test]
+fn converted_revenues_are_noted_in_other_currencies_only() {
+    let ivanov = manager(1, "Ivanov");
+    let mut usd = entry(&ivanov, date(2024, 3, 1), 10_000);
+    usd.revenue = Moneys::new(10_000, Currency::USD);
+    usd.converted = Some(Moneys::new(900_000, Currency::RUB));
+    let mut rub = entry(&ivanov, date(2024, 3, 2), 10_000);
+    rub.converted = rub.converted.or(Some(rub.revenue));
+
+    let statement = Statement::new(
+        date(2024, 3, 1),
+        date(2024, 3, 31),
+        Some(ivanov),
+        vec![usd, rub],
+        Utc::now(),
+        None,
+    );
+
+    let rows = statement.rows();
+    assert_eq!(
+        rows[0].converted,
+        Some(format!("≈ {}", Moneys::new(900_000, Currency::RUB)))
+    );
+    assert_eq!(rows[1].converted, None);
+    assert_eq!(
+        statement.totals().revenue.get(Currency::RUB),
+        Some(Moneys::new(910_000, Currency::RUB))
+    );
+}
+
+#[test]
+fn subtotals_are_per_manager() {
+    let ivanov = manager(1, "Ivanov");
+    let petrov = manager(2, "Petrov");
+
+    let statement = Statement::new(
+        date(2024, 3, 1),
+        date(2024, 3, 31),
+        None,
+        vec![
+            entry(&petrov, date(2024, 3, 1), 10_000),
+            entry(&ivanov, date(2024, 3, 1), 20_000),
+            entry(&ivanov, date(2024, 3, 2), 5_000),
+        ],
+        Utc::now(),
+        None,
+    );
+
+    let subtotals = statement
+        .subtotals()
+        .into_iter()
+        .map(|(user, totals)| (user.family_name, totals.revenue.get(Currency::RUB)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        subtotals,
+        vec![
+            (
+                "Ivanov".to_string(),
+                Some(Moneys::new(25_000, Currency::RUB))
+            ),
+            (
+                "Petrov".to_string(),
+                Some(Moneys::new(10_000, Currency::RUB))
+            ),
+        ]
+    );
+}
+
+#[test]
+fn statements_name_their_period_manager_and_author() {
+    let ivanov = manager(1, "Ivanov");
+    let admin = User {
+        timezone: Some(tz::Tz::Asia__Vladivostok),
+        ..manager(3, "Sidorov")
+    };
+    let generated_at = Utc.with_ymd_and_hms(2024, 4, 1, 6, 30, 0).unwrap();
+
+    let statement = Statement::new(
+        date(2024, 3, 1),
+        date(2024, 3, 31),
+        Some(ivanov),
+        vec![],
+        generated_at,
+        Some(admin),
+    );
+
+    assert_eq!(
+        statement.title(Locale::En),
+        "Revenue statement for 01.03.2024 – 31.03.2024"
+    );
+    assert_eq!(statement.scope(Locale::En), "Manager: Ivanov I. P.");
+    assert_eq!(
+        statement.file_name(),
+        "statement-2024-03-01-2024-03-31-ivanov.pdf"
+    );
+    assert_eq!(
+        statement.generated(Locale::En),
+        "Generated 01.04.2024 16:30 +10 by Sidorov I. P."
+    );
+
+    let company = Statement {
+        manager: None,
+        generated_by: None,
+        ..statement
+    };
+    assert_eq!(company.scope(Locale::En), "All managers");
+    assert_eq!(company.file_name(), "statement-2024-03-01-2024-03-31.pdf");
+    assert_eq!(
+        company.generated(Locale::En),
+        "Generated 01.04.2024 09:30 MSK from the command line"
+    );
+}
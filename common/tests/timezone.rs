@@ -0,0 +1,89 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use common::tz::{self, Tz};
+
+fn utc(s: &str) -> DateTime<Utc> {
+    s.parse().unwrap()
+}
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn today_follows_the_zone() {
+    // 21:30 UTC on the last day of January is already February in Moscow
+    let now = utc("2024-01-31T21:30:00Z");
+
+    assert_eq!(tz::date_at(Tz::UTC, now), date(2024, 1, 31));
+    assert_eq!(tz::date_at(Tz::Europe__Moscow, now), date(2024, 2, 1));
+
+    // and early morning in Vladivostok is still yesterday in UTC and Moscow
+    let now = utc("2024-03-31T14:30:00Z");
+
+    assert_eq!(tz::date_at(Tz::Europe__Moscow, now), date(2024, 3, 31));
+    assert_eq!(tz::date_at(Tz::Asia__Vladivostok, now), date(2024, 4, 1));
+}
+
+#[test]
+fn window_starts_with_the_local_month() {
+    let now = utc("2024-01-31T21:30:00Z");
+
+    let moscow = tz::date_at(Tz::Europe__Moscow, now);
+    assert_eq!(
+        tz::report_window(moscow),
+        (date(2024, 2, 1), date(2024, 2, 1))
+    );
+    assert!(!tz::in_report_window(date(2024, 1, 31), moscow));
+    assert!(tz::in_report_window(date(2024, 2, 1), moscow));
+
+    let utc_today = tz::date_at(Tz::UTC, now);
+    assert!(tz::in_report_window(date(2024, 1, 31), utc_today));
+    assert!(!tz::in_report_window(date(2024, 2, 1), utc_today));
+}
+
+#[test]
+fn month_and_year_ends() {
+    // leap day
+    let today = tz::date_at(Tz::Europe__Moscow, utc("2024-02-28T22:00:00Z"));
+    assert_eq!(today, date(2024, 2, 29));
+    assert_eq!(
+        tz::report_window(today),
+        (date(2024, 2, 1), date(2024, 2, 29))
+    );
+
+    // new year comes to Vladivostok ten hours before UTC
+    let today = tz::date_at(Tz::Asia__Vladivostok, utc("2023-12-31T14:00:00Z"));
+    assert_eq!(today, date(2024, 1, 1));
+    assert!(!tz::in_report_window(date(2023, 12, 31), today));
+
+    // a second before midnight the whole of December is still open in UTC
+    let today = tz::date_at(Tz::UTC, utc("2023-12-31T23:59:59Z"));
+    assert!(tz::in_report_window(date(2023, 12, 1), today));
+    assert!(!tz::in_report_window(date(2023, 11, 30), today));
+    assert!(!tz::in_report_window(date(2024, 1, 1), today));
+}
+
+#[test]
+fn location_zone_comes_first() {
+    let vladivostok = Some(Tz::Asia__Vladivostok);
+    let samara = Some(Tz::Europe__Samara);
+
+    assert_eq!(tz::resolve(vladivostok, samara), Tz::Asia__Vladivostok);
+    assert_eq!(tz::resolve(None, samara), Tz::Europe__Samara);
+    assert_eq!(tz::resolve(None, None), tz::DEFAULT);
+}
+
+#[test]
+fn zone_choice() {
+    assert_eq!(tz::parse_choice(None), Ok(None));
+    assert_eq!(tz::parse_choice(Some(" ")), Ok(None));
+    assert_eq!(
+        tz::parse_choice(Some("Asia/Vladivostok")),
+        Ok(Some(Tz::Asia__Vladivostok))
+    );
+    assert!(tz::parse_choice(Some("Mars/Olympus")).is_err());
+
+    assert_eq!(tz::from_stored(Some("Asia/Omsk")), Tz::Asia__Omsk);
+    assert_eq!(tz::from_stored(Some("Mars/Olympus")), tz::DEFAULT);
+    assert_eq!(tz::from_stored(None), tz::DEFAULT);
+}
@@ -0,0 +1,26 @@
+use common::user::{User, UserSort};
+
+#[test]
+fn sort_in_url() {
+    for sort in UserSort::ALL {
+        assert_eq!(sort.code().parse(), Ok(sort));
+    }
+
+    assert_eq!("".parse::<UserSort>(), Err(()));
+    assert_eq!("password".parse::<UserSort>(), Err(()));
+    assert_eq!(UserSort::default(), UserSort::FamilyName);
+}
+
+#[test]
+fn users_are_active_unless_deactivated() {
+    assert!(User::default().active);
+
+    let mut payload = serde_json::to_value(User::default()).unwrap();
+    payload.as_object_mut().unwrap().remove("active");
+    let user: User = serde_json::from_value(payload.clone()).unwrap();
+    assert!(user.active);
+
+    payload["active"] = false.into();
+    let user: User = serde_json::from_value(payload).unwrap();
+    assert!(!user.active);
+}
@@ -1,6 +1,6 @@
 use app::*;
 use leptos::*;
-use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 
 #[wasm_bindgen]
 pub fn hydrate() {
@@ -8,5 +8,17 @@ pub fn hydrate() {
     _ = console_log::init_with_level(log::Level::Trace);
     console_error_panic_hook::set_once();
 
+    register_service_worker();
+
     leptos::mount_to_body(App);
 }
+
+/// Caches the app shell, see `public/sw.js`, so the report form opens without a
+/// connection. Browsers only have service workers on https and localhost.
+fn register_service_worker() {
+    let navigator = window().navigator();
+
+    if js_sys::Reflect::has(&navigator, &JsValue::from("serviceWorker")).unwrap_or(false) {
+        _ = navigator.service_worker().register("/sw.js");
+    }
+}
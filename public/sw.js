@@ -0,0 +1,86 @@
+// Keeps the app shell available offline: the shell pages and the compiled assets are
+// fetched from the network first and served from the cache without one. Asset names
+// aren't hashed, so a cached copy is never preferred over the deployed one.
+// Other pages and server functions under /api are never cached, reports filed
+// offline are queued by the app in IndexedDB instead.
+
+const CACHE = "app-shell-v1";
+const SHELL = [
+  "/",
+  "/reports/new-report",
+  "/pkg/start-axum-workspace.js",
+  "/pkg/start-axum-workspace.wasm",
+  "/pkg/start-axum-workspace.css",
+  "/favicon.ico",
+];
+
+self.addEventListener("install", (event) => {
+  event.waitUntil(
+    caches
+      .open(CACHE)
+      .then((cache) => cache.addAll(SHELL))
+      .then(() => self.skipWaiting()),
+  );
+});
+
+self.addEventListener("activate", (event) => {
+  event.waitUntil(
+    caches
+      .keys()
+      .then((keys) =>
+        Promise.all(keys.filter((key) => key !== CACHE).map((key) => caches.delete(key))),
+      )
+      .then(() => self.clients.claim()),
+  );
+});
+
+self.addEventListener("fetch", (event) => {
+  const request = event.request;
+  const url = new URL(request.url);
+
+  if (url.origin !== self.location.origin) {
+    return;
+  }
+
+  if (url.pathname.startsWith("/api/")) {
+    // the shell pages are rendered for the signed in user, the next one on a shared
+    // device must not get them offline
+    if (/^\/api\/log(in|out)/.test(url.pathname)) {
+      event.waitUntil(caches.delete(CACHE));
+    }
+    return;
+  }
+
+  if (request.method !== "GET") {
+    return;
+  }
+
+  if (request.mode === "navigate" || SHELL.includes(url.pathname)) {
+    event.respondWith(networkFirst(request, SHELL.includes(url.pathname)));
+  } else if (url.pathname.startsWith("/pkg/")) {
+    event.respondWith(networkFirst(request, true));
+  }
+});
+
+async function networkFirst(request, keep) {
+  const cache = await caches.open(CACHE);
+
+  try {
+    const response = await fetch(request);
+    // a signed out visitor is redirected to the login page, which isn't what the url shows
+    if (keep && response.ok && !response.redirected) {
+      cache.put(request, response.clone());
+    }
+    return response;
+  } catch (err) {
+    const cached =
+      (await cache.match(request, { ignoreSearch: true })) ||
+      // any page hydrates into the route of its url
+      (request.mode === "navigate" &&
+        ((await cache.match("/reports/new-report")) || (await cache.match("/"))));
+    if (cached) {
+      return cached;
+    }
+    throw err;
+  }
+}
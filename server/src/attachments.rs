@@ -0,0 +1,305 @@
+use std::sync::Arc;
+
+use std::fmt::Display;
+
+use axum::{
+    extract::{multipart::MultipartError, Multipart, Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+};
+use chrono::Utc;
+use common::{
+    ctx::AppAuthSession,
+    error::AppError,
+    i18n::{ssr::request_locale, Locale, Message},
+    models::{attachment::ssr::StoredAttachment, Attachment, Entry},
+    user::User,
+    IdType,
+};
+use sqlx::PgPool;
+
+use crate::storage::BlobStorage;
+
+/// Attachment storage and limits, part of the app state.
+#[derive(Clone)]
+pub struct Attachments {
+    pub storage: Arc<dyn BlobStorage>,
+    pub max_size: usize,
+}
+
+/// Form upload of files to an entry, redirects back to the entry with the outcome.
+pub async fn upload(
+    State(pool): State<PgPool>,
+    State(attachments): State<Attachments>,
+    auth: AppAuthSession,
+    headers: HeaderMap,
+    Path(entry_id): Path<IdType>,
+    multipart: Multipart,
+) -> Redirect {
+    let Some(user) = auth.current_user.as_ref() else {
+        return Redirect::to("/login");
+    };
+    let locale = request_locale(Some(user), &headers);
+
+    match store_upload(&pool, &attachments, user, locale, entry_id, multipart).await {
+        Ok(count) => report_page(entry_id, &[("attached", count.to_string().as_str())]),
+        Err(err) => {
+            log::warn!(
+                "attachment upload to {entry_id} rejected: {}",
+                err.message()
+            );
+            report_page(entry_id, &[("attachment_error", err.message())])
+        }
+    }
+}
+
+async fn store_upload(
+    pool: &PgPool,
+    attachments: &Attachments,
+    user: &User,
+    locale: Locale,
+    entry_id: IdType,
+    mut multipart: Multipart,
+) -> Result<usize, AppError> {
+    if !Entry::is_editable_by(pool, entry_id, user)
+        .await
+        .map_err(internal)?
+    {
+        return Err(AppError::Forbidden("err-report-not-editable".to_string()));
+    }
+
+    let too_large = || {
+        AppError::validation(
+            "file",
+            Message::new("err-file-too-large").arg("max", attachments.max_size / 1024 / 1024),
+        )
+    };
+    // the body limit cuts off a request with a larger file before it's read to the end
+    let read_error = |err: MultipartError| match err.status() {
+        StatusCode::PAYLOAD_TOO_LARGE => too_large(),
+        _ => internal(err),
+    };
+
+    let mut files = vec![];
+
+    while let Some(mut field) = multipart.next_field().await.map_err(read_error)? {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let file_name = file_name(field.file_name().unwrap_or_default(), locale);
+        let mut bytes = vec![];
+
+        while let Some(chunk) = field.chunk().await.map_err(read_error)? {
+            bytes.extend_from_slice(&chunk);
+
+            if bytes.len() > attachments.max_size {
+                return Err(too_large());
+            }
+        }
+
+        // browsers send an empty part when no file is chosen
+        if bytes.is_empty() {
+            continue;
+        }
+
+        let content_type = Attachment::sniff(&bytes).ok_or_else(|| {
+            AppError::validation(
+                "file",
+                Message::new("err-file-type").arg("file", file_name.as_str()),
+            )
+        })?;
+
+        files.push((file_name, content_type, bytes));
+    }
+
+    if files.is_empty() {
+        return Err(AppError::validation("file", "err-file-missing"));
+    }
+
+    let count = files.len();
+
+    for (file_name, content_type, bytes) in files {
+        let attachment = Attachment {
+            id: uuid::Uuid::new_v4(),
+            entry_id,
+            file_name,
+            content_type: content_type.to_string(),
+            size: bytes.len() as i64,
+            uploaded_at: Utc::now(),
+        };
+        let key = format!("{entry_id}/{}", attachment.id);
+
+        attachments
+            .storage
+            .put(&key, bytes)
+            .await
+            .map_err(internal)?;
+
+        if let Err(err) = Attachment::insert(pool, &attachment, &key, user.id).await {
+            _ = attachments.storage.delete(&key).await;
+            return Err(internal(err));
+        }
+    }
+
+    Ok(count)
+}
+
+/// Content of an attachment, to users who can view its entry.
+pub async fn download(
+    State(pool): State<PgPool>,
+    State(attachments): State<Attachments>,
+    auth: AppAuthSession,
+    Path(id): Path<IdType>,
+) -> Response {
+    let Some(user) = auth.current_user.as_ref() else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+
+    let stored = match visible_attachment(&pool, user, id).await {
+        Ok(Some(stored)) => stored,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(err) => {
+            log::error!("cannot load attachment {id}: {err:#}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    match attachments.storage.get(&stored.storage_key).await {
+        Ok(bytes) => (
+            [
+                (header::CONTENT_TYPE, stored.attachment.content_type),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!(
+                        "inline; filename*=UTF-8''{}",
+                        percent_encode(&stored.attachment.file_name)
+                    ),
+                ),
+                (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+                (header::CACHE_CONTROL, "private, max-age=3600".to_string()),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(err) => {
+            log::error!("cannot read attachment {id}: {err:#}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Form removal of an attachment, redirects back to its entry.
+pub async fn delete(
+    State(pool): State<PgPool>,
+    State(attachments): State<Attachments>,
+    auth: AppAuthSession,
+    Path(id): Path<IdType>,
+) -> Response {
+    let Some(user) = auth.current_user.as_ref() else {
+        return Redirect::to("/login").into_response();
+    };
+
+    let stored = match visible_attachment(&pool, user, id).await {
+        Ok(Some(stored)) => stored,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(err) => {
+            log::error!("cannot load attachment {id}: {err:#}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let entry_id = stored.attachment.entry_id;
+
+    let result = async {
+        if !Entry::is_editable_by(&pool, entry_id, user)
+            .await
+            .map_err(internal)?
+        {
+            return Err(AppError::Forbidden("err-report-not-editable".to_string()));
+        }
+
+        Attachment::delete(&pool, id).await.map_err(internal)?;
+
+        // the row is gone, a leftover file is only wasted space
+        if let Err(err) = attachments.storage.delete(&stored.storage_key).await {
+            log::warn!(
+                "cannot delete attachment file {}: {err:#}",
+                stored.storage_key
+            );
+        }
+
+        Ok(())
+    }
+    .await;
+
+    match result {
+        Ok(()) => report_page(entry_id, &[]).into_response(),
+        Err(err) => report_page(entry_id, &[("attachment_error", err.message())]).into_response(),
+    }
+}
+
+/// Failure the user can't fix, the details only go to the log.
+fn internal(err: impl Display) -> AppError {
+    log::error!("attachment request failed: {err}");
+    AppError::Internal
+}
+
+/// The attachment if the user can view its entry, per `get_report` rules.
+async fn visible_attachment(
+    pool: &PgPool,
+    user: &User,
+    id: IdType,
+) -> anyhow::Result<Option<StoredAttachment>> {
+    let Some(owner_filter) = Entry::owner_filter(user, pool).await else {
+        return Ok(None);
+    };
+    let Some(stored) = Attachment::get(pool, id).await? else {
+        return Ok(None);
+    };
+
+    let visible = Entry::get_visible(pool, stored.attachment.entry_id, owner_filter)
+        .await?
+        .is_some();
+
+    Ok(visible.then_some(stored))
+}
+
+/// Base name of an uploaded file without control characters, as browsers may send
+/// full paths.
+fn file_name(name: &str, locale: Locale) -> String {
+    let name = name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(200)
+        .collect::<String>();
+
+    match name.trim() {
+        "" => locale.tr("attachments-unnamed"),
+        name => name.to_string(),
+    }
+}
+
+/// RFC 5987 encoding of a header parameter value.
+pub(crate) fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            b => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn report_page(entry_id: IdType, query: &[(&str, &str)]) -> Redirect {
+    let query = serde_urlencoded::to_string(query).unwrap_or_default();
+
+    match query.as_str() {
+        "" => Redirect::to(&format!("/reports/{entry_id}")),
+        query => Redirect::to(&format!("/reports/{entry_id}?{query}")),
+    }
+}
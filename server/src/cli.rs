@@ -0,0 +1,632 @@
+use std::io::{self, BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context};
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand};
+use common::{
+    digest::{DigestFrequency, DigestSubscription},
+    i18n::Locale,
+    migrations::run_migrations,
+    models::{self, EntryWithUser, PaymentMethod},
+    moneys::{Currency, UnknownCurrency},
+    perms,
+    statement::{Statement, StatementQuery},
+    tz::{self, Tz},
+};
+use sqlx::PgPool;
+
+use crate::config::{config, Config};
+use crate::digests::{self, Mailer};
+use crate::entries;
+use crate::fixture::{ensure_admin, seed, FixtureOptions};
+use crate::notify::notifier;
+use crate::rates;
+use crate::reminders;
+use crate::startup::{connect_with_retry, StartupError};
+use crate::statements::{self, StatementFonts};
+
+#[derive(Debug, Parser)]
+#[command(about = "Revenue reports server and admin tasks")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run migrations and serve the application (default)
+    Serve,
+    /// Apply pending database migrations and exit
+    Migrate,
+    /// Create a user with manager or administrator permissions
+    CreateUser {
+        username: String,
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        family_name: String,
+        #[arg(long)]
+        patronym: Option<String>,
+        /// Grant ManageUsers and ViewAll instead of EditOwned and ViewOwned
+        #[arg(long)]
+        admin: bool,
+        /// Read from stdin when omitted
+        #[arg(long, env = "USER_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+    },
+    /// Replace a user's password
+    SetPassword {
+        username: String,
+        /// Read from stdin when omitted
+        #[arg(long, env = "USER_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+    },
+    /// Add permission tokens to a user
+    Grant {
+        username: String,
+        #[arg(required = true, value_parser = perms::ALL)]
+        permissions: Vec<String>,
+    },
+    /// Remove permission tokens from a user
+    Revoke {
+        username: String,
+        #[arg(required = true, value_parser = perms::ALL)]
+        permissions: Vec<String>,
+    },
+    /// Create the default admin and generated demo entries, regardless of FIXTURES
+    SeedFixtures(#[command(flatten)] FixtureOptions),
+    /// Set the currency new reports of a location default to
+    LocationCurrency {
+        address: String,
+        #[arg(value_parser = currency)]
+        currency: Currency,
+    },
+    /// Set the IANA zone report dates of a location are checked in, e.g. Asia/Vladivostok
+    LocationTimezone {
+        address: String,
+        /// Omit to follow the zones of the location's managers
+        #[arg(value_parser = timezone)]
+        timezone: Option<Tz>,
+    },
+    /// Load exchange rates from a CSV file with date,currency,rate[,base] columns
+    ImportRates {
+        /// `-` reads stdin
+        path: PathBuf,
+        /// Currency of rates without a base column [default: the reporting currency]
+        #[arg(long, value_parser = currency)]
+        base: Option<Currency>,
+    },
+    /// Send the missing reports reminder now, with the configured notifier
+    SendReminders {
+        /// List managers missing at least this many days [default: reminders.min_missing]
+        #[arg(long)]
+        min_missing: Option<usize>,
+    },
+    /// Mail the digests due today that weren't mailed yet, with the configured SMTP server
+    SendDigests {
+        /// Send the digests due on this day instead, e.g. 2024-03-01 for February's
+        #[arg(long)]
+        date: Option<NaiveDate>,
+    },
+    /// Subscribe a user to digests mailed to an address, in addition to their others
+    SubscribeDigest {
+        username: String,
+        email: String,
+        #[arg(required = true, value_parser = frequency)]
+        frequencies: Vec<DigestFrequency>,
+    },
+    /// Unsubscribe a user from digests, from every one when none are given
+    UnsubscribeDigest {
+        username: String,
+        #[arg(value_parser = frequency)]
+        frequencies: Vec<DigestFrequency>,
+    },
+    /// Write a month of entries as CSV
+    Export {
+        #[arg(long)]
+        year: i32,
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..=12))]
+        month: u32,
+        /// Only entries filed by this user
+        #[arg(long)]
+        username: Option<String>,
+        /// Write to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Write a PDF statement of a period's entries, as downloaded from the reports page
+    Statement {
+        #[arg(long)]
+        from: NaiveDate,
+        #[arg(long)]
+        to: NaiveDate,
+        /// Only entries filed by this user
+        #[arg(long)]
+        username: Option<String>,
+        /// Write to this file instead of the statement's name in the current directory
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Load entries from a CSV file as written by export, replacing entries of the
+    /// same manager, location and date
+    Import {
+        /// `-` reads stdin
+        path: PathBuf,
+    },
+}
+
+/// Runs an admin command against the configured database.
+pub async fn run(command: Command) -> Result<(), StartupError> {
+    let config = config().await?;
+    let pool = connect_with_retry(config).await?;
+
+    let result = match command {
+        Command::Serve => unreachable!("serve is handled by main"),
+        Command::Migrate => run_migrations(&pool)
+            .await
+            .map(|_| log::info!("migrations applied"))
+            .map_err(StartupError::from),
+        Command::SeedFixtures(options) => match run_migrations(&pool).await {
+            Ok(()) => seed_fixtures(&pool, config, &options)
+                .await
+                .map_err(StartupError::Fixture),
+            Err(err) => Err(err.into()),
+        },
+        Command::CreateUser {
+            username,
+            name,
+            family_name,
+            patronym,
+            admin,
+            password,
+        } => create_user(
+            &pool,
+            &username,
+            &name,
+            &family_name,
+            patronym.as_deref(),
+            admin,
+            password,
+        )
+        .await
+        .map_err(StartupError::Command),
+        Command::SetPassword { username, password } => set_password(&pool, &username, password)
+            .await
+            .map_err(StartupError::Command),
+        Command::Grant {
+            username,
+            permissions,
+        } => change_permissions(&pool, &username, &permissions, true)
+            .await
+            .map_err(StartupError::Command),
+        Command::Revoke {
+            username,
+            permissions,
+        } => change_permissions(&pool, &username, &permissions, false)
+            .await
+            .map_err(StartupError::Command),
+        Command::LocationCurrency { address, currency } => {
+            models::Location::set_currency(&pool, &address, currency)
+                .await
+                .map(|_| log::info!("{address} now defaults to {currency}"))
+                .map_err(|err| StartupError::Command(err.into()))
+        }
+        Command::LocationTimezone { address, timezone } => {
+            models::Location::set_timezone(&pool, &address, timezone)
+                .await
+                .map(|_| match timezone {
+                    Some(timezone) => log::info!("{address} now uses {timezone}"),
+                    None => log::info!("{address} now follows its managers' zones"),
+                })
+                .map_err(|err| StartupError::Command(err.into()))
+        }
+        Command::ImportRates { path, base } => {
+            import_rates(&pool, &path, base.unwrap_or(config.reporting.currency))
+                .await
+                .map_err(StartupError::Command)
+        }
+        Command::SendReminders { min_missing } => send_reminders(
+            &pool,
+            config,
+            min_missing.unwrap_or(config.reminders.min_missing),
+        )
+        .await
+        .map_err(StartupError::Command),
+        Command::SendDigests { date } => send_digests(
+            &pool,
+            config,
+            date.unwrap_or_else(|| tz::today(tz::DEFAULT)),
+        )
+        .await
+        .map_err(StartupError::Command),
+        Command::SubscribeDigest {
+            username,
+            email,
+            frequencies,
+        } => subscribe_digest(&pool, &username, &email, &frequencies)
+            .await
+            .map_err(StartupError::Command),
+        Command::UnsubscribeDigest {
+            username,
+            frequencies,
+        } => unsubscribe_digest(&pool, &username, &frequencies)
+            .await
+            .map_err(StartupError::Command),
+        Command::Export {
+            year,
+            month,
+            username,
+            output,
+        } => export(&pool, year, month, username, output)
+            .await
+            .map_err(StartupError::Command),
+        Command::Statement {
+            from,
+            to,
+            username,
+            output,
+        } => statement(&pool, config, from, to, username, output)
+            .await
+            .map_err(StartupError::Command),
+        Command::Import { path } => import_entries(&pool, &path)
+            .await
+            .map_err(StartupError::Command),
+    };
+
+    pool.close().await;
+
+    result
+}
+
+async fn seed_fixtures(
+    pool: &PgPool,
+    config: &Config,
+    options: &FixtureOptions,
+) -> anyhow::Result<()> {
+    ensure_admin(pool, config).await?;
+    seed(pool, config, options).await
+}
+
+async fn create_user(
+    pool: &PgPool,
+    username: &str,
+    name: &str,
+    family_name: &str,
+    patronym: Option<&str>,
+    admin: bool,
+    password: Option<String>,
+) -> anyhow::Result<()> {
+    let password = password_or_stdin(password)?;
+    let pwd = bcrypt::hash(password, bcrypt::DEFAULT_COST)?;
+
+    let user_id = models::User::insert(pool, username, &pwd, name, family_name, patronym)
+        .await
+        .with_context(|| format!("cannot create user {username}"))?;
+
+    let permissions = if admin { perms::ADMIN } else { perms::MANAGER };
+    models::User::grant(pool, user_id, &permissions).await?;
+
+    log::info!("created user {username} ({user_id})");
+
+    Ok(())
+}
+
+async fn set_password(
+    pool: &PgPool,
+    username: &str,
+    password: Option<String>,
+) -> anyhow::Result<()> {
+    let user = find_user(pool, username).await?;
+    let password = password_or_stdin(password)?;
+    let pwd = bcrypt::hash(password, bcrypt::DEFAULT_COST)?;
+
+    models::User::set_password(pool, user.id, &pwd).await?;
+
+    log::info!("password of {username} updated");
+
+    Ok(())
+}
+
+async fn change_permissions(
+    pool: &PgPool,
+    username: &str,
+    permissions: &[String],
+    grant: bool,
+) -> anyhow::Result<()> {
+    let user = find_user(pool, username).await?;
+    let mut tokens = permissions.iter().map(String::as_str).collect::<Vec<_>>();
+    tokens.sort_unstable();
+    tokens.dedup();
+
+    if grant {
+        models::User::grant(pool, user.id, &tokens).await?;
+        log::info!("granted {} to {username}", tokens.join(", "));
+    } else {
+        models::User::revoke(pool, user.id, &tokens).await?;
+        log::info!("revoked {} from {username}", tokens.join(", "));
+    }
+
+    Ok(())
+}
+
+async fn import_rates(pool: &PgPool, path: &Path, base: Currency) -> anyhow::Result<()> {
+    let reader: Box<dyn Read> = if path == Path::new("-") {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(
+            std::fs::File::open(path).with_context(|| format!("cannot open {}", path.display()))?,
+        )
+    };
+
+    let count = rates::import(pool, reader, base, Locale::default()).await?;
+
+    log::info!("imported {count} exchange rates");
+
+    Ok(())
+}
+
+async fn send_reminders(pool: &PgPool, config: &Config, min_missing: usize) -> anyhow::Result<()> {
+    let notifier = notifier(config)?;
+    let today = tz::today(tz::DEFAULT);
+
+    let count = reminders::send(pool, notifier.as_ref(), today, min_missing).await?;
+
+    log::info!("reminded about {count} managers");
+
+    Ok(())
+}
+
+async fn send_digests(pool: &PgPool, config: &Config, today: NaiveDate) -> anyhow::Result<()> {
+    let mailer = Mailer::new(&config.smtp)?;
+
+    let sent = digests::send(pool, &mailer, config.reporting.currency, today).await?;
+
+    log::info!("mailed {sent} digests due on {today}");
+
+    Ok(())
+}
+
+async fn subscribe_digest(
+    pool: &PgPool,
+    username: &str,
+    email: &str,
+    frequencies: &[DigestFrequency],
+) -> anyhow::Result<()> {
+    let user = find_user(pool, username).await?;
+    if !common::digest::is_email(email) {
+        bail!("invalid email address {email}");
+    }
+
+    let mut subscribed = DigestSubscription::for_user(pool, user.id)
+        .await?
+        .into_iter()
+        .map(|s| s.frequency)
+        .chain(frequencies.iter().copied())
+        .collect::<Vec<_>>();
+    subscribed.sort_unstable();
+    subscribed.dedup();
+
+    DigestSubscription::replace(pool, user.id, email, &subscribed).await?;
+
+    log::info!("{username} gets {} digests at {email}", codes(&subscribed));
+
+    Ok(())
+}
+
+async fn unsubscribe_digest(
+    pool: &PgPool,
+    username: &str,
+    frequencies: &[DigestFrequency],
+) -> anyhow::Result<()> {
+    let user = find_user(pool, username).await?;
+
+    let current = DigestSubscription::for_user(pool, user.id).await?;
+    let Some(email) = current.first().map(|s| s.email.clone()) else {
+        log::info!("{username} has no digest subscriptions");
+        return Ok(());
+    };
+    let kept = current
+        .into_iter()
+        .map(|s| s.frequency)
+        .filter(|f| !frequencies.is_empty() && !frequencies.contains(f))
+        .collect::<Vec<_>>();
+
+    DigestSubscription::replace(pool, user.id, &email, &kept).await?;
+
+    match kept.is_empty() {
+        true => log::info!("{username} gets no digests"),
+        false => log::info!("{username} gets {} digests", codes(&kept)),
+    }
+
+    Ok(())
+}
+
+fn codes(frequencies: &[DigestFrequency]) -> String {
+    frequencies
+        .iter()
+        .map(DigestFrequency::code)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+async fn export(
+    pool: &PgPool,
+    year: i32,
+    month: u32,
+    username: Option<String>,
+    output: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let user_id_filter = match username {
+        Some(username) => Some(find_user(pool, &username).await?.id),
+        None => None,
+    };
+
+    let mut entries = EntryWithUser::list_month(pool, year, month, user_id_filter).await?;
+    entries.reverse();
+
+    let writer: Box<dyn Write> = match output.as_ref() {
+        Some(path) => Box::new(
+            std::fs::File::create(path)
+                .with_context(|| format!("cannot create {}", path.display()))?,
+        ),
+        None => Box::new(io::stdout().lock()),
+    };
+
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record([
+        "date",
+        "username",
+        "family_name",
+        "name",
+        "patronym",
+        "address",
+        "revenue",
+        "currency",
+        "cash",
+        "card",
+        "online",
+        "returns",
+        "shift_start",
+        "shift_end",
+        "shift",
+    ])?;
+
+    for entry in entries.iter() {
+        let payments = PaymentMethod::ALL.map(|method| {
+            entry
+                .payments
+                .iter()
+                .find(|p| p.method == method)
+                .map(|p| p.amount.to_numeric().to_string())
+                .unwrap_or_default()
+        });
+        let shift = [
+            entry.shift.map(|s| s.start.format("%H:%M").to_string()),
+            entry.shift.map(|s| s.end.format("%H:%M").to_string()),
+            entry.shift_label.clone(),
+        ]
+        .map(Option::unwrap_or_default);
+
+        csv.write_record(
+            [
+                entry.date.format("%Y-%m-%d").to_string().as_str(),
+                entry.user.username.as_str(),
+                entry.user.family_name.as_str(),
+                entry.user.name.as_str(),
+                entry.user.patronym.as_deref().unwrap_or_default(),
+                entry.address.as_str(),
+                entry.revenue.to_numeric().to_string().as_str(),
+                entry.revenue.currency.code(),
+            ]
+            .into_iter()
+            .chain(payments.iter().map(String::as_str))
+            .chain(shift.iter().map(String::as_str)),
+        )?;
+    }
+
+    csv.flush()?;
+
+    log::info!("exported {} entries for {year}-{month:02}", entries.len());
+
+    Ok(())
+}
+
+async fn statement(
+    pool: &PgPool,
+    config: &Config,
+    from: NaiveDate,
+    to: NaiveDate,
+    username: Option<String>,
+    output: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let query = StatementQuery {
+        from,
+        to,
+        user_id: match username {
+            Some(username) => Some(find_user(pool, &username).await?.id),
+            None => None,
+        },
+    };
+    query
+        .validate()
+        .map_err(|err| anyhow!("{}", Locale::default().text(err.message())))?;
+
+    let fonts = StatementFonts::load(&config.statements)?;
+    let statement = Statement::load(
+        pool,
+        config.reporting.currency,
+        (query.from, query.to),
+        query.user_id,
+        None,
+    )
+    .await?;
+    let bytes = statements::render(&statement, Locale::default(), &fonts)?;
+
+    let path = output.unwrap_or_else(|| PathBuf::from(statement.file_name()));
+    std::fs::write(&path, bytes).with_context(|| format!("cannot write {}", path.display()))?;
+
+    log::info!(
+        "wrote the statement of {} entries to {}",
+        statement.entries.len(),
+        path.display()
+    );
+
+    Ok(())
+}
+
+async fn import_entries(pool: &PgPool, path: &Path) -> anyhow::Result<()> {
+    let reader: Box<dyn Read> = if path == Path::new("-") {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(
+            std::fs::File::open(path).with_context(|| format!("cannot open {}", path.display()))?,
+        )
+    };
+
+    let imported = entries::import(pool, reader).await?;
+
+    log::info!(
+        "imported {} new and {} replaced entries",
+        imported.created,
+        imported.updated
+    );
+
+    Ok(())
+}
+
+async fn find_user(pool: &PgPool, username: &str) -> anyhow::Result<common::user::User> {
+    common::user::User::get_from_username(username.to_string(), pool)
+        .await
+        .ok_or_else(|| anyhow!("user {username} not found"))
+}
+
+fn password_or_stdin(password: Option<String>) -> anyhow::Result<String> {
+    let password = match password {
+        Some(password) => password,
+        None => {
+            eprint!("password: ");
+            let mut line = String::new();
+            io::stdin().lock().read_line(&mut line)?;
+            line.trim_end_matches(['\r', '\n']).to_string()
+        }
+    };
+
+    if password.is_empty() {
+        bail!("password must not be empty");
+    }
+
+    Ok(password)
+}
+
+fn currency(s: &str) -> Result<Currency, String> {
+    s.parse().map_err(|err: UnknownCurrency| err.to_string())
+}
+
+fn frequency(s: &str) -> Result<DigestFrequency, String> {
+    s.parse()
+}
+
+fn timezone(s: &str) -> Result<Tz, String> {
+    s.parse().map_err(|_| format!("unknown timezone {s}"))
+}
@@ -1,31 +1,522 @@
-use std::env;
+use std::{env, fmt::Display, net::SocketAddr, path::PathBuf, str::FromStr, time::Duration};
 
+use axum_session::{SameSite, SessionConfig};
+use axum_session_auth::AuthConfig;
+use chrono::NaiveTime;
+use common::{anomaly::Thresholds, moneys::Currency, IdType};
 use dotenvy::dotenv;
 use leptos::{get_configuration, leptos_config::ConfFile};
+use serde::Deserialize;
+use sqlx::postgres::PgPoolOptions;
+use thiserror::Error;
 use tokio::sync::OnceCell;
 
+/// Settings file read when `CONFIG_FILE` is not set. It is optional,
+/// every setting has a default and can be overridden from the environment.
+const DEFAULT_CONFIG_FILE: &str = "server.toml";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("{0} must be set")]
+    Missing(&'static str),
+    #[error("invalid value for {key}: {reason}")]
+    Invalid { key: &'static str, reason: String },
+    #[error("cannot read config file {path:?}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("cannot parse config file {path:?}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("leptos configuration: {0}")]
+    Leptos(String),
+}
+
 #[derive(Debug)]
 pub struct DatabaseConfig {
     pub url: String,
-    pub name: String,
 }
 
 impl DatabaseConfig {
-    fn new() -> Self {
-        let db_name = env::var("DB_NAME").expect("DB_NAME must be set");
-
+    fn new() -> Result<Self, ConfigError> {
         let url = if let Ok(url) = env::var("DATABASE_URL") {
             url
         } else {
-            let user = env::var("PG_LOGIN").expect("Database url or credentials must be set");
-            let password = env::var("PG_PWD").expect("Database url or credentials must be set");
-            let host = env::var("PG_HOST").expect("PG_HOST must be set");
+            let db_name = required("DB_NAME")?;
+            let user = required("PG_LOGIN")?;
+            let password = required("PG_PWD")?;
+            let host = required("PG_HOST")?;
             let port = env::var("PG_PORT").unwrap_or("6432".to_string());
 
             format!("postgres://{user}:{password}@{host}:{port}/{db_name}")
         };
 
-        Self { url, name: db_name }
+        Ok(Self { url })
+    }
+}
+
+/// Server side session storage and login persistence.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SessionSettings {
+    /// Table the sessions are stored in, `SESSION_TABLE`. Default: `sessions`.
+    pub table_name: String,
+    /// Session key holding the logged in user id, `SESSION_AUTH_KEY`.
+    /// Default: `public-stage.a.nvlkv.online`.
+    pub auth_key: String,
+    /// How long a "remember me" login is kept, `SESSION_MAX_AGE_SECS`.
+    /// Default: 2 days.
+    pub max_age_secs: u64,
+    /// How long an idle session is kept in the database, `SESSION_LIFETIME_SECS`.
+    /// Default: unset, the session store's own 6 hours.
+    pub lifetime_secs: Option<u64>,
+}
+
+impl Default for SessionSettings {
+    fn default() -> Self {
+        Self {
+            table_name: "sessions".to_string(),
+            auth_key: "public-stage.a.nvlkv.online".to_string(),
+            max_age_secs: 2 * 24 * 60 * 60,
+            lifetime_secs: None,
+        }
+    }
+}
+
+/// Attributes of the session cookie.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CookieSettings {
+    /// Cookie name, `COOKIE_NAME`. Default: `session`.
+    pub name: String,
+    /// Only send the cookie over https, `COOKIE_SECURE`. Default: `false`.
+    pub secure: bool,
+    /// Hide the cookie from scripts, `COOKIE_HTTP_ONLY`. Default: `true`.
+    pub http_only: bool,
+    /// One of `strict`, `lax` or `none`, `COOKIE_SAME_SITE`. Default: `lax`.
+    pub same_site: String,
+    /// Cookie domain, `COOKIE_DOMAIN`. Default: unset, the cookie is bound to the request host.
+    pub domain: Option<String>,
+    /// Cookie path, `COOKIE_PATH`. Default: `/`.
+    pub path: String,
+}
+
+impl Default for CookieSettings {
+    fn default() -> Self {
+        Self {
+            name: "session".to_string(),
+            secure: false,
+            http_only: true,
+            same_site: "lax".to_string(),
+            domain: None,
+            path: "/".to_string(),
+        }
+    }
+}
+
+impl CookieSettings {
+    fn same_site(&self) -> Result<SameSite, ConfigError> {
+        match self.same_site.to_ascii_lowercase().as_str() {
+            "strict" => Ok(SameSite::Strict),
+            "lax" => Ok(SameSite::Lax),
+            "none" => Ok(SameSite::None),
+            other => Err(ConfigError::Invalid {
+                key: "cookie.same_site",
+                reason: format!("expected strict, lax or none, got {other:?}"),
+            }),
+        }
+    }
+}
+
+/// Database connection pool.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PoolSettings {
+    /// `POOL_MAX_CONNECTIONS`. Default: 50.
+    pub max_connections: u32,
+    /// `POOL_MIN_CONNECTIONS`. Default: 0.
+    pub min_connections: u32,
+    /// Close connections older than this, `POOL_MAX_LIFETIME_SECS`. Default: 5 seconds,
+    /// connections go through pgbouncer which does its own pooling.
+    pub max_lifetime_secs: u64,
+    /// Close connections idle for longer than this, `POOL_IDLE_TIMEOUT_SECS`. Default: 10 minutes.
+    pub idle_timeout_secs: u64,
+    /// Give up waiting for a free connection after this, `POOL_ACQUIRE_TIMEOUT_SECS`.
+    /// Default: 30 seconds.
+    pub acquire_timeout_secs: u64,
+    /// Attempts to reach the database at startup, `POOL_CONNECT_ATTEMPTS`. Default: 10.
+    pub connect_attempts: u32,
+    /// Delay before the second attempt, doubled after each failure up to 30 seconds,
+    /// `POOL_CONNECT_BACKOFF_MS`. Default: 500 ms.
+    pub connect_backoff_ms: u64,
+}
+
+impl Default for PoolSettings {
+    fn default() -> Self {
+        Self {
+            max_connections: 50,
+            min_connections: 0,
+            max_lifetime_secs: 5,
+            idle_timeout_secs: 10 * 60,
+            acquire_timeout_secs: 30,
+            connect_attempts: 10,
+            connect_backoff_ms: 500,
+        }
+    }
+}
+
+/// HTTP server lifecycle.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSettings {
+    /// How long in-flight requests may take to finish after SIGTERM or SIGINT,
+    /// `SHUTDOWN_TIMEOUT_SECS`. Default: 30 seconds.
+    pub shutdown_timeout_secs: u64,
+    /// Where `/readyz` and `/metrics` are served, apart from the app so they are not
+    /// public, `METRICS_ADDR`. Default: 127.0.0.1:9090.
+    pub metrics_addr: SocketAddr,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            shutdown_timeout_secs: 30,
+            metrics_addr: SocketAddr::from(([127, 0, 0, 1], 9090)),
+        }
+    }
+}
+
+/// Reporting of amounts in several currencies.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReportingSettings {
+    /// Currency totals are converted to, `REPORTING_CURRENCY`. Default: RUB.
+    pub currency: Currency,
+}
+
+/// Background job reminding of days without reports.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RemindersSettings {
+    /// `REMINDERS_ENABLED`. Default: `false`.
+    pub enabled: bool,
+    /// Daily run time, UTC, `REMINDERS_AT`. Default: `09:00`.
+    pub at: String,
+    /// Managers are listed once they miss this many days of the month,
+    /// `REMINDERS_MIN_MISSING`. Default: 3.
+    pub min_missing: usize,
+    /// `log` or `smtp`, `REMINDERS_NOTIFIER`. Default: `log`.
+    pub notifier: String,
+}
+
+impl Default for RemindersSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            at: "09:00".to_string(),
+            min_missing: 3,
+            notifier: "log".to_string(),
+        }
+    }
+}
+
+impl RemindersSettings {
+    pub fn at(&self) -> Result<NaiveTime, ConfigError> {
+        NaiveTime::parse_from_str(&self.at, "%H:%M").map_err(|err| ConfigError::Invalid {
+            key: "reminders.at",
+            reason: format!("expected HH:MM, {err}"),
+        })
+    }
+}
+
+/// Background job mailing digests to their subscribers.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DigestsSettings {
+    /// `DIGESTS_ENABLED`. Default: `false`.
+    pub enabled: bool,
+    /// Daily run time, UTC, `DIGESTS_AT`. Default: `04:00`, 7 in Moscow.
+    pub at: String,
+}
+
+impl Default for DigestsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            at: "04:00".to_string(),
+        }
+    }
+}
+
+impl DigestsSettings {
+    pub fn at(&self) -> Result<NaiveTime, ConfigError> {
+        NaiveTime::parse_from_str(&self.at, "%H:%M").map_err(|err| ConfigError::Invalid {
+            key: "digests.at",
+            reason: format!("expected HH:MM, {err}"),
+        })
+    }
+}
+
+/// PDF statements of a period's entries.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatementsSettings {
+    /// TrueType font with Cyrillic glyphs, `STATEMENTS_FONT`.
+    /// Default: DejaVu Sans of the `fonts-dejavu-core` package.
+    pub font: PathBuf,
+    /// Its bold face, `STATEMENTS_BOLD_FONT`.
+    pub bold_font: PathBuf,
+}
+
+impl Default for StatementsSettings {
+    fn default() -> Self {
+        Self {
+            font: PathBuf::from("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf"),
+            bold_font: PathBuf::from("/usr/share/fonts/truetype/dejavu/DejaVuSans-Bold.ttf"),
+        }
+    }
+}
+
+/// Flagging of unusual revenues on save.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnomaliesSettings {
+    /// Modified z-score above which a revenue is flagged, `ANOMALY_SCORE`. Default: 3.5.
+    pub score: f64,
+    /// Days of history compared against, `ANOMALY_WINDOW_DAYS`. Default: 60.
+    pub window_days: u32,
+    /// Entries in the window needed before anything is flagged,
+    /// `ANOMALY_MIN_SAMPLES`. Default: 10.
+    pub min_samples: usize,
+}
+
+impl Default for AnomaliesSettings {
+    fn default() -> Self {
+        let thresholds = Thresholds::default();
+
+        Self {
+            score: thresholds.score,
+            window_days: thresholds.window_days,
+            min_samples: thresholds.min_samples,
+        }
+    }
+}
+
+impl AnomaliesSettings {
+    pub fn thresholds(&self) -> Thresholds {
+        Thresholds {
+            score: self.score,
+            window_days: self.window_days,
+            min_samples: self.min_samples,
+        }
+    }
+}
+
+/// Files attached to entries.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AttachmentsSettings {
+    /// `local` or `s3`, `ATTACHMENTS_BACKEND`. Default: `local`.
+    pub backend: String,
+    /// Directory of the local backend, `ATTACHMENTS_DIR`. Default: `attachments`.
+    pub dir: PathBuf,
+    /// Largest accepted file, `ATTACHMENTS_MAX_SIZE_MB`. Default: 10.
+    pub max_size_mb: usize,
+    /// Bucket of the s3 backend, `ATTACHMENTS_S3_BUCKET`.
+    pub s3_bucket: Option<String>,
+    /// `ATTACHMENTS_S3_REGION`. Default: `us-east-1`.
+    pub s3_region: String,
+    /// Endpoint of an S3 compatible service, `ATTACHMENTS_S3_ENDPOINT`. Default: AWS.
+    pub s3_endpoint: Option<String>,
+    /// `ATTACHMENTS_S3_ACCESS_KEY_ID`.
+    pub s3_access_key_id: Option<String>,
+    /// `ATTACHMENTS_S3_SECRET_ACCESS_KEY`.
+    pub s3_secret_access_key: Option<String>,
+}
+
+impl Default for AttachmentsSettings {
+    fn default() -> Self {
+        Self {
+            backend: "local".to_string(),
+            dir: PathBuf::from("attachments"),
+            max_size_mb: 10,
+            s3_bucket: None,
+            s3_region: "us-east-1".to_string(),
+            s3_endpoint: None,
+            s3_access_key_id: None,
+            s3_secret_access_key: None,
+        }
+    }
+}
+
+impl AttachmentsSettings {
+    pub fn max_size(&self) -> usize {
+        self.max_size_mb.saturating_mul(1024 * 1024)
+    }
+}
+
+/// Outgoing mail server.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SmtpSettings {
+    /// `SMTP_HOST`. Default: unset, mail can't be sent.
+    pub host: Option<String>,
+    /// `SMTP_PORT`. Default: 587.
+    pub port: u16,
+    /// `starttls`, `tls` or `none`, `SMTP_SECURITY`. Default: `starttls`.
+    pub security: String,
+    /// `SMTP_USERNAME`. Default: unset, no authentication.
+    pub username: Option<String>,
+    /// `SMTP_PASSWORD`.
+    pub password: Option<String>,
+    /// Sender mailbox, `SMTP_FROM`.
+    pub from: Option<String>,
+    /// Recipients of admin notifications, `SMTP_TO` as a comma separated list.
+    pub to: Vec<String>,
+}
+
+impl Default for SmtpSettings {
+    fn default() -> Self {
+        Self {
+            host: None,
+            port: 587,
+            security: "starttls".to_string(),
+            username: None,
+            password: None,
+            from: None,
+            to: vec![],
+        }
+    }
+}
+
+/// Layout of the optional TOML settings file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SettingsFile {
+    server: ServerSettings,
+    session: SessionSettings,
+    cookie: CookieSettings,
+    pool: PoolSettings,
+    reporting: ReportingSettings,
+    reminders: RemindersSettings,
+    digests: DigestsSettings,
+    statements: StatementsSettings,
+    anomalies: AnomaliesSettings,
+    attachments: AttachmentsSettings,
+    smtp: SmtpSettings,
+}
+
+impl SettingsFile {
+    fn load() -> Result<Self, ConfigError> {
+        let (path, required) = match env::var("CONFIG_FILE") {
+            Ok(path) => (PathBuf::from(path), true),
+            Err(_) => (PathBuf::from(DEFAULT_CONFIG_FILE), false),
+        };
+
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if !required && err.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default())
+            }
+            Err(source) => return Err(ConfigError::Read { path, source }),
+        };
+
+        toml::from_str(&content).map_err(|source| ConfigError::Parse { path, source })
+    }
+
+    fn apply_env(&mut self) -> Result<(), ConfigError> {
+        let Self {
+            server,
+            session,
+            cookie,
+            pool,
+            reporting,
+            reminders,
+            digests,
+            statements,
+            anomalies,
+            attachments,
+            smtp,
+        } = self;
+
+        env_override("SHUTDOWN_TIMEOUT_SECS", &mut server.shutdown_timeout_secs)?;
+        env_override("METRICS_ADDR", &mut server.metrics_addr)?;
+
+        env_override("SESSION_TABLE", &mut session.table_name)?;
+        env_override("SESSION_AUTH_KEY", &mut session.auth_key)?;
+        env_override("SESSION_MAX_AGE_SECS", &mut session.max_age_secs)?;
+        if env::var_os("SESSION_LIFETIME_SECS").is_some() {
+            let mut lifetime_secs = 0;
+            env_override("SESSION_LIFETIME_SECS", &mut lifetime_secs)?;
+            session.lifetime_secs = Some(lifetime_secs);
+        }
+
+        env_override("COOKIE_NAME", &mut cookie.name)?;
+        env_override("COOKIE_SECURE", &mut cookie.secure)?;
+        env_override("COOKIE_HTTP_ONLY", &mut cookie.http_only)?;
+        env_override("COOKIE_SAME_SITE", &mut cookie.same_site)?;
+        env_override("COOKIE_PATH", &mut cookie.path)?;
+        optional_env_override("COOKIE_DOMAIN", &mut cookie.domain);
+
+        env_override("POOL_MAX_CONNECTIONS", &mut pool.max_connections)?;
+        env_override("POOL_MIN_CONNECTIONS", &mut pool.min_connections)?;
+        env_override("POOL_MAX_LIFETIME_SECS", &mut pool.max_lifetime_secs)?;
+        env_override("POOL_IDLE_TIMEOUT_SECS", &mut pool.idle_timeout_secs)?;
+        env_override("POOL_ACQUIRE_TIMEOUT_SECS", &mut pool.acquire_timeout_secs)?;
+        env_override("POOL_CONNECT_ATTEMPTS", &mut pool.connect_attempts)?;
+        env_override("POOL_CONNECT_BACKOFF_MS", &mut pool.connect_backoff_ms)?;
+
+        env_override("REPORTING_CURRENCY", &mut reporting.currency)?;
+
+        env_override("REMINDERS_ENABLED", &mut reminders.enabled)?;
+        env_override("REMINDERS_AT", &mut reminders.at)?;
+        env_override("REMINDERS_MIN_MISSING", &mut reminders.min_missing)?;
+        env_override("REMINDERS_NOTIFIER", &mut reminders.notifier)?;
+
+        env_override("DIGESTS_ENABLED", &mut digests.enabled)?;
+        env_override("DIGESTS_AT", &mut digests.at)?;
+
+        env_override("STATEMENTS_FONT", &mut statements.font)?;
+        env_override("STATEMENTS_BOLD_FONT", &mut statements.bold_font)?;
+
+        env_override("ANOMALY_SCORE", &mut anomalies.score)?;
+        env_override("ANOMALY_WINDOW_DAYS", &mut anomalies.window_days)?;
+        env_override("ANOMALY_MIN_SAMPLES", &mut anomalies.min_samples)?;
+
+        env_override("ATTACHMENTS_BACKEND", &mut attachments.backend)?;
+        env_override("ATTACHMENTS_DIR", &mut attachments.dir)?;
+        env_override("ATTACHMENTS_MAX_SIZE_MB", &mut attachments.max_size_mb)?;
+        optional_env_override("ATTACHMENTS_S3_BUCKET", &mut attachments.s3_bucket);
+        env_override("ATTACHMENTS_S3_REGION", &mut attachments.s3_region)?;
+        optional_env_override("ATTACHMENTS_S3_ENDPOINT", &mut attachments.s3_endpoint);
+        optional_env_override(
+            "ATTACHMENTS_S3_ACCESS_KEY_ID",
+            &mut attachments.s3_access_key_id,
+        );
+        optional_env_override(
+            "ATTACHMENTS_S3_SECRET_ACCESS_KEY",
+            &mut attachments.s3_secret_access_key,
+        );
+
+        optional_env_override("SMTP_HOST", &mut smtp.host);
+        env_override("SMTP_PORT", &mut smtp.port)?;
+        env_override("SMTP_SECURITY", &mut smtp.security)?;
+        optional_env_override("SMTP_USERNAME", &mut smtp.username);
+        optional_env_override("SMTP_PASSWORD", &mut smtp.password);
+        optional_env_override("SMTP_FROM", &mut smtp.from);
+        if let Ok(to) = env::var("SMTP_TO") {
+            smtp.to = to
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+
+        Ok(())
     }
 }
 
@@ -33,15 +524,247 @@ impl DatabaseConfig {
 pub struct Config {
     pub leptos: ConfFile,
     pub db: DatabaseConfig,
-    pub(crate) default_admin_user: String,
-    pub(crate) default_admin_password: String,
+    pub server: ServerSettings,
+    pub session: SessionSettings,
+    pub cookie: CookieSettings,
+    pub pool: PoolSettings,
+    pub reporting: ReportingSettings,
+    pub reminders: RemindersSettings,
+    pub digests: DigestsSettings,
+    pub statements: StatementsSettings,
+    pub anomalies: AnomaliesSettings,
+    pub attachments: AttachmentsSettings,
+    pub smtp: SmtpSettings,
+    /// `ADMIN_USERNAME` and `ADMIN_PWD`, the admin created along with the fixtures.
+    pub(crate) default_admin: Option<(String, String)>,
     pub(crate) demo_user_password: Option<String>,
     pub(crate) create_fixtures: bool,
 }
 
+impl Config {
+    fn validate(&self) -> Result<(), ConfigError> {
+        let positive = |key: &'static str, value: u64| {
+            if value == 0 {
+                Err(ConfigError::Invalid {
+                    key,
+                    reason: "must be greater than zero".to_string(),
+                })
+            } else {
+                Ok(())
+            }
+        };
+
+        positive("session.max_age_secs", self.session.max_age_secs)?;
+        if let Some(lifetime_secs) = self.session.lifetime_secs {
+            positive("session.lifetime_secs", lifetime_secs)?;
+        }
+        positive("pool.max_lifetime_secs", self.pool.max_lifetime_secs)?;
+        positive("pool.idle_timeout_secs", self.pool.idle_timeout_secs)?;
+        positive("pool.acquire_timeout_secs", self.pool.acquire_timeout_secs)?;
+        positive("pool.connect_attempts", self.pool.connect_attempts.into())?;
+
+        let site_addr = self.leptos.leptos_options.site_addr;
+        let metrics_addr = self.server.metrics_addr;
+        if metrics_addr.port() == site_addr.port()
+            && (metrics_addr.ip() == site_addr.ip()
+                || metrics_addr.ip().is_unspecified()
+                || site_addr.ip().is_unspecified())
+        {
+            return Err(ConfigError::Invalid {
+                key: "server.metrics_addr",
+                reason: format!("must not be the site address {site_addr}"),
+            });
+        }
+
+        if self.session.table_name.is_empty() {
+            return Err(ConfigError::Invalid {
+                key: "session.table_name",
+                reason: "must not be empty".to_string(),
+            });
+        }
+
+        if self.cookie.name.is_empty() {
+            return Err(ConfigError::Invalid {
+                key: "cookie.name",
+                reason: "must not be empty".to_string(),
+            });
+        }
+
+        if !self.cookie.path.starts_with('/') {
+            return Err(ConfigError::Invalid {
+                key: "cookie.path",
+                reason: "must start with /".to_string(),
+            });
+        }
+
+        // browsers drop `SameSite=None` cookies that are not `Secure`
+        if self.cookie.same_site()? == SameSite::None && !self.cookie.secure {
+            return Err(ConfigError::Invalid {
+                key: "cookie.same_site",
+                reason: "none requires cookie.secure = true".to_string(),
+            });
+        }
+
+        if self.pool.max_connections == 0 {
+            return Err(ConfigError::Invalid {
+                key: "pool.max_connections",
+                reason: "must be greater than zero".to_string(),
+            });
+        }
+
+        if self.pool.min_connections > self.pool.max_connections {
+            return Err(ConfigError::Invalid {
+                key: "pool.min_connections",
+                reason: "must not exceed pool.max_connections".to_string(),
+            });
+        }
+
+        if !(self.anomalies.score.is_finite() && self.anomalies.score > 0.0) {
+            return Err(ConfigError::Invalid {
+                key: "anomalies.score",
+                reason: "must be a positive number".to_string(),
+            });
+        }
+        positive("anomalies.window_days", self.anomalies.window_days.into())?;
+
+        positive(
+            "attachments.max_size_mb",
+            self.attachments.max_size_mb as u64,
+        )?;
+
+        match self.attachments.backend.as_str() {
+            "local" => {}
+            "s3" if !cfg!(feature = "s3") => {
+                return Err(ConfigError::Invalid {
+                    key: "attachments.backend",
+                    reason: "s3 needs a server built with the s3 feature".to_string(),
+                })
+            }
+            "s3" => {
+                if self.attachments.s3_bucket.is_none() {
+                    return Err(ConfigError::Missing("ATTACHMENTS_S3_BUCKET"));
+                }
+            }
+            other => {
+                return Err(ConfigError::Invalid {
+                    key: "attachments.backend",
+                    reason: format!("expected local or s3, got {other}"),
+                })
+            }
+        }
+
+        self.reminders.at()?;
+
+        match self.reminders.notifier.as_str() {
+            "log" => {}
+            "smtp" => {
+                if self.smtp.host.is_none() {
+                    return Err(ConfigError::Missing("SMTP_HOST"));
+                }
+                if self.smtp.from.is_none() {
+                    return Err(ConfigError::Missing("SMTP_FROM"));
+                }
+                if self.smtp.to.is_empty() {
+                    return Err(ConfigError::Missing("SMTP_TO"));
+                }
+
+                for (key, mailbox) in std::iter::once(("smtp.from", self.smtp.from.as_deref()))
+                    .chain(self.smtp.to.iter().map(|to| ("smtp.to", Some(to.as_str()))))
+                {
+                    if let Some(Err(err)) = mailbox.map(str::parse::<lettre::message::Mailbox>) {
+                        return Err(ConfigError::Invalid {
+                            key,
+                            reason: err.to_string(),
+                        });
+                    }
+                }
+            }
+            other => {
+                return Err(ConfigError::Invalid {
+                    key: "reminders.notifier",
+                    reason: format!("expected log or smtp, got {other:?}"),
+                })
+            }
+        }
+
+        self.digests.at()?;
+
+        if self.digests.enabled {
+            if self.smtp.host.is_none() {
+                return Err(ConfigError::Missing("SMTP_HOST"));
+            }
+            match self
+                .smtp
+                .from
+                .as_deref()
+                .map(str::parse::<lettre::message::Mailbox>)
+            {
+                None => return Err(ConfigError::Missing("SMTP_FROM")),
+                Some(Err(err)) => {
+                    return Err(ConfigError::Invalid {
+                        key: "smtp.from",
+                        reason: err.to_string(),
+                    })
+                }
+                Some(Ok(_)) => {}
+            }
+        }
+
+        if !["starttls", "tls", "none"].contains(&self.smtp.security.as_str()) {
+            return Err(ConfigError::Invalid {
+                key: "smtp.security",
+                reason: format!(
+                    "expected starttls, tls or none, got {:?}",
+                    self.smtp.security
+                ),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn pool_options(&self) -> PgPoolOptions {
+        PgPoolOptions::new()
+            .max_connections(self.pool.max_connections)
+            .min_connections(self.pool.min_connections)
+            .max_lifetime(Some(Duration::from_secs(self.pool.max_lifetime_secs)))
+            .idle_timeout(Some(Duration::from_secs(self.pool.idle_timeout_secs)))
+            .acquire_timeout(Duration::from_secs(self.pool.acquire_timeout_secs))
+    }
+
+    pub fn session_config(&self) -> SessionConfig {
+        let cookie = &self.cookie;
+
+        let session_config = SessionConfig::default()
+            .with_table_name(self.session.table_name.clone())
+            .with_session_name(cookie.name.clone())
+            .with_cookie_path(cookie.path.clone())
+            .with_cookie_same_site(cookie.same_site().unwrap_or(SameSite::Lax))
+            .with_secure(cookie.secure)
+            .with_http_only(cookie.http_only);
+
+        let session_config = match self.session.lifetime_secs {
+            Some(secs) => session_config.with_lifetime(seconds(secs)),
+            None => session_config,
+        };
+
+        match cookie.domain.as_ref() {
+            Some(domain) => session_config.with_cookie_domain(domain.clone()),
+            None => session_config,
+        }
+    }
+
+    pub fn auth_config(&self) -> AuthConfig<IdType> {
+        AuthConfig::<IdType>::default()
+            .with_anonymous_user_id(None)
+            .with_max_age(seconds(self.session.max_age_secs))
+            .with_session_id(self.session.auth_key.clone())
+    }
+}
+
 pub static CONFIG: OnceCell<Config> = OnceCell::const_new();
 
-async fn init_config() -> Config {
+async fn init_config() -> Result<Config, ConfigError> {
     dotenv().ok();
 
     // Setting get_configuration(None) means we'll be using cargo-leptos's env values
@@ -49,18 +772,265 @@ async fn init_config() -> Config {
     // <https://github.com/leptos-rs/start-axum#executing-a-server-on-a-remote-machine-without-the-toolchain>
     // Alternately a file can be specified such as Some("Cargo.toml")
     // The file would need to be included with the executable when moved to deployment
-    let conf = get_configuration(None).await.unwrap();
+    let conf = get_configuration(None)
+        .await
+        .map_err(|err| ConfigError::Leptos(err.to_string()))?;
+
+    let mut settings = SettingsFile::load()?;
+    settings.apply_env()?;
 
-    Config {
+    let config = Config {
         leptos: conf,
-        db: DatabaseConfig::new(),
-        default_admin_user: env::var("ADMIN_USERNAME").expect("ADMIN_USERNAME must be set"),
-        default_admin_password: env::var("ADMIN_PWD").expect("ADMIN_PWD must be set"),
+        db: DatabaseConfig::new()?,
+        server: settings.server,
+        session: settings.session,
+        cookie: settings.cookie,
+        pool: settings.pool,
+        reporting: settings.reporting,
+        reminders: settings.reminders,
+        digests: settings.digests,
+        statements: settings.statements,
+        anomalies: settings.anomalies,
+        attachments: settings.attachments,
+        smtp: settings.smtp,
+        default_admin: match (env::var("ADMIN_USERNAME"), env::var("ADMIN_PWD")) {
+            (Ok(username), Ok(password)) => Some((username, password)),
+            (Err(_), Err(_)) => None,
+            (Ok(_), Err(_)) => return Err(ConfigError::Missing("ADMIN_PWD")),
+            (Err(_), Ok(_)) => return Err(ConfigError::Missing("ADMIN_USERNAME")),
+        },
         create_fixtures: env::var("FIXTURES").map(|f| f == "true").unwrap_or(false),
         demo_user_password: env::var("DEMO_PWD").ok(),
+    };
+
+    config.validate()?;
+
+    Ok(config)
+}
+
+pub async fn config() -> Result<&'static Config, ConfigError> {
+    CONFIG.get_or_try_init(init_config).await
+}
+
+fn required(key: &'static str) -> Result<String, ConfigError> {
+    env::var(key).map_err(|_| ConfigError::Missing(key))
+}
+
+fn env_override<T>(key: &'static str, target: &mut T) -> Result<(), ConfigError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    if let Ok(value) = env::var(key) {
+        *target = value.parse().map_err(|err: T::Err| ConfigError::Invalid {
+            key,
+            reason: err.to_string(),
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Sets an optional setting from the environment, an empty value unsets it.
+fn optional_env_override(key: &'static str, target: &mut Option<String>) {
+    if let Ok(value) = env::var(key) {
+        *target = Some(value).filter(|v| !v.is_empty());
     }
 }
 
-pub async fn config() -> &'static Config {
-    CONFIG.get_or_init(init_config).await
+fn seconds(secs: u64) -> chrono::Duration {
+    chrono::Duration::try_seconds(secs as i64).unwrap_or(chrono::Duration::max_value())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(settings: SettingsFile) -> Config {
+        Config {
+            leptos: ConfFile::default(),
+            db: DatabaseConfig {
+                url: "postgres://localhost/reports".to_string(),
+            },
+            server: settings.server,
+            session: settings.session,
+            cookie: settings.cookie,
+            pool: settings.pool,
+            reporting: settings.reporting,
+            reminders: settings.reminders,
+            digests: settings.digests,
+            statements: settings.statements,
+            anomalies: settings.anomalies,
+            attachments: settings.attachments,
+            smtp: settings.smtp,
+            default_admin: None,
+            demo_user_password: None,
+            create_fixtures: false,
+        }
+    }
+
+    fn invalid_key(settings: SettingsFile) -> &'static str {
+        match config(settings).validate() {
+            Err(ConfigError::Invalid { key, .. }) => key,
+            other => panic!("expected an invalid value, got {other:?}"),
+        }
+    }
+
+    fn missing_key(settings: SettingsFile) -> &'static str {
+        match config(settings).validate() {
+            Err(ConfigError::Missing(key)) => key,
+            other => panic!("expected a missing value, got {other:?}"),
+        }
+    }
+
+    fn smtp() -> SmtpSettings {
+        SmtpSettings {
+            host: Some("mail.example.com".to_string()),
+            from: Some("Reports <reports@example.com>".to_string()),
+            to: vec!["admin@example.com".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        assert!(config(SettingsFile::default()).validate().is_ok());
+    }
+
+    #[test]
+    fn session_and_cookie_are_checked() {
+        let mut settings = SettingsFile::default();
+        settings.session.max_age_secs = 0;
+        assert_eq!(invalid_key(settings), "session.max_age_secs");
+
+        let mut settings = SettingsFile::default();
+        settings.session.lifetime_secs = Some(0);
+        assert_eq!(invalid_key(settings), "session.lifetime_secs");
+
+        let mut settings = SettingsFile::default();
+        settings.session.table_name = String::new();
+        assert_eq!(invalid_key(settings), "session.table_name");
+
+        let mut settings = SettingsFile::default();
+        settings.cookie.name = String::new();
+        assert_eq!(invalid_key(settings), "cookie.name");
+
+        let mut settings = SettingsFile::default();
+        settings.cookie.path = "reports".to_string();
+        assert_eq!(invalid_key(settings), "cookie.path");
+
+        let mut settings = SettingsFile::default();
+        settings.cookie.same_site = "sometimes".to_string();
+        assert_eq!(invalid_key(settings), "cookie.same_site");
+    }
+
+    #[test]
+    fn same_site_none_needs_a_secure_cookie() {
+        let mut settings = SettingsFile::default();
+        settings.cookie.same_site = "None".to_string();
+        assert_eq!(invalid_key(settings), "cookie.same_site");
+
+        let mut settings = SettingsFile::default();
+        settings.cookie.same_site = "None".to_string();
+        settings.cookie.secure = true;
+        assert!(config(settings).validate().is_ok());
+    }
+
+    #[test]
+    fn metrics_are_kept_off_the_site_address() {
+        let mut settings = SettingsFile::default();
+        settings.server.metrics_addr = "0.0.0.0:3000".parse().unwrap();
+        assert_eq!(invalid_key(settings), "server.metrics_addr");
+
+        let mut settings = SettingsFile::default();
+        settings.server.metrics_addr = "0.0.0.0:9090".parse().unwrap();
+        assert!(config(settings).validate().is_ok());
+    }
+
+    #[test]
+    fn pool_bounds_are_checked() {
+        let mut settings = SettingsFile::default();
+        settings.pool.max_connections = 0;
+        assert_eq!(invalid_key(settings), "pool.max_connections");
+
+        let mut settings = SettingsFile::default();
+        settings.pool.min_connections = settings.pool.max_connections + 1;
+        assert_eq!(invalid_key(settings), "pool.min_connections");
+
+        let mut settings = SettingsFile::default();
+        settings.pool.min_connections = settings.pool.max_connections;
+        assert!(config(settings).validate().is_ok());
+
+        let mut settings = SettingsFile::default();
+        settings.pool.acquire_timeout_secs = 0;
+        assert_eq!(invalid_key(settings), "pool.acquire_timeout_secs");
+
+        let mut settings = SettingsFile::default();
+        settings.pool.connect_attempts = 0;
+        assert_eq!(invalid_key(settings), "pool.connect_attempts");
+    }
+
+    #[test]
+    fn smtp_reminders_need_a_server_sender_and_recipients() {
+        let mut settings = SettingsFile::default();
+        settings.reminders.notifier = "smtp".to_string();
+        assert_eq!(missing_key(settings), "SMTP_HOST");
+
+        let mut settings = SettingsFile::default();
+        settings.reminders.notifier = "smtp".to_string();
+        settings.smtp = SmtpSettings {
+            from: None,
+            ..smtp()
+        };
+        assert_eq!(missing_key(settings), "SMTP_FROM");
+
+        let mut settings = SettingsFile::default();
+        settings.reminders.notifier = "smtp".to_string();
+        settings.smtp = SmtpSettings {
+            to: vec![],
+            ..smtp()
+        };
+        assert_eq!(missing_key(settings), "SMTP_TO");
+
+        let mut settings = SettingsFile::default();
+        settings.reminders.notifier = "smtp".to_string();
+        settings.smtp = SmtpSettings {
+            to: vec!["not a mailbox".to_string()],
+            ..smtp()
+        };
+        assert_eq!(invalid_key(settings), "smtp.to");
+
+        let mut settings = SettingsFile::default();
+        settings.reminders.notifier = "smtp".to_string();
+        settings.smtp = smtp();
+        assert!(config(settings).validate().is_ok());
+
+        let mut settings = SettingsFile::default();
+        settings.reminders.notifier = "pigeon".to_string();
+        assert_eq!(invalid_key(settings), "reminders.notifier");
+    }
+
+    #[test]
+    fn digests_need_a_valid_sender() {
+        let mut settings = SettingsFile::default();
+        settings.digests.enabled = true;
+        assert_eq!(missing_key(settings), "SMTP_HOST");
+
+        let mut settings = SettingsFile::default();
+        settings.digests.enabled = true;
+        settings.smtp = SmtpSettings {
+            from: Some("reports at example.com".to_string()),
+            ..smtp()
+        };
+        assert_eq!(invalid_key(settings), "smtp.from");
+
+        let settings = SettingsFile {
+            smtp: SmtpSettings {
+                security: "ssl".to_string(),
+                ..smtp()
+            },
+            ..Default::default()
+        };
+        assert_eq!(invalid_key(settings), "smtp.security");
+    }
 }
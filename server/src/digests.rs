@@ -0,0 +1,282 @@
+use std::collections::{hash_map, HashMap};
+
+use chrono::{NaiveDate, Utc};
+use common::{
+    digest::{Digest, DigestFrequency, DigestSubscription},
+    models::Entry,
+    moneys::Currency,
+    tz,
+    user::User,
+    IdType,
+};
+use lettre::{
+    message::{Mailbox, MultiPart},
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use sqlx::PgPool;
+
+use crate::config::{Config, SmtpSettings};
+use crate::notify::smtp_transport;
+use crate::reminders::next_run;
+
+/// Mails digests to their subscribers through the configured SMTP server.
+pub struct Mailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl Mailer {
+    pub fn new(smtp: &SmtpSettings) -> anyhow::Result<Self> {
+        let (transport, from) = smtp_transport(smtp)?;
+
+        Ok(Self { transport, from })
+    }
+
+    async fn send(
+        &self,
+        to: &str,
+        subject: &str,
+        text: String,
+        html: String,
+    ) -> anyhow::Result<()> {
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to.parse()?)
+            .subject(subject)
+            .multipart(MultiPart::alternative_plain_html(text, html))?;
+
+        self.transport.send(message).await?;
+
+        Ok(())
+    }
+}
+
+/// Runs `send` every day at `digests.at`, UTC, until the process exits.
+pub fn spawn(pool: PgPool, config: &'static Config) -> anyhow::Result<()> {
+    let mailer = Mailer::new(&config.smtp)?;
+    let at = config.digests.at()?;
+    let currency = config.reporting.currency;
+
+    tokio::spawn(async move {
+        loop {
+            let now = Utc::now().naive_utc();
+            let next = next_run(now, at);
+            log::info!("next digests at {next} UTC");
+
+            tokio::time::sleep((next - now).to_std().unwrap_or_default()).await;
+
+            // periods end with the day before in the default zone, like the dashboard's months
+            let today = tz::today(tz::DEFAULT);
+
+            match send(&pool, &mailer, currency, today).await {
+                Ok(sent) => log::info!("mailed {sent} digests"),
+                Err(err) => log::error!("cannot mail digests: {err:#}"),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Mails the digests due on `today` that weren't mailed yet, each with the reports
+/// its subscriber can view. Returns how many were mailed.
+pub async fn send(
+    pool: &PgPool,
+    mailer: &Mailer,
+    currency: Currency,
+    today: NaiveDate,
+) -> anyhow::Result<usize> {
+    let mut sent = 0;
+
+    for frequency in DigestFrequency::ALL {
+        let Some(period) = frequency.period(today) else {
+            continue;
+        };
+
+        // subscribers viewing the same reports get the same digest
+        let mut digests = HashMap::<Option<IdType>, Digest>::new();
+
+        for subscription in DigestSubscription::list_due(pool, frequency, today).await? {
+            let Some(user) = User::get(subscription.user_id, pool).await else {
+                continue;
+            };
+            let Some(owner) = Entry::owner_filter(&user, pool).await else {
+                log::warn!(
+                    "{} can't view reports, no {} digest mailed",
+                    user.username,
+                    frequency.code()
+                );
+                continue;
+            };
+
+            let digest = match digests.entry(owner) {
+                hash_map::Entry::Occupied(slot) => slot.into_mut(),
+                hash_map::Entry::Vacant(slot) => {
+                    slot.insert(Digest::load(pool, currency, frequency, period, owner).await?)
+                }
+            };
+            let locale = user.locale.unwrap_or_default();
+
+            let mailed = mailer
+                .send(
+                    &subscription.email,
+                    &digest.subject(locale),
+                    digest.text(locale),
+                    digest.html(locale),
+                )
+                .await;
+
+            // one unreachable address doesn't hold up the others' digests
+            if let Err(err) = mailed {
+                log::error!(
+                    "cannot mail the {} digest to {}: {err:#}",
+                    frequency.code(),
+                    subscription.email
+                );
+                continue;
+            }
+
+            DigestSubscription::mark_sent(pool, user.id, frequency, today).await?;
+            sent += 1;
+        }
+    }
+
+    Ok(sent)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{migrations::run_migrations, models, perms::ADMIN};
+    use sqlx::{postgres::PgConnectOptions, Connection, Executor, PgConnection};
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+        sync::mpsc,
+    };
+
+    use super::*;
+
+    /// Accepts SMTP sessions on a local port and passes on the recipients and data of
+    /// every message received.
+    async fn smtp_stub() -> (u16, mpsc::UnboundedReceiver<(Vec<String>, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    let (read, mut write) = stream.into_split();
+                    let mut lines = BufReader::new(read).lines();
+                    let mut recipients = vec![];
+
+                    write.write_all(b"220 stub\r\n").await.unwrap();
+
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        let command = line.to_ascii_uppercase();
+                        let reply: &[u8] = if command.starts_with("RCPT") {
+                            recipients.push(line[8..].to_string());
+                            b"250 ok\r\n"
+                        } else if command.starts_with("DATA") {
+                            write.write_all(b"354 go on\r\n").await.unwrap();
+
+                            let mut data = String::new();
+                            while let Ok(Some(line)) = lines.next_line().await {
+                                if line == "." {
+                                    break;
+                                }
+                                data.push_str(&line);
+                                data.push('\n');
+                            }
+                            _ = sender.send((std::mem::take(&mut recipients), data));
+                            b"250 queued\r\n"
+                        } else if command.starts_with("QUIT") {
+                            write.write_all(b"221 bye\r\n").await.unwrap();
+                            return;
+                        } else {
+                            // EHLO, MAIL and RSET
+                            b"250 ok\r\n"
+                        };
+                        write.write_all(reply).await.unwrap();
+                    }
+                });
+            }
+        });
+
+        (port, receiver)
+    }
+
+    /// A migrated database of its own under a unique name next to the one of
+    /// `DATABASE_URL`, dropped by the caller.
+    async fn scratch_database() -> (PgConnection, String, PgPool) {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL");
+        let name = format!(
+            "test_{}_{}",
+            std::process::id(),
+            Utc::now().timestamp_micros()
+        );
+
+        let mut admin = PgConnection::connect(&url).await.unwrap();
+        admin
+            .execute(format!(r#"CREATE DATABASE "{name}""#).as_str())
+            .await
+            .unwrap();
+
+        let options = url.parse::<PgConnectOptions>().unwrap().database(&name);
+        let pool = PgPool::connect_with(options).await.unwrap();
+        run_migrations(&pool).await.unwrap();
+
+        (admin, name, pool)
+    }
+
+    #[tokio::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn mails_due_digests_over_smtp() {
+        let (mut admin, name, pool) = scratch_database().await;
+
+        let user_id = models::User::insert(&pool, "digest", "-", "Дайджест", "Тест", None)
+            .await
+            .unwrap();
+        models::User::grant(&pool, user_id, &ADMIN).await.unwrap();
+        DigestSubscription::replace(
+            &pool,
+            user_id,
+            "boss@example.com",
+            &[DigestFrequency::Daily],
+        )
+        .await
+        .unwrap();
+
+        let (port, mut received) = smtp_stub().await;
+        let mailer = Mailer::new(&SmtpSettings {
+            host: Some("127.0.0.1".to_string()),
+            port,
+            security: "none".to_string(),
+            from: Some("reports@example.com".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 3, 12).unwrap();
+
+        let sent = send(&pool, &mailer, Currency::RUB, today).await.unwrap();
+
+        assert_eq!(sent, 1);
+        let (recipients, data) = received.recv().await.unwrap();
+        assert_eq!(recipients, ["<boss@example.com>"]);
+        assert!(data.contains("From: reports@example.com"), "{data}");
+        assert!(data.contains("multipart/alternative"), "{data}");
+
+        let subscriptions = DigestSubscription::for_user(&pool, user_id).await.unwrap();
+        assert_eq!(subscriptions[0].last_sent_on, Some(today));
+
+        // mailed once a day
+        assert_eq!(send(&pool, &mailer, Currency::RUB, today).await.unwrap(), 0);
+
+        pool.close().await;
+        admin
+            .execute(format!(r#"DROP DATABASE "{name}" WITH (FORCE)"#).as_str())
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::{BTreeSet, HashMap};
+use std::io::Read;
+
+use anyhow::{anyhow, bail, Context};
+use chrono::NaiveDate;
+use common::{
+    i18n::Locale,
+    models::{Entry, Location, Payment, PaymentMethod, Shift, ShiftFields},
+    moneys::{Currency, Moneys, MoneysParseError},
+    IdType,
+};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use sqlx::PgPool;
+
+/// A line of the entries file, as written by `export`. Name columns are ignored, the
+/// manager is found by username. Breakdown columns are optional and must add up to
+/// the revenue when given, shift columns are empty for whole day entries.
+#[derive(Deserialize)]
+struct EntryRecord {
+    date: NaiveDate,
+    username: String,
+    address: String,
+    revenue: String,
+    currency: String,
+    #[serde(default)]
+    cash: String,
+    #[serde(default)]
+    card: String,
+    #[serde(default)]
+    online: String,
+    #[serde(default)]
+    returns: String,
+    #[serde(default)]
+    shift_start: String,
+    #[serde(default)]
+    shift_end: String,
+    #[serde(default)]
+    shift: String,
+}
+
+struct ImportedEntry {
+    line: usize,
+    username: String,
+    address: String,
+    date: NaiveDate,
+    shift: Option<Shift>,
+    shift_label: Option<String>,
+    revenue: Moneys,
+    payments: Vec<Payment>,
+}
+
+/// Entries created and replaced by an import.
+pub struct Imported {
+    pub created: usize,
+    pub updated: usize,
+}
+
+fn parse_csv(reader: impl Read) -> anyhow::Result<Vec<ImportedEntry>> {
+    let mut csv = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+
+    let mut entries = vec![];
+
+    for (i, record) in csv.deserialize::<EntryRecord>().enumerate() {
+        // line 1 is the header
+        let line = i + 2;
+        let record = record.with_context(|| format!("line {line}"))?;
+
+        let currency: Currency = record
+            .currency
+            .parse()
+            .with_context(|| format!("line {line}"))?;
+        let amount = |value: &str| -> anyhow::Result<Moneys> {
+            let decimal: Decimal = value
+                .replace(',', ".")
+                .parse()
+                .with_context(|| format!("line {line}: invalid amount {value}"))?;
+
+            // exact, like the form: extra digits are an error, not rounded away
+            if decimal.scale() > currency.exponent() {
+                let err = MoneysParseError::TooManyFractionDigits(currency.exponent());
+                bail!(
+                    "line {line}: {value}: {}",
+                    Locale::default().message(&err.message())
+                );
+            }
+
+            Ok(Moneys::from_sql(decimal, currency.code())?)
+        };
+
+        let revenue = amount(&record.revenue)?;
+
+        let mut payments = vec![];
+        let mut total = Moneys::new(0, currency);
+
+        for (method, value) in [
+            (PaymentMethod::Cash, &record.cash),
+            (PaymentMethod::Card, &record.card),
+            (PaymentMethod::Online, &record.online),
+            (PaymentMethod::Returns, &record.returns),
+        ] {
+            if value.is_empty() {
+                continue;
+            }
+
+            let amount = amount(value)?;
+
+            if amount.amount < 0 {
+                bail!("line {line}: negative {} amount", method.code());
+            }
+
+            let signed = match method {
+                PaymentMethod::Returns => Moneys::new(-amount.amount, currency),
+                _ => amount,
+            };
+            total = total
+                .checked_add(signed)
+                .ok_or_else(|| anyhow!("line {line}: amount too large"))?;
+
+            payments.push(Payment {
+                method,
+                amount,
+                converted: None,
+            });
+        }
+
+        if !payments.is_empty() && total != revenue {
+            bail!("line {line}: breakdown adds up to {total}, not to the revenue {revenue}");
+        }
+
+        let (shift, shift_label) = ShiftFields {
+            start: record.shift_start,
+            end: record.shift_end,
+            label: record.shift,
+        }
+        .parse()
+        .map_err(|err| anyhow!("line {line}: {}", Locale::default().text(err.message())))?;
+
+        entries.push(ImportedEntry {
+            line,
+            username: record.username,
+            address: record.address,
+            date: record.date,
+            shift,
+            shift_label,
+            revenue,
+            payments,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Parses and stores an entries file, all or nothing. An entry of the same manager,
+/// location, date and shift start is replaced along with its breakdown, other
+/// overlapping entries fail the import.
+pub async fn import(pool: &PgPool, reader: impl Read) -> anyhow::Result<Imported> {
+    let entries = parse_csv(reader)?;
+
+    let mut users: HashMap<String, IdType> = HashMap::new();
+    let mut imported = Imported {
+        created: 0,
+        updated: 0,
+    };
+
+    let mut tx = pool.begin().await?;
+
+    // in a fixed order, so concurrent imports don't deadlock
+    let addresses = entries
+        .iter()
+        .map(|e| e.address.as_str())
+        .collect::<BTreeSet<_>>();
+    for address in addresses {
+        Entry::lock_location(&mut *tx, address).await?;
+    }
+
+    for entry in entries {
+        let user_id = match users.get(&entry.username) {
+            Some(user_id) => *user_id,
+            None => {
+                let user = common::user::User::get_from_username(entry.username.clone(), pool)
+                    .await
+                    .ok_or_else(|| anyhow!("user {} not found", entry.username))?;
+                users.insert(entry.username.clone(), user.id);
+                user.id
+            }
+        };
+
+        Location::default_currency(&mut *tx, &entry.address, entry.revenue.currency).await?;
+
+        let (entry_id, created) = Entry::upsert(
+            &mut *tx,
+            user_id,
+            &entry.address,
+            entry.date,
+            entry.shift,
+            entry.shift_label.as_deref(),
+            entry.revenue,
+        )
+        .await?;
+
+        if let Some(existing) = Entry::find_overlapping(
+            &mut *tx,
+            user_id,
+            &entry.address,
+            entry.date,
+            entry.shift,
+            Some(entry_id),
+        )
+        .await?
+        {
+            bail!("line {}: overlaps entry {existing}", entry.line);
+        }
+
+        Payment::replace(&mut tx, entry_id, &entry.payments).await?;
+
+        if created {
+            imported.created += 1;
+        } else {
+            imported.updated += 1;
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "date,username,address,revenue,currency,cash,card,online,returns\n";
+
+    fn parse(line: &str) -> anyhow::Result<Vec<ImportedEntry>> {
+        parse_csv(format!("{HEADER}{line}\n").as_bytes())
+    }
+
+    #[test]
+    fn amounts_are_exact() {
+        let entries = parse("2024-03-01,ivanov,Lenina 1,\"1234,5\",RUB,,1234.50,,").unwrap();
+        assert_eq!(entries[0].revenue, Moneys::new(123_450, Currency::RUB));
+        assert_eq!(
+            entries[0].payments[0].amount,
+            Moneys::new(123_450, Currency::RUB)
+        );
+
+        let err = parse("2024-03-01,ivanov,Lenina 1,10.005,RUB,,,,")
+            .err()
+            .unwrap();
+        assert!(err.to_string().starts_with("line 2: 10.005:"), "{err}");
+
+        let err = parse("2024-03-01,ivanov,Lenina 1,10.00,RUB,10.001,,,")
+            .err()
+            .unwrap();
+        assert!(err.to_string().starts_with("line 2: 10.001:"), "{err}");
+    }
+
+    #[test]
+    fn breakdowns_add_up_to_the_revenue() {
+        let entries = parse("2024-03-01,ivanov,Lenina 1,90,RUB,60,40,,10").unwrap();
+        assert_eq!(entries[0].payments.len(), 3);
+
+        assert!(parse("2024-03-01,ivanov,Lenina 1,100,RUB,60,40,,10").is_err());
+    }
+}
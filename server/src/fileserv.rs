@@ -16,7 +16,13 @@ pub async fn file_and_error_handler(
     req: Request<Body>,
 ) -> AxumResponse {
     let root = options.site_root.clone();
-    let res = get_static_file(uri.clone(), &root).await.unwrap();
+    let res = match get_static_file(uri.clone(), &root).await {
+        Ok(res) => res,
+        Err((status, message)) => {
+            log::error!("cannot serve {uri}: {message}");
+            return (status, message).into_response();
+        }
+    };
 
     if res.status() == StatusCode::OK {
         res.into_response()
@@ -31,7 +37,12 @@ async fn get_static_file(uri: Uri, root: &str) -> Result<Response<Body>, (Status
     let req = Request::builder()
         .uri(uri.clone())
         .body(Body::empty())
-        .unwrap();
+        .map_err(|err| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Something went wrong: {err}"),
+            )
+        })?;
     // `ServeDir` implements `tower::Service` so we can call it with `tower::ServiceExt::oneshot`
     // This path is relative to the cargo root
     match ServeDir::new(root).oneshot(req).await {
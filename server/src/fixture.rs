@@ -1,13 +1,318 @@
-use chrono::{Days, Utc};
-
-use common::perms::*;
-use sqlx::{postgres::types::PgMoney, PgPool};
+use chrono::{Datelike, Days, NaiveDate, Utc};
+use clap::{Args, FromArgMatches};
+use common::{
+    models::{self, Payment, PaymentMethod},
+    moneys::{Currency, Moneys},
+    perms::*,
+    IdType,
+};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rand_distr::{Distribution, LogNormal};
+use sqlx::PgPool;
 
 use crate::config::Config;
 
-pub async fn make_fixture(pool: &PgPool, config: &Config) {
-    // add admin user from env
-    let admin_username = config.default_admin_user.clone();
+const FAMILY_NAMES: [&str; 16] = [
+    "Иванов",
+    "Смирнов",
+    "Кузнецов",
+    "Попов",
+    "Васильев",
+    "Петров",
+    "Соколов",
+    "Михайлов",
+    "Новиков",
+    "Фёдоров",
+    "Морозов",
+    "Волков",
+    "Алексеев",
+    "Лебедев",
+    "Семёнов",
+    "Егоров",
+];
+
+const NAMES: [&str; 10] = [
+    "Александр",
+    "Сергей",
+    "Дмитрий",
+    "Андрей",
+    "Алексей",
+    "Максим",
+    "Евгений",
+    "Иван",
+    "Михаил",
+    "Артём",
+];
+
+const PATRONYMS: [&str; 8] = [
+    "Александрович",
+    "Сергеевич",
+    "Дмитриевич",
+    "Андреевич",
+    "Алексеевич",
+    "Иванович",
+    "Михайлович",
+    "Петрович",
+];
+
+/// City, first postcode, local currency and its units per ruble.
+const CITIES: [(&str, u32, Currency, f64); 8] = [
+    ("Санкт-Петербург", 190000, Currency::RUB, 1.0),
+    ("Москва", 101000, Currency::RUB, 1.0),
+    ("Казань", 420000, Currency::RUB, 1.0),
+    ("Алматы", 50000, Currency::KZT, 5.5),
+    ("Минск", 220000, Currency::BYN, 0.035),
+    ("Новосибирск", 630000, Currency::RUB, 1.0),
+    ("Екатеринбург", 620000, Currency::RUB, 1.0),
+    ("Самара", 443000, Currency::RUB, 1.0),
+];
+
+const STREETS: [&str; 8] = [
+    "Ленина",
+    "Мира",
+    "Гагарина",
+    "Советская",
+    "Садовая",
+    "Пушкина",
+    "Лесная",
+    "Набережная",
+];
+
+/// Revenue multiplier by weekday, Monday first.
+const WEEKDAY_FACTORS: [f64; 7] = [0.85, 0.9, 0.95, 1.0, 1.15, 1.3, 1.1];
+
+/// Revenue multiplier by month, January first.
+const MONTH_FACTORS: [f64; 12] = [
+    0.8, 0.85, 0.95, 1.0, 1.0, 1.05, 1.05, 1.05, 1.0, 1.0, 1.05, 1.25,
+];
+
+/// Chance that a location closes for several days in a row.
+const CLOSURE_RATE: f64 = 0.002;
+
+/// Chance that a day has returns.
+const RETURNS_RATE: f64 = 0.15;
+
+/// Demo data generator settings. The same options with an explicit `from`
+/// always produce the same users and entries.
+#[derive(Debug, Clone, Args)]
+pub struct FixtureOptions {
+    /// Random generator seed
+    #[arg(long, default_value_t = 42)]
+    pub seed: u64,
+    /// Number of managers, named fixture_user_1..N
+    #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u32).range(1..))]
+    pub managers: u32,
+    /// Number of points of sale, assigned to managers round-robin until each
+    /// manager and location has one
+    #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u32).range(1..))]
+    pub locations: u32,
+    /// First day of the span [default: `days` before today]
+    #[arg(long)]
+    pub from: Option<NaiveDate>,
+    /// Length of the span in days
+    #[arg(long, default_value_t = 730)]
+    pub days: u32,
+    /// Probability that a manager files no report for a day
+    #[arg(long, default_value_t = 0.03, value_parser = probability)]
+    pub gap_rate: f64,
+    /// Rows per INSERT statement
+    #[arg(long, default_value_t = 5000, value_parser = clap::value_parser!(u32).range(1..))]
+    pub batch_size: u32,
+}
+
+impl Default for FixtureOptions {
+    /// The command line defaults.
+    fn default() -> Self {
+        let command = Self::augment_args(clap::Command::new("fixture"));
+
+        Self::from_arg_matches(&command.get_matches_from(["fixture"]))
+            .expect("fixture defaults are valid")
+    }
+}
+
+impl FixtureOptions {
+    /// Half-open `[from, to)` date span.
+    fn span(&self) -> anyhow::Result<(NaiveDate, NaiveDate)> {
+        let days = Days::new(self.days.into());
+
+        let from = match self.from {
+            Some(from) => from,
+            None => Utc::now()
+                .date_naive()
+                .checked_sub_days(days)
+                .ok_or_else(|| anyhow::anyhow!("fixture start date out of range"))?,
+        };
+
+        let to = from
+            .checked_add_days(days)
+            .ok_or_else(|| anyhow::anyhow!("fixture end date out of range"))?;
+
+        Ok((from, to))
+    }
+}
+
+fn probability(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(p) if (0.0..=1.0).contains(&p) => Ok(p),
+        Ok(_) => Err("must be between 0 and 1".to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FixtureManager {
+    pub username: String,
+    pub name: String,
+    pub family_name: String,
+    pub patronym: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FixtureEntry {
+    pub id: IdType,
+    pub manager: usize,
+    pub location: usize,
+    pub date: NaiveDate,
+    pub revenue: Moneys,
+    pub payments: Vec<Payment>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FixtureLocation {
+    pub address: String,
+    pub currency: Currency,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Fixture {
+    pub managers: Vec<FixtureManager>,
+    pub locations: Vec<FixtureLocation>,
+    pub entries: Vec<FixtureEntry>,
+}
+
+struct Trend {
+    base: f64,
+    growth: f64,
+}
+
+/// Generates managers, locations and daily entries for `[from, to)`.
+///
+/// Every location gets a log-normal base revenue in its local currency and a yearly
+/// growth rate, which are scaled by weekday and month factors and daily noise. Managers skip single days
+/// at `gap_rate` and now and then close for a few days in a row. Sales are split between card,
+/// online and cash, and some days have returns.
+pub fn generate(options: &FixtureOptions, from: NaiveDate, to: NaiveDate) -> Fixture {
+    let mut rng = ChaCha8Rng::seed_from_u64(options.seed);
+
+    let managers = (1..=options.managers)
+        .map(|i| FixtureManager {
+            username: format!("fixture_user_{i}"),
+            name: NAMES.choose(&mut rng).unwrap().to_string(),
+            family_name: FAMILY_NAMES.choose(&mut rng).unwrap().to_string(),
+            patronym: PATRONYMS.choose(&mut rng).unwrap().to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    let base_revenue = LogNormal::new(40_000f64.ln(), 0.5).unwrap();
+    let noise = LogNormal::new(0.0, 0.15).unwrap();
+
+    let mut locations = vec![];
+    let mut trends = vec![];
+
+    for i in 0..options.locations as usize {
+        let (city, postcode, currency, per_ruble) = CITIES[i % CITIES.len()];
+        let street = STREETS.choose(&mut rng).unwrap();
+        let house = rng.gen_range(1..120);
+        let postcode = postcode + rng.gen_range(0..100);
+
+        locations.push(FixtureLocation {
+            address: format!("ул. {street}, {house}, г.{city}, {postcode:06}"),
+            currency,
+        });
+        trends.push(Trend {
+            base: base_revenue.sample(&mut rng) * per_ruble,
+            growth: rng.gen_range(0.0..0.15),
+        });
+    }
+
+    let mut entries = vec![];
+
+    for i in 0..managers.len().max(locations.len()) {
+        let manager = i % managers.len();
+        let location = i % locations.len();
+        let currency = locations[location].currency;
+        let Trend { base, growth } = trends[location];
+        let mut closed_for = 0;
+
+        for (day, date) in from.iter_days().take_while(|d| *d < to).enumerate() {
+            if closed_for > 0 {
+                closed_for -= 1;
+                continue;
+            }
+
+            if rng.gen_bool(CLOSURE_RATE) {
+                closed_for = rng.gen_range(1..10);
+                continue;
+            }
+
+            if rng.gen_bool(options.gap_rate) {
+                continue;
+            }
+
+            let trend = (1.0 + growth).powf(day as f64 / 365.0);
+            let weekday = WEEKDAY_FACTORS[date.weekday().num_days_from_monday() as usize];
+            let season = MONTH_FACTORS[date.month0() as usize];
+            let sales = base * trend * weekday * season * noise.sample(&mut rng);
+            let sales = (sales * 10f64.powi(currency.exponent() as i32)).round() as i64;
+
+            let card = (sales as f64 * rng.gen_range(0.5..0.75)).round() as i64;
+            let online = (sales as f64 * rng.gen_range(0.03..0.15)).round() as i64;
+            let returns = if rng.gen_bool(RETURNS_RATE) {
+                (sales as f64 * rng.gen_range(0.0..0.03)).round() as i64
+            } else {
+                0
+            };
+
+            let payments = [
+                (PaymentMethod::Cash, sales - card - online),
+                (PaymentMethod::Card, card),
+                (PaymentMethod::Online, online),
+                (PaymentMethod::Returns, returns),
+            ]
+            .into_iter()
+            .filter(|(_, amount)| *amount > 0)
+            .map(|(method, amount)| Payment {
+                method,
+                amount: Moneys::new(amount, currency),
+                converted: None,
+            })
+            .collect();
+
+            entries.push(FixtureEntry {
+                id: uuid::Builder::from_random_bytes(rng.gen()).into_uuid(),
+                manager,
+                location,
+                date,
+                revenue: Moneys::new(sales - returns, currency),
+                payments,
+            });
+        }
+    }
+
+    Fixture {
+        managers,
+        locations,
+        entries,
+    }
+}
+
+/// Creates the admin from `ADMIN_USERNAME`/`ADMIN_PWD` unless it exists already.
+pub async fn ensure_admin(pool: &PgPool, config: &Config) -> anyhow::Result<()> {
+    let Some((admin_username, admin_password)) = config.default_admin.as_ref() else {
+        return Ok(());
+    };
+
     let admin = sqlx::query!(
         r#"
         SELECT * FROM users
@@ -16,134 +321,211 @@ pub async fn make_fixture(pool: &PgPool, config: &Config) {
         admin_username
     )
     .fetch_all(pool)
-    .await
-    .unwrap();
+    .await?;
 
-    if admin.first().is_none() {
-        let admin_username = config.default_admin_user.clone();
-        let pwd =
-            bcrypt::hash(config.default_admin_password.as_str(), bcrypt::DEFAULT_COST).unwrap();
+    if admin.is_empty() {
+        let pwd = bcrypt::hash(admin_password.as_str(), bcrypt::DEFAULT_COST)?;
 
-        let admin = sqlx::query!(
-            r#"
-            INSERT INTO users(username, password, name, family_name, patronym)
-            VALUES ($1, $2, $3, $4, $5)
-            RETURNING id
-            "#,
-            admin_username,
-            pwd.clone(),
-            "Админ",
-            "По",
-            "Умолчанию",
-        )
-        .fetch_one(pool)
-        .await
-        .unwrap();
+        let admin_id =
+            models::User::insert(pool, admin_username, &pwd, "Админ", "По", Some("Умолчанию"))
+                .await?;
 
-        sqlx::query!(
-            r#"
-            INSERT INTO permissions(user_id, token)
-            VALUES ($1, $2), ($1, $3)
-            "#,
-            admin.id,
-            MANAGE_USERS,
-            VIEW_ALL
-        )
-        .execute(pool)
-        .await
-        .unwrap();
+        models::User::grant(pool, admin_id, &ADMIN).await?;
 
         log::info!("Added admin user");
     }
 
-    // add fixture records
+    Ok(())
+}
+
+/// Startup fixtures: the admin, plus demo data with default options while
+/// the database has fewer than 100 entries.
+pub async fn make_fixture(pool: &PgPool, config: &Config) -> anyhow::Result<()> {
+    ensure_admin(pool, config).await?;
+
     let record = sqlx::query!(
         r#"
             SELECT COUNT(*) FROM entries
         "#
     )
     .fetch_one(pool)
-    .await
-    .unwrap();
+    .await?;
 
     if record.count.map(|c| c < 100).unwrap_or(true) {
-        let then = Utc::now()
-            .date_naive()
-            .checked_sub_days(Days::new(365 * 2))
-            .unwrap();
-
-        let pwd = config
-            .demo_user_password
-            .as_ref()
-            .map(|d| bcrypt::hash(d, bcrypt::DEFAULT_COST).unwrap())
-            .unwrap_or_default();
-
-        let fixture_user_1 = sqlx::query!(
-            r#"
-            INSERT INTO users(username, password, name, family_name, patronym)
-            VALUES ($1, $2, $3, $4, $5)
-            RETURNING id
-            "#,
-            "fixture_user_1",
-            pwd.clone(),
-            "Демо",
-            "Пользователь",
-            "Один",
-        )
-        .fetch_one(pool)
-        .await
-        .unwrap();
+        seed(pool, config, &FixtureOptions::default()).await?;
+    }
+
+    Ok(())
+}
 
-        let fixture_user_2 = sqlx::query!(
+/// Writes generated demo data in one transaction. Fixture users are upserted by
+/// username and their entries within the span replaced, so reseeding is idempotent.
+pub async fn seed(pool: &PgPool, config: &Config, options: &FixtureOptions) -> anyhow::Result<()> {
+    let (from, to) = options.span()?;
+    let fixture = generate(options, from, to);
+
+    let pwd = config
+        .demo_user_password
+        .as_ref()
+        .map(|d| bcrypt::hash(d, bcrypt::DEFAULT_COST))
+        .transpose()?
+        .unwrap_or_default();
+
+    let mut tx = pool.begin().await?;
+    let mut user_ids: Vec<IdType> = vec![];
+
+    for manager in fixture.managers.iter() {
+        let user = sqlx::query!(
             r#"
             INSERT INTO users(username, password, name, family_name, patronym)
             VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (username) DO UPDATE
+            SET password = EXCLUDED.password,
+                name = EXCLUDED.name,
+                family_name = EXCLUDED.family_name,
+                patronym = EXCLUDED.patronym
             RETURNING id
             "#,
-            "fixture_user_2",
+            manager.username,
             pwd.clone(),
-            "Демо 2",
-            "Пользователь",
-            "Два",
+            manager.name,
+            manager.family_name,
+            manager.patronym,
         )
-        .fetch_one(pool)
-        .await
-        .unwrap();
+        .fetch_one(&mut *tx)
+        .await?;
+
+        models::User::grant(&mut *tx, user.id, &MANAGER).await?;
+
+        user_ids.push(user.id);
+    }
+
+    sqlx::query!(
+        r#"
+        DELETE FROM entries
+        WHERE by_user_id = ANY($1) AND date >= $2 AND date < $3
+        "#,
+        &user_ids,
+        from,
+        to
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    for location in fixture.locations.iter() {
+        models::Location::set_currency(&mut *tx, &location.address, location.currency).await?;
+    }
+
+    for batch in fixture.entries.chunks(options.batch_size as usize) {
+        let ids = batch.iter().map(|e| e.id).collect::<Vec<_>>();
+        let by_user_ids = batch
+            .iter()
+            .map(|e| user_ids[e.manager])
+            .collect::<Vec<_>>();
+        let dates = batch.iter().map(|e| e.date).collect::<Vec<_>>();
+        let addresses = batch
+            .iter()
+            .map(|e| fixture.locations[e.location].address.clone())
+            .collect::<Vec<_>>();
+        let revenues = batch
+            .iter()
+            .map(|e| e.revenue.to_numeric())
+            .collect::<Vec<_>>();
+        let currencies = batch
+            .iter()
+            .map(|e| e.revenue.currency.code().to_string())
+            .collect::<Vec<_>>();
 
         sqlx::query!(
             r#"
-            INSERT INTO permissions(user_id, token)
-            VALUES ($1, $3), ($1, $4), ($2, $3), ($2, $4)
+            INSERT INTO entries(id, by_user_id, date, address, revenue, currency)
+            SELECT * FROM UNNEST($1::UUID[], $2::UUID[], $3::DATE[], $4::VARCHAR[], $5::NUMERIC[], $6::CHAR(3)[])
             "#,
-            fixture_user_1.id,
-            fixture_user_2.id,
-            VIEW_OWNED,
-            EDIT_OWNED
+            &ids,
+            &by_user_ids,
+            &dates,
+            &addresses,
+            &revenues,
+            &currencies
         )
-        .execute(pool)
-        .await
-        .unwrap();
-
-        for (i, date) in then.iter_days().take(365 * 2).enumerate() {
-            sqlx::query!(
-                r#"
-                INSERT INTO entries(by_user_id, date, address, revenue)
-                VALUES ($1, $2, $3, $4), ($5, $6, $7, $8);
-                "#,
-                fixture_user_1.id,
-                date,
-                "Демо адрес, 1/21, г.Санкт-Петербург, 197000",
-                PgMoney((i as i64 + 1) * 200 + i as i64),
-                fixture_user_2.id,
-                date,
-                "Демо адрес, 2/42, г.Москва, 103274",
-                PgMoney((i as i64 + 1) * 275 + i as i64),
-            )
-            .execute(pool)
-            .await
-            .unwrap();
+        .execute(&mut *tx)
+        .await?;
+
+        let (entry_ids, payments): (Vec<_>, Vec<_>) = batch
+            .iter()
+            .flat_map(|e| e.payments.iter().map(|p| (e.id, p.clone())))
+            .unzip();
+
+        Payment::insert(&mut *tx, &entry_ids, &payments).await?;
+    }
+
+    tx.commit().await?;
+
+    log::info!(
+        "created fixtures: {} managers, {} entries from {from} to {to}",
+        fixture.managers.len(),
+        fixture.entries.len()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(seed: u64) -> FixtureOptions {
+        FixtureOptions {
+            seed,
+            days: 60,
+            ..Default::default()
         }
+    }
+
+    fn from() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+    }
+
+    #[test]
+    fn defaults_are_the_command_line_ones() {
+        let options = FixtureOptions::default();
+
+        assert_eq!(options.seed, 42);
+        assert_eq!((options.managers, options.locations), (2, 2));
+        assert_eq!(options.from, None);
+        assert_eq!(options.days, 730);
+        assert_eq!(options.gap_rate, 0.03);
+        assert_eq!(options.batch_size, 5000);
+    }
+
+    #[test]
+    fn the_same_seed_generates_the_same_data() {
+        let to = from() + Days::new(60);
+
+        assert_eq!(
+            generate(&options(7), from(), to),
+            generate(&options(7), from(), to)
+        );
+        assert_ne!(
+            generate(&options(7), from(), to),
+            generate(&options(8), from(), to)
+        );
+    }
 
-        log::info!("created fixtures");
+    #[test]
+    fn every_location_gets_entries() {
+        let options = FixtureOptions {
+            managers: 2,
+            locations: 5,
+            ..options(1)
+        };
+        let fixture = generate(&options, from(), from() + Days::new(60));
+
+        for location in 0..fixture.locations.len() {
+            assert!(fixture.entries.iter().any(|e| e.location == location));
+        }
+        for manager in 0..fixture.managers.len() {
+            assert!(fixture.entries.iter().any(|e| e.manager == manager));
+        }
     }
 }
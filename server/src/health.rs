@@ -0,0 +1,31 @@
+use axum::{extract::State, http::StatusCode};
+use common::migrations::pending_migrations;
+use sqlx::PgPool;
+
+/// Liveness: the process is up and serving requests.
+pub async fn healthz() -> StatusCode {
+    StatusCode::OK
+}
+
+/// Readiness: the database answers and every bundled migration is applied.
+pub async fn readyz(State(pool): State<PgPool>) -> (StatusCode, String) {
+    if let Err(err) = sqlx::query("SELECT 1").execute(&pool).await {
+        log::warn!("readiness: database ping failed: {err}");
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "database unavailable".into(),
+        );
+    }
+
+    match pending_migrations(&pool).await {
+        Ok(0) => (StatusCode::OK, "ok".into()),
+        Ok(n) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("{n} migrations pending"),
+        ),
+        Err(err) => {
+            log::warn!("readiness: cannot read migrations: {err}");
+            (StatusCode::SERVICE_UNAVAILABLE, "migrations unknown".into())
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::{convert::Infallible, time::Duration};
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive},
+        IntoResponse, Response, Sse,
+    },
+};
+use common::{
+    ctx::AppAuthSession,
+    live::{EntryChange, CHANNEL},
+    models::Entry,
+};
+use sqlx::{postgres::PgListener, PgPool};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// Changes buffered per subscriber, a slower one is told to reload instead.
+const BUFFER: usize = 256;
+
+/// Pause before listening again after the database connection failed.
+const RETRY: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug)]
+enum Update {
+    Entry(EntryChange),
+    /// Changes may have been lost while the listener reconnected.
+    Missed,
+    /// The server is shutting down, streams end so it doesn't wait for them.
+    Closing,
+}
+
+/// Entry changes notified by the database, fanned out to the browsers subscribed to
+/// `/events`. Part of the app state.
+#[derive(Clone)]
+pub struct LiveUpdates(broadcast::Sender<Update>);
+
+impl LiveUpdates {
+    /// Listens to the database until its pool is closed.
+    pub fn spawn(pool: PgPool) -> Self {
+        let (sender, _) = broadcast::channel(BUFFER);
+        let updates = Self(sender);
+
+        let publisher = updates.clone();
+        tokio::spawn(async move {
+            while let Err(err) = publisher.listen(&pool).await {
+                if matches!(err, sqlx::Error::PoolClosed) {
+                    break;
+                }
+                log::error!("cannot listen to entry changes: {err}");
+                tokio::time::sleep(RETRY).await;
+                publisher.publish(Update::Missed);
+            }
+        });
+
+        updates
+    }
+
+    async fn listen(&self, pool: &PgPool) -> Result<(), sqlx::Error> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(CHANNEL).await?;
+
+        loop {
+            let Some(notification) = listener.try_recv().await? else {
+                // the connection is reopened by the next call
+                self.publish(Update::Missed);
+                continue;
+            };
+
+            match serde_json::from_str::<EntryChange>(notification.payload()) {
+                Ok(change) => self.publish(Update::Entry(change)),
+                Err(err) => log::warn!("unknown entry change {}: {err}", notification.payload()),
+            }
+        }
+    }
+
+    /// Ends the open streams, browsers reconnect on their own once the server is back.
+    pub fn close(&self) {
+        self.publish(Update::Closing);
+    }
+
+    fn publish(&self, update: Update) {
+        // fails only while nobody is subscribed
+        _ = self.0.send(update);
+    }
+}
+
+/// Stream of the changes of entries the user can see: `entry` events with the change,
+/// and `reload` ones when some may have been missed.
+pub async fn events(
+    State(pool): State<PgPool>,
+    State(updates): State<LiveUpdates>,
+    auth: AppAuthSession,
+) -> Response {
+    let Some(user) = auth.current_user.as_ref() else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+    let Some(owner) = Entry::owner_filter(user, &pool).await else {
+        return StatusCode::FORBIDDEN.into_response();
+    };
+
+    let stream = futures::stream::unfold(updates.0.subscribe(), move |mut receiver| async move {
+        loop {
+            let event = match receiver.recv().await {
+                Ok(Update::Entry(change)) if change.visible_to(owner) => {
+                    Event::default().event("entry").json_data(change).ok()?
+                }
+                Ok(Update::Entry(_)) => continue,
+                Ok(Update::Missed) | Err(RecvError::Lagged(_)) => {
+                    Event::default().event("reload").data("")
+                }
+                Ok(Update::Closing) | Err(RecvError::Closed) => return None,
+            };
+
+            return Some((Ok::<_, Infallible>(event), receiver));
+        }
+    });
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
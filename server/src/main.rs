@@ -1,105 +1,270 @@
+mod attachments;
+mod cli;
 mod config;
+mod digests;
+mod entries;
 mod fixture;
+mod health;
+mod live;
+mod metrics;
+mod notify;
+mod rates;
+mod reminders;
+mod startup;
+mod statements;
+mod storage;
 
 pub mod fileserv;
 
+use std::future::IntoFuture;
+use std::process::ExitCode;
+use std::time::Duration;
+
 use app::*;
 use axum::body::Body as AxumBody;
-use axum::extract::{FromRef, Path, State};
-use axum::http::Request;
+use axum::extract::{DefaultBodyLimit, FromRef, Path, State};
+use axum::http::{Request, StatusCode};
+use axum::middleware;
 use axum::response::{IntoResponse, Response};
-use axum::routing::get;
+use axum::routing::{get, post};
 use axum::Router;
-use axum_session::{SessionConfig, SessionLayer, SessionPgPool, SessionStore};
-use axum_session_auth::{AuthConfig, AuthSessionLayer};
-use common::{ctx::AppAuthSession, migrations::run_migrations, user, IdType};
+use axum_session::{SessionLayer, SessionPgPool, SessionStore};
+use axum_session_auth::AuthSessionLayer;
+use clap::Parser;
+use cli::{Cli, Command};
+use common::{
+    anomaly::{ssr::AnomalyThresholds, Thresholds},
+    ctx::AppAuthSession,
+    error::AppError,
+    exchange::ssr::ReportingCurrency,
+    i18n::ssr::request_locale,
+    migrations::run_migrations,
+    moneys::Currency,
+    user, IdType,
+};
 use config::Config;
 use fileserv::file_and_error_handler;
-use leptos::*;
+use leptos::{server_fn::error::ServerFnErrorSerde, *};
 use leptos_axum::{generate_route_list, handle_server_fns_with_context, LeptosRoutes};
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use sqlx::PgPool;
 use tower_http::trace::{self, TraceLayer};
 
-use crate::config::config;
+use crate::attachments::Attachments;
+use crate::config::{config, ConfigError};
 use crate::fixture::make_fixture;
+use crate::live::LiveUpdates;
+use crate::startup::{connect_with_retry, shutdown_signal, StartupError};
+use crate::statements::StatementFonts;
 
 #[derive(Clone, FromRef)]
 pub struct AppState {
     pool: PgPool,
     leptos_options: LeptosOptions,
+    reporting_currency: Currency,
+    anomaly_thresholds: Thresholds,
+    attachments: Attachments,
+    live_updates: LiveUpdates,
+    statement_fonts: StatementFonts,
 }
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
+async fn main() -> ExitCode {
     tracing_subscriber::fmt()
         .with_target(false)
         .compact()
         .init();
 
-    let config = config().await;
+    let command = match Cli::try_parse() {
+        Ok(cli) => cli.command.unwrap_or(Command::Serve),
+        Err(err) => {
+            _ = err.print();
+            // 2, clap's default, is taken by configuration errors
+            return if err.use_stderr() {
+                ExitCode::from(64)
+            } else {
+                ExitCode::SUCCESS
+            };
+        }
+    };
+
+    let result = match command {
+        Command::Serve => serve().await,
+        command => cli::run(command).await,
+    };
 
-    let pool = PgPoolOptions::new()
-        .max_lifetime(Some(core::time::Duration::new(5, 0)))
-        .max_connections(50)
-        .connect(config.db.url.as_str())
-        .await?;
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            tracing::error!(stage = err.stage(), error = %err, "server failed");
+            err.exit_code()
+        }
+    }
+}
 
-    initial_setup(&pool, config).await;
+async fn serve() -> Result<(), StartupError> {
+    let config = config().await?;
+
+    let pool = connect_with_retry(config).await?;
+
+    initial_setup(&pool, config).await?;
+
+    if config.reminders.enabled {
+        reminders::spawn(pool.clone(), config).map_err(|err| {
+            StartupError::Config(ConfigError::Invalid {
+                key: "reminders",
+                reason: format!("{err:#}"),
+            })
+        })?;
+    }
+
+    if config.digests.enabled {
+        digests::spawn(pool.clone(), config).map_err(|err| {
+            StartupError::Config(ConfigError::Invalid {
+                key: "digests",
+                reason: format!("{err:#}"),
+            })
+        })?;
+    }
+
+    let storage = storage::storage(config).map_err(|err| {
+        StartupError::Config(ConfigError::Invalid {
+            key: "attachments",
+            reason: format!("{err:#}"),
+        })
+    })?;
+
+    let statement_fonts = StatementFonts::load(&config.statements).map_err(|err| {
+        StartupError::Config(ConfigError::Invalid {
+            key: "statements",
+            reason: format!("{err:#}"),
+        })
+    })?;
 
     let leptos_options = config.leptos.leptos_options.clone();
+    let live_updates = LiveUpdates::spawn(pool.clone());
 
     let state = AppState {
         pool,
         leptos_options,
+        reporting_currency: config.reporting.currency,
+        anomaly_thresholds: config.anomalies.thresholds(),
+        attachments: Attachments {
+            storage,
+            max_size: config.attachments.max_size(),
+        },
+        live_updates,
+        statement_fonts,
     };
 
     let addr = state.leptos_options.site_addr;
     let routes = generate_route_list(App);
 
-    let session_config = SessionConfig::default().with_table_name("sessions");
+    let session_store = SessionStore::<SessionPgPool>::new(
+        Some(state.pool.clone().into()),
+        config.session_config(),
+    )
+    .await
+    .map_err(|err| StartupError::Database(err.into()))?;
+
+    common::metrics::register();
 
-    let auth_config = AuthConfig::<IdType>::default()
-        .with_anonymous_user_id(None)
-        .with_max_age(chrono::Duration::try_days(2).unwrap())
-        .with_session_id("public-stage.a.nvlkv.online");
+    // readiness and metrics tell about the database, so they are kept off the public
+    // address; probes and scrapes bypass the session layers so they don't create sessions
+    let ops = Router::new()
+        .route("/healthz", get(health::healthz))
+        .route("/readyz", get(health::readyz))
+        .route("/metrics", get(metrics::metrics))
+        .with_state(state.clone());
 
-    let session_store =
-        SessionStore::<SessionPgPool>::new(Some(state.pool.clone().into()), session_config).await?;
+    let pool = state.pool.clone();
+    let live_updates = state.live_updates.clone();
 
     let app = Router::new()
         .route(
             "/api/*fn_name",
             get(server_fn_handler).post(server_fn_handler),
         )
+        .route("/rates/upload", post(rates::upload))
+        .route(
+            "/reports/:id/attachments",
+            // room for the multipart framing around the largest file
+            post(attachments::upload).layer(DefaultBodyLimit::max(
+                config.attachments.max_size() + 64 * 1024,
+            )),
+        )
+        .route("/attachments/:id", get(attachments::download))
+        .route("/attachments/:id/delete", post(attachments::delete))
+        .route("/events", get(live::events))
+        .route("/statements.pdf", get(statements::download))
         .leptos_routes_with_handler(routes, get(leptos_routes_handler))
         .fallback(file_and_error_handler)
         .layer(
             AuthSessionLayer::<user::User, IdType, SessionPgPool, PgPool>::new(Some(
                 state.pool.clone(),
             ))
-            .with_config(auth_config),
+            .with_config(config.auth_config()),
         )
         .layer(SessionLayer::new(session_store))
+        .layer(middleware::from_fn(metrics::track_http))
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(trace::DefaultMakeSpan::new().level(tracing::Level::INFO))
                 .on_response(trace::DefaultOnResponse::new().level(tracing::Level::INFO)),
         )
-        .with_state(state);
+        .with_state(state)
+        .route("/healthz", get(health::healthz));
 
     log::info!("listening on http://{}", &addr);
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app.into_make_service()).await?;
+
+    let ops_addr = config.server.metrics_addr;
+    log::info!("serving probes and metrics on http://{ops_addr}");
+    let ops_listener = tokio::net::TcpListener::bind(ops_addr).await?;
+    // answers until the app is drained, then stops along with it
+    let ops_server = tokio::spawn(axum::serve(ops_listener, ops.into_make_service()).into_future());
+
+    let (drain_tx, drain_rx) = tokio::sync::oneshot::channel();
+    let drain_timeout = Duration::from_secs(config.server.shutdown_timeout_secs);
+
+    let server =
+        axum::serve(listener, app.into_make_service()).with_graceful_shutdown(async move {
+            shutdown_signal().await;
+            live_updates.close();
+            _ = drain_tx.send(());
+        });
+
+    // stop waiting for in-flight requests once the drain timeout passes
+    let drain_deadline = async move {
+        match drain_rx.await {
+            Ok(()) => tokio::time::sleep(drain_timeout).await,
+            Err(_) => std::future::pending().await,
+        }
+    };
+
+    tokio::select! {
+        res = server.into_future() => res?,
+        _ = drain_deadline => {
+            log::warn!("requests still running after {drain_timeout:?}, shutting down anyway");
+        }
+    }
+
+    ops_server.abort();
+    pool.close().await;
+    log::info!("server stopped");
 
     Ok(())
 }
 
-async fn initial_setup(pool: &PgPool, config: &Config) {
-    run_migrations(pool).await;
+async fn initial_setup(pool: &PgPool, config: &Config) -> Result<(), StartupError> {
+    run_migrations(pool).await?;
 
     if config.create_fixtures {
-        make_fixture(pool, config).await;
+        make_fixture(pool, config)
+            .await
+            .map_err(StartupError::Fixture)?;
     }
+
+    Ok(())
 }
 
 async fn leptos_routes_handler(
@@ -107,11 +272,16 @@ async fn leptos_routes_handler(
     auth_session: AppAuthSession,
     req: Request<AxumBody>,
 ) -> Response {
+    let locale = request_locale(auth_session.current_user.as_ref(), req.headers());
+
     let handler = leptos_axum::render_app_to_stream_with_context(
         app_state.leptos_options.clone(),
         move || {
+            provide_context(locale);
             provide_context(auth_session.clone());
             provide_context(app_state.pool.clone());
+            provide_context(ReportingCurrency(app_state.reporting_currency));
+            provide_context(AnomalyThresholds(app_state.anomaly_thresholds));
         },
         App,
     );
@@ -124,15 +294,44 @@ async fn server_fn_handler(
     auth_session: AppAuthSession,
     path: Path<String>,
     request: Request<AxumBody>,
-) -> impl IntoResponse {
+) -> Response {
     log::debug!("{:?}", path);
 
-    handle_server_fns_with_context(
+    let locale = request_locale(auth_session.current_user.as_ref(), request.headers());
+
+    let response = handle_server_fns_with_context(
         move || {
+            provide_context(locale);
             provide_context(auth_session.clone());
             provide_context(app_state.pool.clone());
+            provide_context(ReportingCurrency(app_state.reporting_currency));
+            provide_context(AnomalyThresholds(app_state.anomaly_thresholds));
         },
         request,
     )
     .await
+    .into_response();
+
+    with_error_status(response).await
+}
+
+/// Leptos answers every failed server function with 500, the status of its [`AppError`]
+/// tells clients what went wrong.
+async fn with_error_status(response: Response) -> Response {
+    if response.status() != StatusCode::INTERNAL_SERVER_ERROR {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, 64 * 1024).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    if let Ok(ServerFnError::WrappedServerError(err)) =
+        std::str::from_utf8(&bytes).map(<ServerFnError<AppError> as ServerFnErrorSerde>::de)
+    {
+        parts.status = err.status_code();
+    }
+
+    Response::from_parts(parts, AxumBody::from(bytes))
 }
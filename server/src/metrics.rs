@@ -0,0 +1,141 @@
+use std::{collections::HashMap, sync::LazyLock, time::Instant};
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use leptos::server_fn::axum::server_fn_paths;
+use prometheus::{
+    register_histogram_vec, register_int_gauge_vec, Encoder, HistogramVec, IntGaugeVec, TextEncoder,
+};
+use sqlx::PgPool;
+
+const SERVER_FN_ROUTE: &str = "/api/*fn_name";
+
+/// Names of the registered server functions by path, so that unknown paths don't
+/// add label values.
+static SERVER_FNS: LazyLock<HashMap<&'static str, &'static str>> = LazyLock::new(|| {
+    server_fn_paths()
+        .filter_map(|(path, _)| Some((path, server_fn_name(path)?)))
+        .collect()
+});
+
+static HTTP_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "http_request_duration_seconds",
+        "HTTP request latency by route",
+        &["method", "route", "status"]
+    )
+    .unwrap()
+});
+
+static SERVER_FN_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "server_fn_duration_seconds",
+        "Server function latency by function name",
+        &["name", "status"]
+    )
+    .unwrap()
+});
+
+static DB_POOL: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "db_pool_connections",
+        "Database pool connections by state",
+        &["state"]
+    )
+    .unwrap()
+});
+
+/// Records request latency per matched route, and per server function name
+/// for calls to `/api/*fn_name`.
+pub async fn track_http(req: Request, next: Next) -> Response {
+    let start = Instant::now();
+
+    let method = req.method().to_string();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_owned());
+    let server_fn = match route.as_deref() {
+        Some(SERVER_FN_ROUTE) => Some(
+            SERVER_FNS
+                .get(req.uri().path())
+                .copied()
+                .unwrap_or("unknown"),
+        ),
+        _ => None,
+    };
+
+    let response = next.run(req).await;
+
+    let elapsed = start.elapsed().as_secs_f64();
+    let status = response.status().as_u16().to_string();
+
+    HTTP_DURATION
+        .with_label_values(&[
+            method.as_str(),
+            route.as_deref().unwrap_or("fallback"),
+            status.as_str(),
+        ])
+        .observe(elapsed);
+
+    if let Some(name) = server_fn {
+        SERVER_FN_DURATION
+            .with_label_values(&[name, status.as_str()])
+            .observe(elapsed);
+    }
+
+    response
+}
+
+/// Leptos appends a hash of the definition site to the function name in the path.
+fn server_fn_name(path: &str) -> Option<&str> {
+    let name = path
+        .strip_prefix("/api/")?
+        .trim_end_matches(|c: char| c.is_ascii_digit());
+
+    (!name.is_empty()).then_some(name)
+}
+
+/// Prometheus text exposition of everything in the default registry.
+pub async fn metrics(State(pool): State<PgPool>) -> Response {
+    let idle = pool.num_idle() as i64;
+    let size = pool.size() as i64;
+    DB_POOL.with_label_values(&["idle"]).set(idle);
+    DB_POOL.with_label_values(&["in_use"]).set(size - idle);
+    DB_POOL
+        .with_label_values(&["max"])
+        .set(pool.options().get_max_connections() as i64);
+
+    let encoder = TextEncoder::new();
+    let mut buffer = vec![];
+
+    if let Err(err) = encoder.encode(&prometheus::gather(), &mut buffer) {
+        log::error!("cannot encode metrics: {err}");
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    (
+        [(header::CONTENT_TYPE, encoder.format_type().to_owned())],
+        buffer,
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_functions_are_named_without_the_hash() {
+        assert_eq!(
+            server_fn_name("/api/list_plans13820390751184412851"),
+            Some("list_plans")
+        );
+        assert_eq!(server_fn_name("/api/123"), None);
+        assert_eq!(server_fn_name("/rates/upload"), None);
+    }
+}
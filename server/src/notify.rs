@@ -0,0 +1,108 @@
+use axum::async_trait;
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+
+use crate::config::{Config, SmtpSettings};
+
+/// Delivers notifications for admins.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, subject: &str, body: &str) -> anyhow::Result<()>;
+}
+
+/// Writes notifications to the server log.
+pub struct LogNotifier;
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    async fn notify(&self, subject: &str, body: &str) -> anyhow::Result<()> {
+        log::warn!("{subject}\n{body}");
+
+        Ok(())
+    }
+}
+
+/// Mails notifications to the configured recipients.
+pub struct SmtpNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+impl SmtpNotifier {
+    pub fn new(smtp: &SmtpSettings) -> anyhow::Result<Self> {
+        let (transport, from) = smtp_transport(smtp)?;
+        let to = smtp
+            .to
+            .iter()
+            .map(|to| to.parse())
+            .collect::<Result<Vec<Mailbox>, _>>()?;
+
+        Ok(Self {
+            transport,
+            from,
+            to,
+        })
+    }
+}
+
+/// Connection to the configured mail server and the sender mailbox.
+pub fn smtp_transport(
+    smtp: &SmtpSettings,
+) -> anyhow::Result<(AsyncSmtpTransport<Tokio1Executor>, Mailbox)> {
+    let host = smtp
+        .host
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("SMTP_HOST must be set"))?;
+
+    let builder = match smtp.security.as_str() {
+        "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(host)?,
+        "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?,
+        _ => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+    };
+
+    let builder = match (&smtp.username, &smtp.password) {
+        (Some(username), password) => builder.credentials(Credentials::new(
+            username.clone(),
+            password.clone().unwrap_or_default(),
+        )),
+        (None, _) => builder,
+    };
+
+    let from = smtp
+        .from
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("SMTP_FROM must be set"))?
+        .parse()?;
+
+    Ok((builder.port(smtp.port).build(), from))
+}
+
+#[async_trait]
+impl Notifier for SmtpNotifier {
+    async fn notify(&self, subject: &str, body: &str) -> anyhow::Result<()> {
+        let mut message = Message::builder()
+            .from(self.from.clone())
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN);
+
+        for to in self.to.iter() {
+            message = message.to(to.clone());
+        }
+
+        self.transport.send(message.body(body.to_string())?).await?;
+
+        Ok(())
+    }
+}
+
+/// The notifier selected by `reminders.notifier`.
+pub fn notifier(config: &Config) -> anyhow::Result<Box<dyn Notifier>> {
+    match config.reminders.notifier.as_str() {
+        "smtp" => Ok(Box::new(SmtpNotifier::new(&config.smtp)?)),
+        _ => Ok(Box::new(LogNotifier)),
+    }
+}
@@ -0,0 +1,161 @@
+use std::io::Read;
+
+use anyhow::{anyhow, bail};
+use axum::{
+    extract::{Multipart, State},
+    http::HeaderMap,
+    response::Redirect,
+};
+use axum_session_auth::HasPermission;
+use chrono::NaiveDate;
+use common::{
+    ctx::AppAuthSession,
+    exchange::ssr::Rate,
+    i18n::{ssr::request_locale, Locale, Message},
+    moneys::{Currency, UnknownCurrency},
+    perms::MANAGE_USERS,
+};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use sqlx::PgPool;
+
+/// A line of the rates file: `date,currency,rate` with an optional `base` column
+/// that defaults to the reporting currency.
+#[derive(Deserialize)]
+struct RateRecord {
+    date: NaiveDate,
+    currency: String,
+    rate: String,
+    #[serde(default)]
+    base: Option<String>,
+}
+
+/// Errors are texts in the given locale, they are shown as they are.
+pub fn parse_csv(
+    reader: impl Read,
+    default_base: Currency,
+    locale: Locale,
+) -> anyhow::Result<Vec<Rate>> {
+    let mut csv = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+
+    let mut rates = vec![];
+
+    for (i, record) in csv.deserialize::<RateRecord>().enumerate() {
+        // line 1 is the header
+        let line = i + 2;
+        let line_error = |detail: String| {
+            anyhow!(
+                "{}",
+                locale.message(
+                    &Message::new("err-rates-line")
+                        .arg("line", line)
+                        .arg("detail", detail)
+                )
+            )
+        };
+
+        let record = record.map_err(|err| line_error(err.to_string()))?;
+
+        let currency: Currency = record
+            .currency
+            .parse()
+            .map_err(|err: UnknownCurrency| line_error(locale.message(&err.message())))?;
+        let base = match record.base.as_deref() {
+            None | Some("") => default_base,
+            Some(code) => code
+                .parse()
+                .map_err(|err: UnknownCurrency| line_error(locale.message(&err.message())))?,
+        };
+        let rate: Decimal = record.rate.replace(',', ".").parse().map_err(|_| {
+            line_error(
+                locale.message(&Message::new("err-rates-rate").arg("rate", record.rate.as_str())),
+            )
+        })?;
+
+        if rate <= Decimal::ZERO {
+            return Err(line_error(locale.tr("err-rates-not-positive")));
+        }
+
+        if currency == base {
+            return Err(line_error(locale.message(
+                &Message::new("err-rates-same-currency").arg("currency", currency.to_string()),
+            )));
+        }
+
+        rates.push(Rate {
+            date: record.date,
+            currency,
+            base,
+            rate,
+        });
+    }
+
+    if rates.is_empty() {
+        bail!("{}", locale.tr("err-rates-empty"));
+    }
+
+    Ok(rates)
+}
+
+/// Parses and stores a rates file, all or nothing.
+pub async fn import(
+    pool: &PgPool,
+    reader: impl Read,
+    default_base: Currency,
+    locale: Locale,
+) -> anyhow::Result<usize> {
+    let rates = parse_csv(reader, default_base, locale)?;
+
+    let mut tx = pool.begin().await?;
+    Rate::upsert(&mut *tx, &rates).await?;
+    tx.commit().await?;
+
+    Ok(rates.len())
+}
+
+/// Form upload of a rates file, redirects back to the rates page with the outcome.
+pub async fn upload(
+    State(pool): State<PgPool>,
+    State(reporting_currency): State<Currency>,
+    auth: AppAuthSession,
+    headers: HeaderMap,
+    multipart: Multipart,
+) -> Redirect {
+    let Some(user) = auth.current_user.as_ref() else {
+        return Redirect::to("/login");
+    };
+    let locale = request_locale(Some(user), &headers);
+
+    if !user.has(MANAGE_USERS, &Some(&pool)).await {
+        return rates_page(&[("error", "err-forbidden-upload-rates")]);
+    }
+
+    match read_upload(multipart, locale).await {
+        Ok(file) => match import(&pool, file.as_slice(), reporting_currency, locale).await {
+            Ok(count) => rates_page(&[("imported", count.to_string().as_str())]),
+            Err(err) => {
+                log::warn!("rates upload rejected: {err:#}");
+                rates_page(&[("error", format!("{err:#}").as_str())])
+            }
+        },
+        Err(err) => rates_page(&[("error", format!("{err:#}").as_str())]),
+    }
+}
+
+async fn read_upload(mut multipart: Multipart, locale: Locale) -> anyhow::Result<Vec<u8>> {
+    while let Some(field) = multipart.next_field().await? {
+        if field.name() == Some("file") {
+            return Ok(field.bytes().await?.to_vec());
+        }
+    }
+
+    Err(anyhow!("{}", locale.tr("err-file-missing")))
+}
+
+fn rates_page(query: &[(&str, &str)]) -> Redirect {
+    let query = serde_urlencoded::to_string(query).unwrap_or_default();
+
+    Redirect::to(&format!("/rates?{query}"))
+}
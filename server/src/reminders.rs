@@ -0,0 +1,92 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use common::{models::MissingReports, tz};
+use sqlx::PgPool;
+
+use crate::config::Config;
+use crate::notify::{notifier, Notifier};
+
+/// Runs `send` every day at `reminders.at`, UTC, until the process exits.
+pub fn spawn(pool: PgPool, config: &'static Config) -> anyhow::Result<()> {
+    let notifier = notifier(config)?;
+    let at = config.reminders.at()?;
+    let min_missing = config.reminders.min_missing;
+
+    tokio::spawn(async move {
+        loop {
+            let now = Utc::now().naive_utc();
+            let next = next_run(now, at);
+            log::info!("next missing reports check at {next} UTC");
+
+            tokio::time::sleep((next - now).to_std().unwrap_or_default()).await;
+
+            // the reminder covers every manager, so it goes by the default zone
+            let today = tz::today(tz::DEFAULT);
+
+            if let Err(err) = send(&pool, notifier.as_ref(), today, min_missing).await {
+                log::error!("cannot send missing reports reminder: {err:#}");
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Notifies about managers missing at least `min_missing` reports this month,
+/// returns how many were listed.
+pub async fn send(
+    pool: &PgPool,
+    notifier: &dyn Notifier,
+    today: NaiveDate,
+    min_missing: usize,
+) -> anyhow::Result<usize> {
+    let Some((from, to)) = MissingReports::window(today) else {
+        return Ok(0);
+    };
+
+    let missing = MissingReports::list(pool, from, to, None)
+        .await?
+        .into_iter()
+        .filter(|m| m.dates.len() >= min_missing.max(1))
+        .collect::<Vec<_>>();
+
+    if missing.is_empty() {
+        log::info!("no managers missing {min_missing} or more reports");
+        return Ok(0);
+    }
+
+    let subject = format!("Нет отчетов у менеджеров: {}", missing.len());
+    let body = missing
+        .iter()
+        .map(|m| {
+            let dates = m
+                .dates
+                .iter()
+                .map(|d| d.format("%d.%m").to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            format!(
+                "{} {} ({}), дней без отчета: {}: {dates}",
+                m.user.family_name,
+                m.user.name,
+                m.user.username,
+                m.dates.len()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    notifier.notify(&subject, &body).await?;
+
+    Ok(missing.len())
+}
+
+pub(crate) fn next_run(now: NaiveDateTime, at: NaiveTime) -> NaiveDateTime {
+    let today = now.date().and_time(at);
+
+    if today > now {
+        today
+    } else {
+        today + chrono::Duration::days(1)
+    }
+}
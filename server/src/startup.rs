@@ -0,0 +1,104 @@
+use std::{process::ExitCode, time::Duration};
+
+use sqlx::{migrate::MigrateError, PgPool};
+use thiserror::Error;
+
+use crate::config::{Config, ConfigError};
+
+const MAX_CONNECT_BACKOFF: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Error)]
+pub enum StartupError {
+    #[error("configuration: {0}")]
+    Config(#[from] ConfigError),
+    #[error("database: {0}")]
+    Database(#[source] anyhow::Error),
+    #[error("migrations: {0}")]
+    Migration(#[from] MigrateError),
+    #[error("fixtures: {0:#}")]
+    Fixture(#[source] anyhow::Error),
+    #[error("server: {0}")]
+    Serve(#[from] std::io::Error),
+    #[error("{0:#}")]
+    Command(#[source] anyhow::Error),
+}
+
+impl StartupError {
+    /// Process exit code, distinct per failure stage so orchestrators can tell them apart.
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            StartupError::Config(_) => ExitCode::from(2),
+            StartupError::Database(_) => ExitCode::from(3),
+            StartupError::Migration(_) => ExitCode::from(4),
+            StartupError::Fixture(_) | StartupError::Serve(_) | StartupError::Command(_) => {
+                ExitCode::FAILURE
+            }
+        }
+    }
+
+    pub fn stage(&self) -> &'static str {
+        match self {
+            StartupError::Config(_) => "config",
+            StartupError::Database(_) => "database",
+            StartupError::Migration(_) => "migration",
+            StartupError::Fixture(_) => "fixture",
+            StartupError::Serve(_) => "serve",
+            StartupError::Command(_) => "command",
+        }
+    }
+}
+
+/// Connects to the database, retrying with exponential backoff while it is unreachable.
+pub async fn connect_with_retry(config: &Config) -> Result<PgPool, StartupError> {
+    let attempts = config.pool.connect_attempts;
+    let mut backoff = Duration::from_millis(config.pool.connect_backoff_ms);
+    let mut attempt = 1;
+
+    loop {
+        match config.pool_options().connect(config.db.url.as_str()).await {
+            Ok(pool) => return Ok(pool),
+            Err(err) if attempt < attempts => {
+                log::warn!(
+                    "database connection attempt {attempt}/{attempts} failed: {err}, retrying in {backoff:?}"
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_CONNECT_BACKOFF);
+                attempt += 1;
+            }
+            Err(err) => return Err(StartupError::Database(err.into())),
+        }
+    }
+}
+
+/// Resolves on the first SIGINT (ctrl-c) or SIGTERM.
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            log::error!("cannot listen for ctrl-c: {err}");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(err) => {
+                log::error!("cannot listen for SIGTERM: {err}");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    log::info!("shutdown signal received, draining connections");
+}
@@ -0,0 +1,526 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::Context;
+use axum::{
+    extract::{rejection::QueryRejection, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use common::{
+    ctx::AppAuthSession,
+    i18n::{ssr::request_locale, Locale, Message},
+    models::Entry,
+    moneys::Currency,
+    statement::{Statement, StatementQuery},
+    summary::Totals,
+};
+use printpdf::{
+    Color, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, Point,
+    Pt, Rgb,
+};
+use sqlx::PgPool;
+use ttf_parser::Face;
+
+use crate::attachments::percent_encode;
+use crate::config::StatementsSettings;
+
+/// A4 landscape.
+const PAGE_WIDTH: f32 = 297.0;
+const PAGE_HEIGHT: f32 = 210.0;
+const MARGIN: f32 = 15.0;
+/// Room kept at the bottom of each page for the footer.
+const FOOTER: f32 = 10.0;
+/// Space between a cell's border and its text.
+const PADDING: f32 = 1.5;
+
+/// Font sizes in points and the line heights they are set with, in millimetres.
+const TITLE: (f32, f32) = (14.0, 7.0);
+const HEADING: (f32, f32) = (11.0, 6.0);
+const BODY: (f32, f32) = (9.0, 4.2);
+const NOTE: (f32, f32) = (7.5, 3.5);
+
+/// Fonts statements are set in, read once at startup.
+#[derive(Clone)]
+pub struct StatementFonts {
+    regular: Arc<Vec<u8>>,
+    bold: Arc<Vec<u8>>,
+}
+
+impl StatementFonts {
+    pub fn load(settings: &StatementsSettings) -> anyhow::Result<Self> {
+        Ok(Self {
+            regular: read_font(&settings.font)?,
+            bold: read_font(&settings.bold_font)?,
+        })
+    }
+}
+
+fn read_font(path: &Path) -> anyhow::Result<Arc<Vec<u8>>> {
+    let bytes = std::fs::read(path).with_context(|| format!("cannot read {}", path.display()))?;
+    Face::parse(&bytes, 0).with_context(|| format!("{} is not a TrueType font", path.display()))?;
+
+    Ok(Arc::new(bytes))
+}
+
+/// Statement of the requested period as a PDF file, to users who can view its entries.
+pub async fn download(
+    State(pool): State<PgPool>,
+    State(currency): State<Currency>,
+    State(fonts): State<StatementFonts>,
+    auth: AppAuthSession,
+    headers: HeaderMap,
+    query: Result<Query<StatementQuery>, QueryRejection>,
+) -> Response {
+    let Some(user) = auth.current_user.as_ref() else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+    let locale = request_locale(Some(user), &headers);
+
+    let Query(query) = match query {
+        Ok(query) => query,
+        Err(rejection) => {
+            let detail = Message::new("err-request").arg("detail", rejection.body_text());
+            return (rejection.status(), locale.error_text(&detail.to_string())).into_response();
+        }
+    };
+
+    let filter = Entry::owner_filter(user, &pool).await;
+    let owner = match query
+        .owner(filter)
+        .and_then(|owner| query.validate().map(|_| owner))
+    {
+        Ok(owner) => owner,
+        Err(err) => return (err.status_code(), locale.error_text(err.message())).into_response(),
+    };
+
+    let statement = match Statement::load(
+        &pool,
+        currency,
+        (query.from, query.to),
+        owner,
+        Some(user.clone()),
+    )
+    .await
+    {
+        Ok(statement) => statement,
+        Err(sqlx::Error::RowNotFound) => return StatusCode::NOT_FOUND.into_response(),
+        Err(err) => {
+            log::error!("cannot load statement: {err:#}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let file_name = statement.file_name();
+
+    match tokio::task::spawn_blocking(move || render(&statement, locale, &fonts)).await {
+        Ok(Ok(bytes)) => (
+            [
+                (header::CONTENT_TYPE, "application/pdf".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!(
+                        "attachment; filename*=UTF-8''{}",
+                        percent_encode(&file_name)
+                    ),
+                ),
+                (header::CACHE_CONTROL, "no-store".to_string()),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Ok(Err(err)) => {
+            log::error!("cannot render statement {file_name}: {err:#}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+        Err(err) => {
+            log::error!("statement {file_name} panicked: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// The statement as a PDF document in `locale`: its entries, the manager subtotals
+/// and totals, then a signature line; every page ends with who generated it when.
+pub fn render(
+    statement: &Statement,
+    locale: Locale,
+    fonts: &StatementFonts,
+) -> anyhow::Result<Vec<u8>> {
+    let title = statement.title(locale);
+    let (doc, page, layer) = PdfDocument::new(&title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "statement");
+    let layer = doc.get_page(page).get_layer(layer);
+
+    let mut pdf = Pdf {
+        regular: Font::new(&doc, &fonts.regular)?,
+        bold: Font::new(&doc, &fonts.bold)?,
+        doc,
+        pages: vec![layer],
+        y: PAGE_HEIGHT - MARGIN,
+    };
+
+    pdf.text(&title, true, TITLE);
+    pdf.text(&statement.scope(locale), false, HEADING);
+    pdf.y -= HEADING.1;
+
+    if statement.entries.is_empty() {
+        pdf.text(&locale.tr("statement-empty"), false, BODY);
+    } else {
+        let columns = [
+            Column::left(40.0),
+            Column::left(50.0),
+            Column::left(117.0),
+            Column::right(60.0),
+        ];
+        let header = Statement::header(locale).map(Cell::text);
+        let rows = statement
+            .rows()
+            .into_iter()
+            .map(|row| {
+                let date = match row.shift.is_empty() {
+                    true => Cell::text(row.date),
+                    false => Cell::text(row.date).note(row.shift),
+                };
+                let revenue = match row.converted {
+                    Some(converted) => Cell::text(row.revenue).note(converted),
+                    None => Cell::text(row.revenue),
+                };
+
+                vec![
+                    date,
+                    Cell::text(row.manager),
+                    Cell::text(row.address),
+                    revenue,
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        pdf.table(&columns, &header, &rows, None);
+        pdf.y -= HEADING.1;
+
+        let totals = statement.totals();
+        let subtotals = statement.subtotals();
+        let width = |count: usize| (PAGE_WIDTH - 2.0 * MARGIN - 50.0) / count as f32;
+        let labels = totals.columns(&totals, locale);
+        let columns = [Column::left(50.0)]
+            .into_iter()
+            .chain(
+                labels
+                    .iter()
+                    .map(|_| Column::right(width(labels.len() + 1))),
+            )
+            .chain([Column::right(width(labels.len() + 1))])
+            .collect::<Vec<_>>();
+        let row = |name: String, manager: &Totals| {
+            [Cell::text(name)]
+                .into_iter()
+                .chain(
+                    manager
+                        .columns(&totals, locale)
+                        .into_iter()
+                        .map(|(_, value)| Cell::text(value)),
+                )
+                .chain([Cell::text(manager.revenue.to_string())])
+                .collect::<Vec<_>>()
+        };
+        let header = [Cell::text(locale.tr("field-manager"))]
+            .into_iter()
+            .chain(labels.into_iter().map(|(label, _)| Cell::text(label)))
+            .chain([Cell::text(locale.tr("field-revenue"))])
+            .collect::<Vec<_>>();
+        let rows = subtotals
+            .iter()
+            .map(|(user, manager)| row(user.short_name(), manager))
+            .collect::<Vec<_>>();
+
+        pdf.keep(HEADING.1 + 3.0 * (BODY.1 + 2.0 * PADDING));
+        pdf.text(&locale.tr("statement-subtotals"), true, HEADING);
+        pdf.table(
+            &columns,
+            &header,
+            &rows,
+            Some(&row(locale.tr("statement-total"), &totals)),
+        );
+    }
+
+    pdf.y -= 2.0 * HEADING.1;
+    pdf.keep(HEADING.1);
+    pdf.text(
+        &format!(
+            "{}: ______________________",
+            locale.tr("statement-signature")
+        ),
+        false,
+        HEADING,
+    );
+
+    let generated = statement.generated(locale);
+    let count = pdf.pages.len();
+    for (i, layer) in pdf.pages.iter().enumerate() {
+        let page = locale.message(
+            &Message::new("statement-page")
+                .arg("page", i + 1)
+                .arg("pages", count),
+        );
+        let y = MARGIN - FOOTER / 2.0;
+
+        layer.set_fill_color(GREY);
+        layer.use_text(&generated, NOTE.0, Mm(MARGIN), Mm(y), &pdf.regular.pdf);
+        let x = PAGE_WIDTH - MARGIN - pdf.regular.width(&page, NOTE.0);
+        layer.use_text(&page, NOTE.0, Mm(x), Mm(y), &pdf.regular.pdf);
+    }
+
+    Ok(pdf.doc.save_to_bytes()?)
+}
+
+const BLACK: Color = Color::Rgb(Rgb {
+    r: 0.0,
+    g: 0.0,
+    b: 0.0,
+    icc_profile: None,
+});
+
+/// `text-slate-500`, as secondary lines are shown in the app.
+const GREY: Color = Color::Rgb(Rgb {
+    r: 0.39,
+    g: 0.45,
+    b: 0.55,
+    icc_profile: None,
+});
+
+/// A font embedded in the document and measured with its metrics.
+struct Font<'a> {
+    face: Face<'a>,
+    pdf: IndirectFontRef,
+}
+
+impl<'a> Font<'a> {
+    fn new(doc: &PdfDocumentReference, bytes: &'a [u8]) -> anyhow::Result<Self> {
+        Ok(Self {
+            face: Face::parse(bytes, 0)?,
+            pdf: doc.add_external_font(bytes)?,
+        })
+    }
+
+    /// Width of `text` set in `size` points, in millimetres.
+    fn width(&self, text: &str, size: f32) -> f32 {
+        let units = text
+            .chars()
+            .filter_map(|c| self.face.glyph_index(c))
+            .filter_map(|glyph| self.face.glyph_hor_advance(glyph))
+            .map(f32::from)
+            .sum::<f32>();
+
+        Mm::from(Pt(units * size / f32::from(self.face.units_per_em()))).0
+    }
+
+    /// Lines of `text` no wider than `width` where it can be broken at spaces.
+    fn wrap(&self, text: &str, size: f32, width: f32) -> Vec<String> {
+        let mut lines = vec![];
+        let mut line = String::new();
+
+        for word in text.split(' ') {
+            let longer = match line.is_empty() {
+                true => word.to_string(),
+                false => format!("{line} {word}"),
+            };
+
+            if line.is_empty() || self.width(&longer, size) <= width {
+                line = longer;
+            } else {
+                lines.push(std::mem::replace(&mut line, word.to_string()));
+            }
+        }
+        lines.push(line);
+
+        lines
+    }
+}
+
+struct Column {
+    width: f32,
+    /// Amounts are aligned right.
+    right: bool,
+}
+
+impl Column {
+    fn left(width: f32) -> Self {
+        Self {
+            width,
+            right: false,
+        }
+    }
+
+    fn right(width: f32) -> Self {
+        Self { width, right: true }
+    }
+}
+
+/// Text of a table cell and a smaller grey note below it, as in `ReportsTable`.
+struct Cell {
+    text: String,
+    note: Option<String>,
+}
+
+impl Cell {
+    fn text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            note: None,
+        }
+    }
+
+    fn note(self, note: impl Into<String>) -> Self {
+        Self {
+            note: Some(note.into()),
+            ..self
+        }
+    }
+}
+
+/// The document being written, top to bottom from `y` on its last page.
+struct Pdf<'a> {
+    doc: PdfDocumentReference,
+    pages: Vec<PdfLayerReference>,
+    regular: Font<'a>,
+    bold: Font<'a>,
+    y: f32,
+}
+
+impl Pdf<'_> {
+    fn font(&self, bold: bool) -> &Font<'_> {
+        match bold {
+            true => &self.bold,
+            false => &self.regular,
+        }
+    }
+
+    fn layer(&self) -> &PdfLayerReference {
+        self.pages.last().expect("the document starts with a page")
+    }
+
+    /// Starts a new page unless `height` fits on the current one.
+    fn keep(&mut self, height: f32) {
+        if self.y - height < MARGIN + FOOTER {
+            let (page, layer) = self
+                .doc
+                .add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "statement");
+            self.pages.push(self.doc.get_page(page).get_layer(layer));
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn text(&mut self, text: &str, bold: bool, (size, height): (f32, f32)) {
+        self.keep(height);
+        self.y -= height;
+
+        let layer = self.layer();
+        layer.set_fill_color(BLACK);
+        layer.use_text(text, size, Mm(MARGIN), Mm(self.y), &self.font(bold).pdf);
+    }
+
+    fn rule(&self, width: f32, thickness: f32) {
+        let layer = self.layer();
+        layer.set_outline_color(GREY);
+        layer.set_outline_thickness(thickness);
+        layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(MARGIN), Mm(self.y)), false),
+                (Point::new(Mm(MARGIN + width), Mm(self.y)), false),
+            ],
+            is_closed: false,
+        });
+    }
+
+    /// Rows under a bold header repeated on each page, and an optional bold footer.
+    fn table(
+        &mut self,
+        columns: &[Column],
+        header: &[Cell],
+        rows: &[Vec<Cell>],
+        footer: Option<&[Cell]>,
+    ) {
+        let width = columns.iter().map(|c| c.width).sum::<f32>();
+
+        self.header(columns, header, width);
+        for row in rows {
+            if self.row(columns, row, false, true) {
+                self.header(columns, header, width);
+                self.row(columns, row, false, false);
+            }
+            self.rule(width, 0.3);
+        }
+        if let Some(footer) = footer {
+            if self.row(columns, footer, true, true) {
+                self.header(columns, header, width);
+                self.row(columns, footer, true, false);
+            }
+            self.rule(width, 1.0);
+        }
+    }
+
+    fn header(&mut self, columns: &[Column], header: &[Cell], width: f32) {
+        self.row(columns, header, true, false);
+        self.rule(width, 1.0);
+    }
+
+    /// Writes a row of cells wrapped to their columns. With `paged` it only starts a
+    /// new page when the row doesn't fit, and returns `true` so the caller repeats
+    /// the header first.
+    fn row(&mut self, columns: &[Column], cells: &[Cell], bold: bool, paged: bool) -> bool {
+        let lines = columns
+            .iter()
+            .zip(cells)
+            .map(|(column, cell)| {
+                let width = column.width - 2.0 * PADDING;
+                let text = self.font(bold).wrap(&cell.text, BODY.0, width);
+                let note = cell
+                    .note
+                    .as_ref()
+                    .map(|note| self.regular.wrap(note, NOTE.0, width))
+                    .unwrap_or_default();
+
+                (text, note)
+            })
+            .collect::<Vec<_>>();
+        let height = lines
+            .iter()
+            .map(|(text, note)| text.len() as f32 * BODY.1 + note.len() as f32 * NOTE.1)
+            .fold(0.0, f32::max)
+            + 2.0 * PADDING;
+
+        if paged && self.y - height < MARGIN + FOOTER {
+            self.keep(height);
+            return true;
+        }
+        self.keep(height);
+
+        let layer = self.layer();
+        let font = self.font(bold);
+        let mut x = MARGIN;
+        for (column, (text, note)) in columns.iter().zip(lines) {
+            let mut y = self.y - PADDING;
+            let mut write = |line: &str, font: &Font, (size, height): (f32, f32)| {
+                y -= height;
+                let left = match column.right {
+                    true => x + column.width - PADDING - font.width(line, size),
+                    false => x + PADDING,
+                };
+                // baselines sit a quarter of the line above its bottom
+                layer.use_text(line, size, Mm(left), Mm(y + height / 4.0), &font.pdf);
+            };
+
+            layer.set_fill_color(BLACK);
+            for line in text.iter() {
+                write(line, font, BODY);
+            }
+            layer.set_fill_color(GREY);
+            for line in note.iter() {
+                write(line, &self.regular, NOTE);
+            }
+
+            x += column.width;
+        }
+        self.y -= height;
+
+        false
+    }
+}
@@ -0,0 +1,156 @@
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{bail, Context};
+use axum::async_trait;
+
+use crate::config::Config;
+
+/// Keeps file contents under keys like `<entry id>/<attachment id>`.
+#[async_trait]
+pub trait BlobStorage: Send + Sync {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> anyhow::Result<()>;
+    async fn get(&self, key: &str) -> anyhow::Result<Vec<u8>>;
+    async fn delete(&self, key: &str) -> anyhow::Result<()>;
+}
+
+/// Stores files in a local directory.
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> anyhow::Result<PathBuf> {
+        let key = Path::new(key);
+
+        // keys are generated, but never let one point outside the root
+        if !key.components().all(|c| matches!(c, Component::Normal(_))) {
+            bail!("invalid storage key {}", key.display());
+        }
+
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl BlobStorage for LocalStorage {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> anyhow::Result<()> {
+        let path = self.path(key)?;
+
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir)
+                .await
+                .with_context(|| format!("cannot create {}", dir.display()))?;
+        }
+
+        // write aside and rename, so a failed upload leaves no partial file
+        let partial = path.with_extension("partial");
+        tokio::fs::write(&partial, bytes)
+            .await
+            .with_context(|| format!("cannot write {}", partial.display()))?;
+        tokio::fs::rename(&partial, &path).await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Vec<u8>> {
+        let path = self.path(key)?;
+
+        tokio::fs::read(&path)
+            .await
+            .with_context(|| format!("cannot read {}", path.display()))
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        let path = self.path(key)?;
+
+        match tokio::fs::remove_file(&path).await {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                Err(err).with_context(|| format!("cannot delete {}", path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Stores files in a bucket of S3 or a compatible service.
+#[cfg(feature = "s3")]
+pub struct S3Storage {
+    store: object_store::aws::AmazonS3,
+}
+
+#[cfg(feature = "s3")]
+impl S3Storage {
+    pub fn new(settings: &crate::config::AttachmentsSettings) -> anyhow::Result<Self> {
+        use object_store::aws::AmazonS3Builder;
+
+        let bucket = settings
+            .s3_bucket
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("ATTACHMENTS_S3_BUCKET must be set"))?;
+
+        let mut builder = AmazonS3Builder::from_env()
+            .with_bucket_name(bucket)
+            .with_region(&settings.s3_region);
+
+        if let Some(endpoint) = settings.s3_endpoint.as_deref() {
+            builder = builder
+                .with_endpoint(endpoint)
+                .with_allow_http(endpoint.starts_with("http://"))
+                .with_virtual_hosted_style_request(false);
+        }
+        if let Some(key_id) = settings.s3_access_key_id.as_deref() {
+            builder = builder.with_access_key_id(key_id);
+        }
+        if let Some(secret) = settings.s3_secret_access_key.as_deref() {
+            builder = builder.with_secret_access_key(secret);
+        }
+
+        Ok(Self {
+            store: builder.build()?,
+        })
+    }
+}
+
+#[cfg(feature = "s3")]
+#[async_trait]
+impl BlobStorage for S3Storage {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> anyhow::Result<()> {
+        use object_store::ObjectStore;
+
+        self.store.put(&key.into(), bytes.into()).await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Vec<u8>> {
+        use object_store::ObjectStore;
+
+        let bytes = self.store.get(&key.into()).await?.bytes().await?;
+
+        Ok(bytes.to_vec())
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        use object_store::ObjectStore;
+
+        match self.store.delete(&key.into()).await {
+            Err(object_store::Error::NotFound { .. }) => Ok(()),
+            result => Ok(result?),
+        }
+    }
+}
+
+/// Storage of the configured backend.
+pub fn storage(config: &Config) -> anyhow::Result<Arc<dyn BlobStorage>> {
+    match config.attachments.backend.as_str() {
+        #[cfg(feature = "s3")]
+        "s3" => Ok(Arc::new(S3Storage::new(&config.attachments)?)),
+        "local" => Ok(Arc::new(LocalStorage::new(&config.attachments.dir))),
+        other => bail!("unsupported attachments backend {other}"),
+    }
+}